@@ -1,5 +1,6 @@
 use codespan_reporting::files::{SimpleFile, SimpleFiles};
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 use crate::FileManager;
 
@@ -19,6 +20,10 @@ impl PathString {
     pub fn from_path(p: PathBuf) -> Self {
         PathString(p)
     }
+
+    pub fn as_path(&self) -> &Path {
+        &self.0
+    }
 }
 impl From<PathBuf> for PathString {
     fn from(pb: PathBuf) -> PathString {
@@ -34,7 +39,9 @@ impl From<&PathBuf> for PathString {
 pub struct FileMap(SimpleFiles<PathString, String>);
 
 // XXX: Note that we derive Default here due to ModuleOrigin requiring us to set a FileId
-#[derive(Default, Debug, Clone, PartialEq, Eq, Copy, Hash)]
+#[derive(
+    Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Copy, Hash, Serialize, Deserialize,
+)]
 pub struct FileId(usize);
 
 impl FileId {
@@ -48,12 +55,18 @@ impl FileId {
     }
 }
 
+#[derive(Clone, Copy)]
 pub struct File<'input>(&'input SimpleFile<PathString, String>);
 
 impl<'input> File<'input> {
     pub fn source(self) -> &'input str {
         self.0.source()
     }
+
+    /// The path which this file was read from.
+    pub fn path(self) -> &'input Path {
+        self.0.name().as_path()
+    }
 }
 
 impl FileMap {
@@ -64,6 +77,18 @@ impl FileMap {
     pub fn get_file(&self, file_id: FileId) -> Option<File> {
         self.0.get(file_id.0).map(File).ok()
     }
+
+    /// Searches for the file which was read from `path`.
+    pub fn find_file(&self, path: &Path) -> Option<FileId> {
+        let mut file_id = 0;
+        while let Ok(file) = self.0.get(file_id) {
+            if file.name().as_path() == path {
+                return Some(FileId(file_id));
+            }
+            file_id += 1;
+        }
+        None
+    }
 }
 
 impl Default for FileMap {
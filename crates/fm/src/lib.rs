@@ -33,12 +33,18 @@ pub struct FileManager {
     file_map: file_map::FileMap,
     id_to_path: HashMap<FileId, VirtualPath>,
     path_to_id: HashMap<VirtualPath, FileId>,
+
+    /// Contents to use in place of the contents on disk for the given paths.
+    overlays: HashMap<PathBuf, String>,
 }
 
 impl FileManager {
     // XXX: Maybe use a AsRef<Path> here, for API ergonomics
     pub fn add_file(&mut self, path_to_file: &Path, file_type: FileType) -> Option<FileId> {
-        let source = file_reader::read_file_to_string(path_to_file).ok()?;
+        let source = match self.overlays.get(path_to_file) {
+            Some(contents) => contents.clone(),
+            None => file_reader::read_file_to_string(path_to_file).ok()?,
+        };
 
         let file_id = self.file_map.add_file(path_to_file.to_path_buf().into(), source);
         let path_to_file = virtualize_path(path_to_file, file_type);
@@ -57,10 +63,24 @@ impl FileManager {
         assert!(old_value.is_none(), "ice: the same path was inserted into the file manager twice");
     }
 
-    pub fn fetch_file(&mut self, file_id: FileId) -> File {
+    /// Registers `contents` to be used as the source of the file at `path_to_file` instead of
+    /// reading it from disk. This allows editors to check files with unsaved changes.
+    ///
+    /// Overlays only apply to files which are added after the overlay is registered.
+    pub fn add_overlay(&mut self, path_to_file: PathBuf, contents: String) {
+        self.overlays.insert(path_to_file, contents);
+    }
+
+    pub fn fetch_file(&self, file_id: FileId) -> File {
         // Unwrap as we ensure that all file_id's map to a corresponding file in the file map
         self.file_map.get_file(file_id).unwrap()
     }
+
+    /// Returns the FileId of the file which was read from `path_to_file`, if it has been added.
+    pub fn find_file_id(&self, path_to_file: &Path) -> Option<FileId> {
+        self.file_map.find_file(path_to_file)
+    }
+
     fn path(&mut self, file_id: FileId) -> &Path {
         // Unwrap as we ensure that all file_ids are created by the file manager
         // So all file_ids will points to a corresponding path
@@ -140,6 +160,18 @@ mod tests {
         assert!(fm.path(file_id).ends_with("foo"));
     }
     #[test]
+    fn overlay_replaces_file_contents() {
+        let dir = tempdir().unwrap();
+        let file_path = dummy_file_path(&dir, "lib.nr");
+
+        let mut fm = FileManager::default();
+        fm.add_overlay(file_path.clone(), "fn main() {}".to_owned());
+
+        let file_id = fm.add_file(&file_path, FileType::Root).unwrap();
+        assert_eq!(fm.fetch_file(file_id).source(), "fn main() {}");
+        assert_eq!(fm.find_file_id(&file_path), Some(file_id));
+    }
+    #[test]
     fn path_resolve_sub_module() {
         let mut fm = FileManager::default();
 
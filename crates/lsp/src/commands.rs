@@ -0,0 +1,168 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use async_lsp::{ClientSocket, LanguageClient};
+use lsp_types::{
+    CodeLens, Command, ExecuteCommandParams, LogMessageParams, MessageType, ShowMessageParams, Url,
+};
+use noirc_driver::Driver;
+use serde_json::Value as JsonValue;
+
+use crate::{position::span_to_range, CommandRunner};
+
+const TEST_COMMAND: &str = "nargo.test";
+const EXECUTE_COMMAND: &str = "nargo.execute";
+const PROVE_COMMAND: &str = "nargo.prove";
+const INFO_COMMAND: &str = "nargo.info";
+
+/// The names of the commands which can be run through `workspace/executeCommand`.
+pub(crate) const COMMANDS: [&str; 4] = [TEST_COMMAND, EXECUTE_COMMAND, PROVE_COMMAND, INFO_COMMAND];
+
+/// A nargo command run on the package containing a document, as offered by a code lens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NargoCommand {
+    /// Runs the test function with the given name.
+    Test { test_name: String },
+    /// Executes the package's `main` function using the inputs in `Prover.toml`.
+    Execute,
+    /// Creates a proof of the package's `main` function using the inputs in `Prover.toml`.
+    Prove,
+    /// Reports the size of the circuit generated for the package's `main` function.
+    Info,
+}
+
+impl NargoCommand {
+    /// Parses the command and the document it is to be run for from the parameters of a
+    /// `workspace/executeCommand` request.
+    pub(crate) fn from_params(params: &ExecuteCommandParams) -> Option<(PathBuf, NargoCommand)> {
+        let mut arguments = params.arguments.iter().map(JsonValue::as_str);
+        let file_path = Url::parse(arguments.next()??).ok()?.to_file_path().ok()?;
+
+        let command = match params.command.as_str() {
+            TEST_COMMAND => NargoCommand::Test { test_name: arguments.next()??.to_owned() },
+            EXECUTE_COMMAND => NargoCommand::Execute,
+            PROVE_COMMAND => NargoCommand::Prove,
+            INFO_COMMAND => NargoCommand::Info,
+            _ => return None,
+        };
+
+        Some((file_path, command))
+    }
+
+    fn to_lsp_command(&self, uri: &Url) -> Command {
+        let (title, command, mut arguments) = match self {
+            NargoCommand::Test { test_name } => {
+                ("Run test", TEST_COMMAND, vec![JsonValue::from(test_name.as_str())])
+            }
+            NargoCommand::Execute => ("Execute", EXECUTE_COMMAND, Vec::new()),
+            NargoCommand::Prove => ("Prove", PROVE_COMMAND, Vec::new()),
+            NargoCommand::Info => ("Info", INFO_COMMAND, Vec::new()),
+        };
+        arguments.insert(0, JsonValue::from(uri.as_str()));
+
+        Command { title: title.to_owned(), command: command.to_owned(), arguments: Some(arguments) }
+    }
+}
+
+impl fmt::Display for NargoCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NargoCommand::Test { test_name } => write!(f, "test {test_name}"),
+            NargoCommand::Execute => write!(f, "execute"),
+            NargoCommand::Prove => write!(f, "prove"),
+            NargoCommand::Info => write!(f, "info"),
+        }
+    }
+}
+
+/// Returns a code lens to run each test function declared in a document, along with lenses to
+/// execute, prove and inspect the `main` function of a binary package.
+///
+/// The driver's crate must already have been checked so that its functions are known.
+pub(crate) fn code_lenses(driver: &Driver, file_path: &Path) -> Option<Vec<CodeLens>> {
+    let file_id = driver.file_manager().find_file_id(file_path)?;
+    let source = driver.file_manager().fetch_file(file_id).source();
+    let uri = Url::from_file_path(file_path).ok()?;
+    let interner = &driver.context().def_interner;
+
+    let mut lenses = Vec::new();
+    let mut add_lens = |location: noirc_errors::Location, command: NargoCommand| {
+        if location.file == file_id {
+            lenses.push(CodeLens {
+                range: span_to_range(source, location.span),
+                command: Some(command.to_lsp_command(&uri)),
+                data: None,
+            });
+        }
+    };
+
+    let test_functions =
+        driver.context().def_map(driver.local_crate())?.get_all_test_functions(interner);
+    for test_function in test_functions {
+        let test_name = interner.function_name(&test_function.id).to_owned();
+        add_lens(test_function.location, NargoCommand::Test { test_name });
+    }
+
+    if let Some(main) = driver.main_function() {
+        let location = interner.function_meta(&main).name.location;
+        for command in [NargoCommand::Execute, NargoCommand::Prove, NargoCommand::Info] {
+            add_lens(location, command);
+        }
+    }
+
+    Some(lenses)
+}
+
+/// Runs a command, logging its output to the client as it is produced and showing the user
+/// whether it succeeded once it has finished.
+pub(crate) fn run_command(
+    mut client: ClientSocket,
+    runner: &CommandRunner,
+    file_path: &Path,
+    command: NargoCommand,
+) {
+    // The client may have already disconnected, in which case there is nobody to notify
+    let mut log = |message: String| {
+        let _ = client.log_message(LogMessageParams { typ: MessageType::LOG, message });
+    };
+    let result = runner(file_path, &command, &mut log);
+
+    let (typ, message) = match result {
+        Ok(()) => (MessageType::INFO, format!("nargo {command} succeeded")),
+        Err(error) => (MessageType::ERROR, format!("nargo {command} failed: {error}")),
+    };
+    let _ = client.show_message(ShowMessageParams { typ, message });
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{ExecuteCommandParams, Url};
+
+    use super::NargoCommand;
+
+    #[test]
+    fn round_trips_lsp_commands() {
+        let uri = Url::parse("file:///package/src/main.nr").unwrap();
+        let commands = [
+            NargoCommand::Test { test_name: "test_main".to_owned() },
+            NargoCommand::Execute,
+            NargoCommand::Prove,
+            NargoCommand::Info,
+        ];
+
+        for command in commands {
+            let lsp_command = command.to_lsp_command(&uri);
+            let params = ExecuteCommandParams {
+                command: lsp_command.command,
+                arguments: lsp_command.arguments.unwrap(),
+                work_done_progress_params: Default::default(),
+            };
+
+            let (file_path, parsed) = NargoCommand::from_params(&params).unwrap();
+            assert_eq!(Url::from_file_path(file_path).unwrap(), uri);
+            assert_eq!(parsed, command);
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use std::{collections::HashMap, path::Path};
+
+use fm::FileId;
+use iter_extended::vecmap;
+use lsp_types::{CompletionItem, CompletionItemKind, Position};
+use noirc_driver::Driver;
+use noirc_frontend::{
+    hir::{
+        def_map::{LocalModuleId, ModuleData, ModuleDefId, ModuleId},
+        Context,
+    },
+    hir_def::{
+        expr::{HirArrayLiteral, HirExpression, HirIdent, HirLiteral},
+        function::Param,
+        stmt::{HirConstrainStatement, HirPattern, HirStatement},
+    },
+    node_interner::{ExprId, FuncId, NodeInterner},
+    Ident, Type,
+};
+
+use crate::position::position_to_offset;
+
+/// What is being completed, as determined from the text before the cursor.
+#[derive(Debug, PartialEq, Eq)]
+enum CompletionContext {
+    /// An item in the module named by the given path segments, e.g. `dep::std::hash::`.
+    Path(Vec<String>),
+
+    /// A field or method of the expression ending before the `.` at the given byte offset.
+    Member { dot_offset: usize },
+
+    /// A local variable or an item in the scope of the current module.
+    Name,
+}
+
+/// A completion request for a position within a document.
+///
+/// The name being completed often leaves the document unparseable, such as `foo.` or `std::`,
+/// so the request holds a copy of the document with that name removed for checking instead.
+pub(crate) struct CompletionRequest {
+    context: CompletionContext,
+    offset: usize,
+    source: String,
+}
+
+impl CompletionRequest {
+    pub(crate) fn new(source: &str, position: Position) -> Self {
+        let offset = position_to_offset(source, position);
+        let context = completion_context(source, offset);
+
+        let source = match &context {
+            CompletionContext::Path(_) | CompletionContext::Member { .. } => {
+                let name_start = source[..offset].trim_end_matches(is_identifier_char).len();
+                let separator_start = match &context {
+                    CompletionContext::Member { dot_offset } => *dot_offset,
+                    _ => name_start - "::".len(),
+                };
+
+                // Finish off the statement if the cursor is at the end of a line
+                let rest_of_line = source[offset..].split('\n').next().unwrap_or_default();
+                let terminator = if rest_of_line.trim().is_empty() { ";" } else { "" };
+
+                format!("{}{terminator}{}", &source[..separator_start], &source[offset..])
+            }
+            CompletionContext::Name => source.to_owned(),
+        };
+
+        CompletionRequest { context, offset, source }
+    }
+
+    /// The contents of the document to check the package with before completing.
+    pub(crate) fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the completions at the requested position.
+    ///
+    /// The driver's crate must already have been checked using [`CompletionRequest::source`]
+    /// as the contents of the document.
+    pub(crate) fn complete(&self, driver: &Driver, file_path: &Path) -> Vec<CompletionItem> {
+        self.try_complete(driver, file_path).unwrap_or_default()
+    }
+
+    fn try_complete(&self, driver: &Driver, file_path: &Path) -> Option<Vec<CompletionItem>> {
+        let context = driver.context();
+        let interner = &context.def_interner;
+        let file_id = driver.file_manager().find_file_id(file_path)?;
+        let current_module = module_of_file(context, file_id)?;
+
+        match &self.context {
+            CompletionContext::Path(segments) => {
+                path_completions(context, current_module, segments)
+            }
+            CompletionContext::Member { dot_offset } => {
+                let receiver_end = self.source[..*dot_offset].trim_end().len();
+                let receiver =
+                    interner.expression_at(file_id, receiver_end.checked_sub(1)? as u32)?;
+                Some(member_completions(interner, &interner.id_type(receiver)))
+            }
+            CompletionContext::Name => {
+                let offset = self.offset as u32;
+
+                let mut completions = match interner.function_at(file_id, offset) {
+                    Some(func_id) => local_completions(interner, func_id, offset),
+                    None => Vec::new(),
+                };
+                completions
+                    .extend(scope_completions(interner, module_data(context, current_module)?));
+                Some(completions)
+            }
+        }
+    }
+}
+
+fn completion_context(source: &str, offset: usize) -> CompletionContext {
+    let before_cursor = &source[..offset];
+    let before_name = before_cursor.trim_end_matches(is_identifier_char);
+
+    if let Some(path) = before_name.strip_suffix("::") {
+        let path_start = path.trim_end_matches(|c| is_identifier_char(c) || c == ':').len();
+        let segments = path[path_start..].split("::").map(str::to_owned).collect();
+        CompletionContext::Path(segments)
+    } else if before_name.ends_with('.') {
+        CompletionContext::Member { dot_offset: before_name.len() - 1 }
+    } else {
+        CompletionContext::Name
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Returns the module declared by the given file. When a file contains inline modules, this is
+/// the outermost module in the file.
+fn module_of_file(context: &Context, file_id: FileId) -> Option<ModuleId> {
+    context.crates().find_map(|krate| {
+        let def_map = context.def_map(krate)?;
+
+        // Parent modules are always collected before their children
+        def_map
+            .modules()
+            .iter()
+            .find(|(_, module)| module.origin.file_id() == file_id)
+            .map(|(index, _)| ModuleId { krate, local_id: LocalModuleId(index) })
+    })
+}
+
+fn module_data(context: &Context, module_id: ModuleId) -> Option<&ModuleData> {
+    context.def_map(module_id.krate)?.modules().get(module_id.local_id.0)
+}
+
+fn path_completions(
+    context: &Context,
+    current_module: ModuleId,
+    segments: &[String],
+) -> Option<Vec<CompletionItem>> {
+    let current_def_map = context.def_map(current_module.krate)?;
+
+    let (mut module_id, rest) = match segments.split_first() {
+        Some((first, rest)) if first == "crate" => {
+            (ModuleId { krate: current_module.krate, local_id: current_def_map.root() }, rest)
+        }
+        Some((first, rest)) if first == "dep" => match rest.split_first() {
+            Some((dependency, rest)) => (*current_def_map.extern_prelude().get(dependency)?, rest),
+            None => {
+                return Some(vecmap_sorted(current_def_map.extern_prelude().keys(), |name| {
+                    completion_item(name, CompletionItemKind::MODULE, None)
+                }));
+            }
+        },
+        _ => (current_module, segments),
+    };
+
+    for segment in rest {
+        let module = module_data(context, module_id)?;
+        module_id = match module.find_name(&Ident::from(segment.clone())).types {
+            Some((ModuleDefId::ModuleId(id), _)) => id,
+            // The methods of a struct are declared in a module of the same name
+            Some((ModuleDefId::TypeId(id), _)) => id.0,
+            _ => return None,
+        };
+    }
+
+    Some(scope_completions(&context.def_interner, module_data(context, module_id)?))
+}
+
+/// Returns a completion for each item visible in the given module.
+fn scope_completions(interner: &NodeInterner, module: &ModuleData) -> Vec<CompletionItem> {
+    let scope = module.scope();
+    let items = scope.types().iter().chain(scope.values());
+
+    vecmap_sorted(items, |(name, (id, _))| {
+        let (kind, detail) = match id {
+            ModuleDefId::ModuleId(_) => (CompletionItemKind::MODULE, None),
+            ModuleDefId::TypeId(_) => (CompletionItemKind::STRUCT, None),
+            ModuleDefId::FunctionId(func_id) => {
+                let typ = interner.function_meta(func_id).typ;
+                (CompletionItemKind::FUNCTION, Some(typ.to_string()))
+            }
+            ModuleDefId::GlobalId(stmt_id) => {
+                let ident = interner.let_statement(stmt_id).ident();
+                (CompletionItemKind::CONSTANT, Some(interner.id_type(ident.id).to_string()))
+            }
+        };
+        completion_item(&name.0.contents, kind, detail)
+    })
+}
+
+/// Returns a completion for each field and method of the given type.
+fn member_completions(interner: &NodeInterner, typ: &Type) -> Vec<CompletionItem> {
+    let mut completions = Vec::new();
+
+    if let Type::Struct(struct_type, generic_args) = typ.follow_bindings() {
+        let fields = struct_type.borrow().get_fields(&generic_args);
+        completions.extend(fields.into_iter().map(|(name, typ)| {
+            completion_item(&name, CompletionItemKind::FIELD, Some(typ.to_string()))
+        }));
+    }
+
+    for (name, method) in interner.methods_of_type(typ) {
+        // The object a method is called on is passed as its first parameter
+        let meta = interner.function_meta(&method);
+        if !meta.parameters.is_empty() {
+            let detail = Some(meta.typ.to_string());
+            completions.push(completion_item(name, CompletionItemKind::METHOD, detail));
+        }
+    }
+
+    completions.sort_by(|a, b| a.label.cmp(&b.label));
+    completions
+}
+
+/// Returns a completion for each local variable in scope at the given offset into a function.
+fn local_completions(interner: &NodeInterner, func_id: FuncId, offset: u32) -> Vec<CompletionItem> {
+    let mut locals = Vec::new();
+    for Param(pattern, _, _) in interner.function_meta(&func_id).parameters.iter() {
+        pattern_identifiers(pattern, &mut locals);
+    }
+    collect_locals(interner, *interner.function(&func_id).as_expr(), offset, &mut locals);
+
+    // Later declarations shadow any earlier ones of the same name
+    let mut names = HashMap::new();
+    for local in locals.into_iter().filter(|local| local.location.span.start() < offset) {
+        names.insert(interner.definition_name(local.id).to_owned(), local.id);
+    }
+
+    vecmap_sorted(names, |(name, id)| {
+        let detail = Some(interner.id_type(id).to_string());
+        completion_item(&name, CompletionItemKind::VARIABLE, detail)
+    })
+}
+
+/// Collects the variables declared within the given expression which are in scope at `offset`,
+/// following the same scoping rules as the resolver. Variables declared after the offset are
+/// included and must be filtered out afterward.
+fn collect_locals(
+    interner: &NodeInterner,
+    expr_id: ExprId,
+    offset: u32,
+    locals: &mut Vec<HirIdent>,
+) {
+    let contains_offset = |expr_id: &ExprId| {
+        // Identifiers never declare variables, and those created when desugaring method calls
+        // have no location
+        !matches!(interner.expression(expr_id), HirExpression::Ident(_)) && {
+            let span = interner.expr_span(expr_id);
+            span.start() <= offset && offset <= span.end()
+        }
+    };
+
+    let children = match interner.expression(&expr_id) {
+        HirExpression::Block(block) => {
+            let mut children = Vec::new();
+            for statement in block.statements() {
+                match interner.statement(statement) {
+                    HirStatement::Let(let_statement) => {
+                        pattern_identifiers(&let_statement.pattern, locals);
+                        children.push(let_statement.expression);
+                    }
+                    HirStatement::Assign(assign) => children.push(assign.expression),
+                    HirStatement::Constrain(HirConstrainStatement(expr_id, ..))
+                    | HirStatement::Expression(expr_id)
+                    | HirStatement::Semi(expr_id) => children.push(expr_id),
+                    HirStatement::Error => (),
+                }
+            }
+            children
+        }
+        HirExpression::For(for_expr) => {
+            if contains_offset(&for_expr.block) {
+                locals.push(for_expr.identifier);
+            }
+            vec![for_expr.start_range, for_expr.end_range, for_expr.block]
+        }
+        HirExpression::Lambda(lambda) => {
+            if contains_offset(&lambda.body) {
+                for (pattern, _) in &lambda.parameters {
+                    pattern_identifiers(pattern, locals);
+                }
+            }
+            vec![lambda.body]
+        }
+        HirExpression::Literal(HirLiteral::Array(HirArrayLiteral::Standard(elements))) => elements,
+        HirExpression::Literal(HirLiteral::Array(HirArrayLiteral::Repeated {
+            repeated_element,
+            ..
+        })) => vec![repeated_element],
+        HirExpression::Prefix(prefix) => vec![prefix.rhs],
+        HirExpression::Infix(infix) => vec![infix.lhs, infix.rhs],
+        HirExpression::Index(index) => vec![index.collection, index.index],
+        HirExpression::Constructor(constructor) => vecmap(constructor.fields, |field| field.1),
+        HirExpression::MemberAccess(access) => vec![access.lhs],
+        HirExpression::Call(call) => {
+            let mut children = call.arguments;
+            children.push(call.func);
+            children
+        }
+        HirExpression::MethodCall(call) => {
+            let mut children = call.arguments;
+            children.push(call.object);
+            children
+        }
+        HirExpression::Cast(cast) => vec![cast.lhs],
+        HirExpression::If(if_expr) => {
+            let mut children = vec![if_expr.condition, if_expr.consequence];
+            children.extend(if_expr.alternative);
+            children
+        }
+        HirExpression::Tuple(elements) => elements,
+        HirExpression::Ident(_) | HirExpression::Literal(_) | HirExpression::Error => Vec::new(),
+    };
+
+    // Only the expression the offset is within can have any more variables in scope
+    if let Some(child) = children.into_iter().find(contains_offset) {
+        collect_locals(interner, child, offset, locals);
+    }
+}
+
+fn pattern_identifiers(pattern: &HirPattern, identifiers: &mut Vec<HirIdent>) {
+    match pattern {
+        HirPattern::Identifier(ident) => identifiers.push(*ident),
+        HirPattern::Mutable(pattern, _) => pattern_identifiers(pattern, identifiers),
+        HirPattern::Tuple(patterns, _) => {
+            for pattern in patterns {
+                pattern_identifiers(pattern, identifiers);
+            }
+        }
+        HirPattern::Struct(_, fields, _) => {
+            for (_, pattern) in fields {
+                pattern_identifiers(pattern, identifiers);
+            }
+        }
+    }
+}
+
+fn completion_item(
+    label: &str,
+    kind: CompletionItemKind,
+    detail: Option<String>,
+) -> CompletionItem {
+    CompletionItem {
+        label: label.to_owned(),
+        kind: Some(kind),
+        detail,
+        ..CompletionItem::default()
+    }
+}
+
+/// Maps each item to a completion, sorted by label so completions are returned in a stable order.
+fn vecmap_sorted<T>(
+    items: impl IntoIterator<Item = T>,
+    f: impl FnMut(T) -> CompletionItem,
+) -> Vec<CompletionItem> {
+    let mut completions: Vec<_> = items.into_iter().map(f).collect();
+    completions.sort_by(|a, b| a.label.cmp(&b.label));
+    completions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{completion_context, CompletionContext};
+
+    #[test]
+    fn determines_completion_context() {
+        let context = |source: &str| completion_context(source, source.len());
+
+        assert_eq!(
+            context("use dep::std::ha"),
+            CompletionContext::Path(vec!["dep".to_owned(), "std".to_owned()])
+        );
+        assert_eq!(
+            context("    let x = crate::"),
+            CompletionContext::Path(vec!["crate".to_owned()])
+        );
+        assert_eq!(context("    foo.bar.ba"), CompletionContext::Member { dot_offset: 11 });
+        assert_eq!(context("    foo(x)."), CompletionContext::Member { dot_offset: 10 });
+        assert_eq!(context("    let x = fo"), CompletionContext::Name);
+    }
+}
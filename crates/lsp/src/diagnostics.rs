@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use lsp_types::{Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Url};
+use noirc_driver::Driver;
+use noirc_errors::{CustomDiagnostic, DiagnosticKind, FileDiagnostic};
+
+use crate::position::span_to_range;
+
+/// Runs the lexing, parsing, name resolution, and type checking passes over the driver's crate
+/// and converts any errors and warnings found into LSP diagnostics, grouped by document.
+pub(crate) fn check_crate(driver: &mut Driver) -> HashMap<Url, Vec<Diagnostic>> {
+    let file_diagnostics = match driver.check_crate(false) {
+        Ok(warnings) => warnings,
+        Err(errors_and_warnings) => errors_and_warnings,
+    };
+
+    let file_manager = driver.file_manager();
+    let mut diagnostics: HashMap<Url, Vec<Diagnostic>> = HashMap::new();
+
+    for FileDiagnostic { file_id, diagnostic, .. } in file_diagnostics {
+        let file = file_manager.fetch_file(file_id);
+
+        // Files from the standard library are embedded into the compiler so have no location on disk.
+        let uri = match Url::from_file_path(file.path()) {
+            Ok(uri) => uri,
+            Err(()) => continue,
+        };
+
+        let diagnostic = convert_diagnostic(file.source(), &uri, diagnostic);
+        diagnostics.entry(uri).or_default().push(diagnostic);
+    }
+
+    diagnostics
+}
+
+/// Converts a compiler diagnostic into an LSP diagnostic.
+///
+/// The first label of a diagnostic marks its primary location, the same as when it is reported
+/// to the terminal. Any other labels and notes are attached as related information.
+fn convert_diagnostic(source: &str, uri: &Url, diagnostic: CustomDiagnostic) -> Diagnostic {
+    let CustomDiagnostic { mut message, secondaries, notes, kind } = diagnostic;
+
+    let severity = match kind {
+        DiagnosticKind::Error => DiagnosticSeverity::ERROR,
+        DiagnosticKind::Warning => DiagnosticSeverity::WARNING,
+    };
+
+    let mut labels = secondaries.into_iter();
+    let range = match labels.next() {
+        Some(primary) => {
+            if !primary.message.is_empty() && primary.message != message {
+                message.push('\n');
+                message.push_str(&primary.message);
+            }
+            span_to_range(source, primary.span)
+        }
+        None => Default::default(),
+    };
+
+    let related_location = |range| Location { uri: uri.clone(), range };
+    let mut related_information: Vec<_> = labels
+        .map(|label| DiagnosticRelatedInformation {
+            location: related_location(span_to_range(source, label.span)),
+            message: label.message,
+        })
+        .collect();
+    related_information.extend(notes.into_iter().map(|note| DiagnosticRelatedInformation {
+        location: related_location(range),
+        message: format!("note: {note}"),
+    }));
+
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        source: Some("nargo".to_owned()),
+        message,
+        related_information: (!related_information.is_empty()).then_some(related_information),
+        ..Diagnostic::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{DiagnosticSeverity, Position, Range, Url};
+    use noirc_errors::{CustomDiagnostic, Span};
+
+    use super::convert_diagnostic;
+
+    #[test]
+    fn converts_labels_and_notes() {
+        let source = "fn main() {\n    let x = 1;\n}\n";
+        let uri = Url::parse("file:///project/src/main.nr").unwrap();
+
+        let mut diagnostic = CustomDiagnostic::simple_warning(
+            "unused variable x".to_owned(),
+            "unused variable".to_owned(),
+            // Spans produced by the lexer end on the last character of the token
+            Span::exclusive(20, 20),
+        );
+        diagnostic.add_secondary("declared here".to_owned(), Span::exclusive(0, 1));
+        diagnostic.add_note("prefix the name with an underscore".to_owned());
+
+        let diagnostic = convert_diagnostic(source, &uri, diagnostic);
+
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(diagnostic.message, "unused variable x\nunused variable");
+        assert_eq!(
+            diagnostic.range,
+            Range {
+                start: Position { line: 1, character: 8 },
+                end: Position { line: 1, character: 9 }
+            }
+        );
+
+        let related_information = diagnostic.related_information.unwrap();
+        assert_eq!(related_information.len(), 2);
+        assert_eq!(related_information[0].message, "declared here");
+        assert_eq!(related_information[1].message, "note: prefix the name with an underscore");
+        assert_eq!(related_information[1].location.range, diagnostic.range);
+    }
+}
@@ -0,0 +1,204 @@
+use std::path::Path;
+
+use iter_extended::vecmap;
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position};
+use noirc_abi::{AbiDistinctness, AbiVisibility};
+use noirc_driver::Driver;
+use noirc_frontend::{
+    node_interner::{DefinitionKind, FuncId, NodeInterner, ReferenceId},
+    ContractFunctionType, Type,
+};
+
+use crate::position::{position_to_offset, span_to_range};
+
+/// Describes the item at the given position in a document.
+///
+/// Names of functions show the function's full signature, while the names of other
+/// declarations and any other expression show the type inferred for them. Declarations are
+/// followed by their doc comments, if they have any.
+///
+/// The driver's crate must already have been checked so that its types are inferred.
+pub(crate) fn hover(driver: &Driver, file_path: &Path, position: Position) -> Option<Hover> {
+    let file_id = driver.file_manager().find_file_id(file_path)?;
+    let source = driver.file_manager().fetch_file(file_id).source();
+    let offset = position_to_offset(source, position) as u32;
+    let interner = &driver.context().def_interner;
+
+    if let Some((location, id)) = interner.reference_location_at(file_id, offset) {
+        if let Some(mut value) = describe_reference(interner, id) {
+            if let Some(doc_comments) = interner.doc_comments(id) {
+                value.push_str("\n\n");
+                value.push_str(&doc_comments_markdown(doc_comments));
+            }
+            let range = span_to_range(source, location.span);
+            return Some(Hover { contents: markdown(value), range: Some(range) });
+        }
+    }
+
+    let expr_id = interner.expression_at(file_id, offset)?;
+    let typ = interner.id_type(expr_id);
+    let range = span_to_range(source, interner.expr_span(&expr_id));
+    Some(Hover { contents: markdown(code_block(&typ.to_string())), range: Some(range) })
+}
+
+/// Returns the Markdown describing a declaration.
+fn describe_reference(interner: &NodeInterner, id: ReferenceId) -> Option<String> {
+    match id {
+        ReferenceId::Definition(definition_id) => {
+            let definition = interner.definition(definition_id);
+            let typ = interner.id_type(definition_id);
+
+            Some(match &definition.kind {
+                DefinitionKind::Function(func_id) => describe_function(interner, *func_id),
+                DefinitionKind::Global(_) => {
+                    code_block(&format!("global {}: {typ}", definition.name))
+                }
+                DefinitionKind::Local(_) if definition.mutable => {
+                    code_block(&format!("mut {}: {typ}", definition.name))
+                }
+                DefinitionKind::Local(_) => code_block(&format!("{}: {typ}", definition.name)),
+                DefinitionKind::GenericType(_) => code_block(&definition.name),
+            })
+        }
+        ReferenceId::Struct(struct_id) => {
+            let struct_type = interner.get_struct(struct_id);
+            let struct_type = struct_type.borrow();
+            Some(code_block(&format!("struct {}", struct_type.name)))
+        }
+        ReferenceId::StructMember(struct_id, index) => {
+            let struct_type = interner.get_struct(struct_id);
+            let struct_type = struct_type.borrow();
+
+            // Fields are shown as declared, without any generics being substituted
+            let generics =
+                vecmap(&struct_type.generics, |(_, var)| Type::TypeVariable(var.clone()));
+            let (name, typ) = struct_type.get_fields(&generics).into_iter().nth(index)?;
+            Some(code_block(&format!("{}.{name}: {typ}", struct_type.name)))
+        }
+        ReferenceId::Module(_) => None,
+    }
+}
+
+fn describe_function(interner: &NodeInterner, func_id: FuncId) -> String {
+    let meta = interner.function_meta(&func_id);
+
+    let mut signature = String::new();
+    if let Some(attribute) = &meta.attributes {
+        signature.push_str(&format!("{attribute}\n"));
+    }
+    if meta.is_unconstrained {
+        signature.push_str("unconstrained ");
+    }
+    if meta.contract_function_type == Some(ContractFunctionType::Open) {
+        signature.push_str("open ");
+    }
+
+    signature.push_str("fn ");
+    signature.push_str(interner.function_name(&func_id));
+
+    if !meta.generic_names.is_empty() {
+        signature.push_str(&format!("<{}>", meta.generic_names.join(", ")));
+    }
+
+    let parameters = vecmap(meta.parameters.iter(), |parameter| {
+        let pattern = interner.pattern_to_string(&parameter.0);
+        match parameter.2 {
+            AbiVisibility::Public => format!("{pattern}: pub {}", parameter.1),
+            AbiVisibility::Private => format!("{pattern}: {}", parameter.1),
+        }
+    });
+    signature.push_str(&format!("({})", parameters.join(", ")));
+
+    let return_type = meta.return_type();
+    if return_type != &Type::Unit {
+        signature.push_str(" -> ");
+        if meta.return_distinctness == AbiDistinctness::Distinct {
+            signature.push_str("distinct ");
+        }
+        if meta.return_visibility == AbiVisibility::Public {
+            signature.push_str("pub ");
+        }
+        signature.push_str(&return_type.to_string());
+    }
+
+    let mut value = code_block(&signature);
+    match meta.contract_function_type {
+        Some(ContractFunctionType::Secret) => value.push_str("\n\nSecret contract function"),
+        Some(ContractFunctionType::Open) => value.push_str("\n\nOpen contract function"),
+        None => (),
+    }
+    value
+}
+
+/// Joins the lines of doc comments, which are Markdown already, removing the space which
+/// usually follows the `///`.
+fn doc_comments_markdown(doc_comments: &[String]) -> String {
+    let lines = vecmap(doc_comments, |line| line.strip_prefix(' ').unwrap_or(line));
+    lines.join("\n")
+}
+
+fn markdown(value: String) -> HoverContents {
+    HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value })
+}
+
+fn code_block(code: &str) -> String {
+    format!("```noir\n{code}\n```")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use acvm::Language;
+    use lsp_types::{HoverContents, MarkupContent, MarkupKind, Position, Range};
+    use noirc_driver::Driver;
+    use noirc_frontend::graph::CrateType;
+
+    use super::hover;
+
+    const SOURCE: &str = "/// Adds two numbers.
+fn add(x: Field, y: Field) -> Field {
+    x + y
+}
+
+fn main(a: Field) {
+    let sum = add(a, 1);
+    assert(sum == 2);
+}
+";
+
+    fn range(line: u32, start: u32, end: u32) -> Range {
+        Range { start: Position { line, character: start }, end: Position { line, character: end } }
+    }
+
+    #[test]
+    fn describes_names_and_expressions() {
+        let file_path = Path::new("/hover/src/main.nr");
+        let mut driver = Driver::new(&Language::R1CS, Box::new(|_| true));
+        driver.add_file_overlay(file_path.to_path_buf(), SOURCE.to_owned());
+        driver.create_local_crate(file_path, CrateType::Binary);
+        driver.check_crate(false).unwrap();
+
+        let hover_at = |line, character| {
+            let hover = hover(&driver, file_path, Position { line, character }).unwrap();
+            match hover.contents {
+                HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value }) => {
+                    (value, hover.range)
+                }
+                contents => panic!("expected Markdown, got {contents:?}"),
+            }
+        };
+
+        // A call shows the function's signature followed by its doc comments
+        assert_eq!(
+            hover_at(6, 15),
+            (
+                "```noir\nfn add(x: Field, y: Field) -> Field\n```\n\nAdds two numbers.".to_owned(),
+                Some(range(6, 14, 17))
+            )
+        );
+        assert_eq!(hover_at(6, 18), ("```noir\na: Field\n```".to_owned(), Some(range(6, 18, 19))));
+        // Any other expression shows its type, covering the whole expression
+        assert_eq!(hover_at(2, 6), ("```noir\nField\n```".to_owned(), Some(range(2, 4, 9))));
+    }
+}
@@ -0,0 +1,227 @@
+use std::{collections::HashMap, path::Path};
+
+use fm::FileId;
+use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, Range};
+use noirc_driver::{CompileOptions, Driver};
+use noirc_frontend::{
+    hir_def::{
+        expr::{HirArrayLiteral, HirExpression, HirLiteral},
+        stmt::{HirConstrainStatement, HirLValue, HirPattern, HirStatement},
+    },
+    node_interner::{DefinitionKind, ExprId, FuncId, StmtId},
+    FunctionKind, Type,
+};
+
+use crate::position::offset_to_position;
+
+/// Returns the hints to show within the given range of a document: the inferred type of each
+/// variable declared by a `let` statement without a type annotation and, if `opcode_counts` is
+/// set, the number of ACIR opcodes each called function compiles to.
+///
+/// The driver's crate must already have been checked so that its types are inferred. Opcode
+/// counts should only be requested if it was checked without errors.
+pub(crate) fn inlay_hints(
+    driver: &Driver,
+    file_path: &Path,
+    range: Range,
+    opcode_counts: bool,
+) -> Option<Vec<InlayHint>> {
+    let file_id = driver.file_manager().find_file_id(file_path)?;
+    let mut collector = HintCollector {
+        driver,
+        file_id,
+        opcode_counts: opcode_counts.then(HashMap::new),
+        hints: Vec::new(),
+    };
+
+    let interner = &driver.context().def_interner;
+    for func_id in interner.functions_in_file(file_id) {
+        collector.visit_expression(*interner.function(&func_id).as_expr());
+    }
+
+    let mut hints = collector.hints;
+    hints.retain(|hint| range.start <= hint.position && hint.position <= range.end);
+    hints.sort_by_key(|hint| hint.position);
+    Some(hints)
+}
+
+struct HintCollector<'a> {
+    driver: &'a Driver,
+    file_id: FileId,
+
+    /// The number of opcodes each function called compiles to, if opcode counts were requested.
+    /// Functions which can't be compiled on their own have no count.
+    opcode_counts: Option<HashMap<FuncId, Option<usize>>>,
+
+    hints: Vec<InlayHint>,
+}
+
+impl HintCollector<'_> {
+    fn visit_statement(&mut self, stmt_id: StmtId) {
+        let driver = self.driver;
+        let interner = &driver.context().def_interner;
+
+        match interner.statement(&stmt_id) {
+            HirStatement::Let(let_statement) => {
+                // Variables declared without a type annotation are given a fresh type variable
+                if let Type::TypeVariable(_) = let_statement.r#type {
+                    self.add_pattern_type_hints(&let_statement.pattern);
+                }
+                self.visit_expression(let_statement.expression);
+            }
+            HirStatement::Assign(assign) => {
+                self.visit_lvalue(&assign.lvalue);
+                self.visit_expression(assign.expression);
+            }
+            HirStatement::Constrain(HirConstrainStatement(expr_id, ..))
+            | HirStatement::Expression(expr_id)
+            | HirStatement::Semi(expr_id) => self.visit_expression(expr_id),
+            HirStatement::Error => (),
+        }
+    }
+
+    fn visit_lvalue(&mut self, lvalue: &HirLValue) {
+        match lvalue {
+            HirLValue::Ident(..) => (),
+            HirLValue::MemberAccess { object, .. } => self.visit_lvalue(object),
+            HirLValue::Index { array, index, .. } => {
+                self.visit_lvalue(array);
+                self.visit_expression(*index);
+            }
+        }
+    }
+
+    fn visit_expression(&mut self, expr_id: ExprId) {
+        let driver = self.driver;
+        let interner = &driver.context().def_interner;
+
+        let children = match interner.expression(&expr_id) {
+            HirExpression::Block(block) => {
+                for statement in block.statements() {
+                    self.visit_statement(*statement);
+                }
+                Vec::new()
+            }
+            HirExpression::Call(call) => {
+                if let HirExpression::Ident(ident) = interner.expression(&call.func) {
+                    if let DefinitionKind::Function(func_id) = interner.definition(ident.id).kind {
+                        self.add_opcode_count_hint(expr_id, func_id);
+                    }
+                }
+                let mut children = call.arguments;
+                children.push(call.func);
+                children
+            }
+            HirExpression::MethodCall(call) => {
+                let mut children = call.arguments;
+                children.push(call.object);
+                children
+            }
+            HirExpression::Literal(HirLiteral::Array(HirArrayLiteral::Standard(elements))) => {
+                elements
+            }
+            HirExpression::Literal(HirLiteral::Array(HirArrayLiteral::Repeated {
+                repeated_element,
+                ..
+            })) => vec![repeated_element],
+            HirExpression::Prefix(prefix) => vec![prefix.rhs],
+            HirExpression::Infix(infix) => vec![infix.lhs, infix.rhs],
+            HirExpression::Index(index) => vec![index.collection, index.index],
+            HirExpression::Constructor(constructor) => {
+                constructor.fields.into_iter().map(|(_, expr_id)| expr_id).collect()
+            }
+            HirExpression::MemberAccess(access) => vec![access.lhs],
+            HirExpression::Cast(cast) => vec![cast.lhs],
+            HirExpression::For(for_expr) => {
+                vec![for_expr.start_range, for_expr.end_range, for_expr.block]
+            }
+            HirExpression::If(if_expr) => {
+                let mut children = vec![if_expr.condition, if_expr.consequence];
+                children.extend(if_expr.alternative);
+                children
+            }
+            HirExpression::Tuple(elements) => elements,
+            HirExpression::Lambda(lambda) => vec![lambda.body],
+            HirExpression::Ident(_) | HirExpression::Literal(_) | HirExpression::Error => {
+                Vec::new()
+            }
+        };
+
+        for child in children {
+            self.visit_expression(child);
+        }
+    }
+
+    fn add_pattern_type_hints(&mut self, pattern: &HirPattern) {
+        match pattern {
+            HirPattern::Identifier(ident) => {
+                let typ = self.driver.context().def_interner.id_type(ident.id);
+                if !matches!(typ, Type::Error) && ident.location.file == self.file_id {
+                    let offset = ident.location.span.end() as usize + 1;
+                    self.add_hint(offset, format!(": {typ}"), Some(InlayHintKind::TYPE));
+                }
+            }
+            HirPattern::Mutable(pattern, _) => self.add_pattern_type_hints(pattern),
+            HirPattern::Tuple(patterns, _) => {
+                for pattern in patterns {
+                    self.add_pattern_type_hints(pattern);
+                }
+            }
+            HirPattern::Struct(_, fields, _) => {
+                for (_, pattern) in fields {
+                    self.add_pattern_type_hints(pattern);
+                }
+            }
+        }
+    }
+
+    fn add_opcode_count_hint(&mut self, call: ExprId, func_id: FuncId) {
+        let driver = self.driver;
+        let opcode_count = match &mut self.opcode_counts {
+            Some(opcode_counts) => {
+                *opcode_counts.entry(func_id).or_insert_with(|| count_opcodes(driver, func_id))
+            }
+            None => return,
+        };
+
+        let location = driver.context().def_interner.expr_location(&call);
+        match opcode_count {
+            Some(opcode_count) if location.file == self.file_id => {
+                let plural = if opcode_count == 1 { "" } else { "s" };
+                let label = format!("{opcode_count} ACIR opcode{plural}");
+                self.add_hint(location.span.end() as usize + 1, label, None);
+            }
+            _ => (),
+        }
+    }
+
+    fn add_hint(&mut self, offset: usize, label: String, kind: Option<InlayHintKind>) {
+        let source = self.driver.file_manager().fetch_file(self.file_id).source();
+        self.hints.push(InlayHint {
+            position: offset_to_position(source, offset),
+            label: InlayHintLabel::String(label),
+            kind,
+            text_edits: None,
+            tooltip: None,
+            padding_left: Some(kind.is_none()),
+            padding_right: None,
+            data: None,
+        });
+    }
+}
+
+/// Compiles a function as if it were the `main` function of a program, returning the number of
+/// opcodes in the resulting circuit.
+///
+/// Only functions with a body and without generics can be compiled on their own.
+fn count_opcodes(driver: &Driver, func_id: FuncId) -> Option<usize> {
+    let meta = driver.context().def_interner.function_meta(&func_id);
+    if meta.kind != FunctionKind::Normal || matches!(meta.typ, Type::Forall(..)) {
+        return None;
+    }
+
+    // Standard output is used to communicate with the editor, so `println` must stay quiet
+    let options = CompileOptions { show_output: false, ..CompileOptions::default() };
+    let program = driver.compile_no_check(&options, func_id).ok()?;
+    Some(program.circuit.opcodes.len())
+}
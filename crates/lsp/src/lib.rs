@@ -1,52 +1,160 @@
 use std::{
+    collections::{HashMap, HashSet},
     future::Future,
     ops::ControlFlow,
+    path::{Path, PathBuf},
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 
 use async_lsp::{
-    router::Router, AnyEvent, AnyNotification, AnyRequest, Error, LspService, ResponseError,
+    router::Router, AnyEvent, AnyNotification, AnyRequest, ClientSocket, Error, ErrorCode,
+    LanguageClient, LspService, ResponseError,
 };
 use lsp_types::{
-    notification, request, DidChangeConfigurationParams, DidChangeTextDocumentParams,
-    DidCloseTextDocumentParams, DidOpenTextDocumentParams, InitializeParams, InitializeResult,
-    InitializedParams, ServerCapabilities,
+    notification, request, CodeLens, CodeLensOptions, CodeLensParams, CompletionOptions,
+    CompletionParams, CompletionResponse, Diagnostic, DiagnosticSeverity,
+    DidChangeConfigurationParams, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, DidSaveTextDocumentParams, DocumentFormattingParams,
+    DocumentSymbolParams, DocumentSymbolResponse, ExecuteCommandOptions, ExecuteCommandParams,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverParams, HoverProviderCapability,
+    InitializeParams, InitializeResult, InitializedParams, InlayHint, InlayHintParams, Location,
+    OneOf, Position, PrepareRenameResponse, PublishDiagnosticsParams, Range, ReferenceParams,
+    RenameOptions, RenameParams, SaveOptions, ServerCapabilities, TextDocumentPositionParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, TextEdit, Url, WorkDoneProgressOptions, WorkspaceEdit,
+    WorkspaceSymbolParams, WorkspaceSymbolResponse,
 };
+use noirc_driver::Driver;
+use noirc_frontend::formatter;
 use serde_json::Value as JsonValue;
 use tower::Service;
 
+mod commands;
+mod completion;
+mod diagnostics;
+mod hover;
+mod inlay_hints;
+mod position;
+mod references;
+mod rename;
+mod symbols;
+
+/// Creates a [`Driver`] for the package containing the file at the given path, with the
+/// package's dependencies and the standard library added to its crate graph.
+///
+/// The contents of the documents open in the editor are passed along so that they can be used
+/// in place of the files on disk. Errors are returned as a message to be shown to the user.
+pub type DriverFactory =
+    Box<dyn Fn(&Path, &HashMap<PathBuf, String>) -> Result<Driver, String> + Send>;
+
+pub use commands::NargoCommand;
+
+/// Loads the formatting options of the package containing the file at the given path, which
+/// are set in the `[fmt]` section of its manifest. Errors are returned as a message to be shown
+/// to the user.
+pub type FormatConfigLoader = Box<dyn Fn(&Path) -> Result<formatter::Config, String> + Send>;
+
+/// Finds the root of the workspace containing the file at the given path. Only files within it
+/// are edited by renames, as those elsewhere, such as git dependencies, are shared with other
+/// packages. Errors are returned as a message to be shown to the user.
+pub type WorkspaceRootFinder = Box<dyn Fn(&Path) -> Result<PathBuf, String> + Send>;
+
+/// Runs a [`NargoCommand`] on the package containing the file at the given path, passing each
+/// line of output to the given function as it is produced.
+///
+/// Commands are run on their own thread so that the server can continue handling requests in
+/// the meantime. Errors are returned as a message to be shown to the user.
+pub type CommandRunner =
+    Box<dyn Fn(&Path, &NargoCommand, &mut dyn FnMut(String)) -> Result<(), String> + Send + Sync>;
+
 // State for the LSP gets implemented on this struct and is internal to the implementation
-#[derive(Debug, Default)]
-struct LspState;
+struct LspState {
+    client: ClientSocket,
+    driver_factory: DriverFactory,
+    command_runner: Arc<CommandRunner>,
+    format_config_loader: FormatConfigLoader,
+    workspace_root_finder: WorkspaceRootFinder,
+
+    /// The contents of each document open in the editor, which may not have been saved to disk.
+    open_documents: HashMap<PathBuf, String>,
+
+    /// Documents which diagnostics have been published for, so they can be cleared once fixed.
+    documents_with_diagnostics: HashSet<Url>,
+
+    /// Whether inlay hints should show the number of ACIR opcodes each function call compiles
+    /// to. This is set by the `opcodeCountHints` setting as compiling each function is slow.
+    opcode_count_hints: bool,
+}
+
+impl LspState {
+    fn new(
+        client: &ClientSocket,
+        driver_factory: DriverFactory,
+        command_runner: CommandRunner,
+        format_config_loader: FormatConfigLoader,
+        workspace_root_finder: WorkspaceRootFinder,
+    ) -> Self {
+        Self {
+            client: client.clone(),
+            driver_factory,
+            command_runner: Arc::new(command_runner),
+            format_config_loader,
+            workspace_root_finder,
+            open_documents: HashMap::new(),
+            documents_with_diagnostics: HashSet::new(),
+            opcode_count_hints: false,
+        }
+    }
+}
 
 pub struct NargoLspService {
     router: Router<LspState>,
 }
 
 impl NargoLspService {
-    pub fn new() -> Self {
-        let state = LspState::default();
+    pub fn new(
+        client: &ClientSocket,
+        driver_factory: DriverFactory,
+        command_runner: CommandRunner,
+        format_config_loader: FormatConfigLoader,
+        workspace_root_finder: WorkspaceRootFinder,
+    ) -> Self {
+        let state = LspState::new(
+            client,
+            driver_factory,
+            command_runner,
+            format_config_loader,
+            workspace_root_finder,
+        );
         let mut router = Router::new(state);
         router
             .request::<request::Initialize, _>(on_initialize)
             .request::<request::Shutdown, _>(on_shutdown)
+            .request::<request::GotoDefinition, _>(on_goto_definition)
+            .request::<request::References, _>(on_references)
+            .request::<request::HoverRequest, _>(on_hover)
+            .request::<request::Completion, _>(on_completion)
+            .request::<request::CodeLensRequest, _>(on_code_lens)
+            .request::<request::ExecuteCommand, _>(on_execute_command)
+            .request::<request::DocumentSymbolRequest, _>(on_document_symbol)
+            .request::<request::WorkspaceSymbol, _>(on_workspace_symbol)
+            .request::<request::PrepareRenameRequest, _>(on_prepare_rename)
+            .request::<request::Rename, _>(on_rename)
+            .request::<request::InlayHintRequest, _>(on_inlay_hint)
+            .request::<request::Formatting, _>(on_formatting)
             .notification::<notification::Initialized>(on_initialized)
             .notification::<notification::DidChangeConfiguration>(on_did_change_configuration)
             .notification::<notification::DidOpenTextDocument>(on_did_open_text_document)
             .notification::<notification::DidChangeTextDocument>(on_did_change_text_document)
             .notification::<notification::DidCloseTextDocument>(on_did_close_text_document)
+            .notification::<notification::DidSaveTextDocument>(on_did_save_text_document)
             .notification::<notification::Exit>(on_exit);
         Self { router }
     }
 }
 
-impl Default for NargoLspService {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 // This trait implemented as a passthrough to the router, which makes
 // our `NargoLspService` a normal Service as far as Tower is concerned.
 impl Service<AnyRequest> for NargoLspService {
@@ -86,12 +194,49 @@ impl LspService for NargoLspService {
 // and params passed in.
 
 fn on_initialize(
-    _state: &mut LspState,
-    _params: InitializeParams,
+    state: &mut LspState,
+    params: InitializeParams,
 ) -> impl Future<Output = Result<InitializeResult, ResponseError>> {
+    if let Some(options) = &params.initialization_options {
+        update_settings(state, options);
+    }
+
     async {
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Options(
+                    TextDocumentSyncOptions {
+                        open_close: Some(true),
+                        change: Some(TextDocumentSyncKind::FULL),
+                        save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                            include_text: Some(false),
+                        })),
+                        ..TextDocumentSyncOptions::default()
+                    },
+                )),
+                definition_provider: Some(OneOf::Left(true)),
+                references_provider: Some(OneOf::Left(true)),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(vec![".".to_owned(), ":".to_owned()]),
+                    ..CompletionOptions::default()
+                }),
+                code_lens_provider: Some(CodeLensOptions { resolve_provider: Some(false) }),
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: commands::COMMANDS
+                        .iter()
+                        .map(|&command| command.to_owned())
+                        .collect(),
+                    ..ExecuteCommandOptions::default()
+                }),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                })),
+                inlay_hint_provider: Some(OneOf::Left(true)),
+                document_formatting_provider: Some(OneOf::Left(true)),
                 // Add capabilities before this spread when adding support for one
                 ..ServerCapabilities::default()
             },
@@ -107,6 +252,217 @@ fn on_shutdown(
     async { Ok(()) }
 }
 
+fn on_goto_definition(
+    state: &mut LspState,
+    params: GotoDefinitionParams,
+) -> impl Future<Output = Result<Option<GotoDefinitionResponse>, ResponseError>> {
+    let position = params.text_document_position_params;
+
+    let location = check_document_package(state, &position.text_document.uri).and_then(
+        |(driver, file_path)| {
+            let id = references::reference_at(&driver, &file_path, position.position)?;
+            references::declaration_location(&driver, id)
+        },
+    );
+
+    async move { Ok(location.map(GotoDefinitionResponse::Scalar)) }
+}
+
+fn on_references(
+    state: &mut LspState,
+    params: ReferenceParams,
+) -> impl Future<Output = Result<Option<Vec<Location>>, ResponseError>> {
+    let position = params.text_document_position;
+    let include_declaration = params.context.include_declaration;
+
+    let locations = check_document_package(state, &position.text_document.uri).and_then(
+        |(driver, file_path)| {
+            let id = references::reference_at(&driver, &file_path, position.position)?;
+            Some(references::reference_locations(&driver, id, include_declaration))
+        },
+    );
+
+    async move { Ok(locations) }
+}
+
+fn on_hover(
+    state: &mut LspState,
+    params: HoverParams,
+) -> impl Future<Output = Result<Option<Hover>, ResponseError>> {
+    let position = params.text_document_position_params;
+
+    let hover = check_document_package(state, &position.text_document.uri)
+        .and_then(|(driver, file_path)| hover::hover(&driver, &file_path, position.position));
+
+    async move { Ok(hover) }
+}
+
+fn on_completion(
+    state: &mut LspState,
+    params: CompletionParams,
+) -> impl Future<Output = Result<Option<CompletionResponse>, ResponseError>> {
+    let position = params.text_document_position;
+
+    let completions = position.text_document.uri.to_file_path().ok().and_then(|file_path| {
+        let source = match state.open_documents.get(&file_path) {
+            Some(source) => source.clone(),
+            None => std::fs::read_to_string(&file_path).ok()?,
+        };
+        let request = completion::CompletionRequest::new(&source, position.position);
+
+        // The package is checked with the name being completed removed so that it still parses
+        let mut documents = state.open_documents.clone();
+        documents.insert(file_path.clone(), request.source().to_owned());
+
+        let driver = check_package(state, &file_path, &documents)?;
+        Some(CompletionResponse::Array(request.complete(&driver, &file_path)))
+    });
+
+    async move { Ok(completions) }
+}
+
+fn on_code_lens(
+    state: &mut LspState,
+    params: CodeLensParams,
+) -> impl Future<Output = Result<Option<Vec<CodeLens>>, ResponseError>> {
+    let lenses = check_document_package(state, &params.text_document.uri)
+        .and_then(|(driver, file_path)| commands::code_lenses(&driver, &file_path));
+
+    async move { Ok(lenses) }
+}
+
+fn on_execute_command(
+    state: &mut LspState,
+    params: ExecuteCommandParams,
+) -> impl Future<Output = Result<Option<JsonValue>, ResponseError>> {
+    let result = match NargoCommand::from_params(&params) {
+        Some((file_path, command)) => {
+            let client = state.client.clone();
+            let runner = state.command_runner.clone();
+
+            // Output is streamed back to the client as the command runs
+            std::thread::spawn(move || commands::run_command(client, &runner, &file_path, command));
+            Ok(None)
+        }
+        None => Err(ResponseError::new(
+            ErrorCode::INVALID_PARAMS,
+            format!("invalid arguments for command `{}`", params.command),
+        )),
+    };
+
+    async move { result }
+}
+
+fn on_document_symbol(
+    state: &mut LspState,
+    params: DocumentSymbolParams,
+) -> impl Future<Output = Result<Option<DocumentSymbolResponse>, ResponseError>> {
+    let symbols = params.text_document.uri.to_file_path().ok().and_then(|file_path| {
+        let source = match state.open_documents.get(&file_path) {
+            Some(source) => source.clone(),
+            None => std::fs::read_to_string(&file_path).ok()?,
+        };
+        Some(DocumentSymbolResponse::Nested(symbols::document_symbols(&source)))
+    });
+
+    async move { Ok(symbols) }
+}
+
+fn on_workspace_symbol(
+    state: &mut LspState,
+    params: WorkspaceSymbolParams,
+) -> impl Future<Output = Result<Option<WorkspaceSymbolResponse>, ResponseError>> {
+    // Symbols are searched for in each package with a document open in the editor
+    let mut visited_files = HashSet::new();
+    let mut symbols = Vec::new();
+    for file_path in state.open_documents.keys() {
+        if visited_files.contains(file_path) {
+            continue;
+        }
+        if let Some(driver) = check_package(state, file_path, &state.open_documents) {
+            symbols.extend(symbols::workspace_symbols(&driver, &params.query, &mut visited_files));
+        }
+    }
+
+    async move { Ok(Some(WorkspaceSymbolResponse::Flat(symbols))) }
+}
+
+fn on_prepare_rename(
+    state: &mut LspState,
+    params: TextDocumentPositionParams,
+) -> impl Future<Output = Result<Option<PrepareRenameResponse>, ResponseError>> {
+    let response = match check_document_package(state, &params.text_document.uri) {
+        Some((driver, file_path)) => (state.workspace_root_finder)(&file_path)
+            .and_then(|workspace_root| {
+                rename::prepare_rename(&driver, &file_path, &workspace_root, params.position)
+            })
+            .map_err(|message| ResponseError::new(ErrorCode::REQUEST_FAILED, message)),
+        None => Ok(None),
+    };
+
+    async move { response }
+}
+
+fn on_rename(
+    state: &mut LspState,
+    params: RenameParams,
+) -> impl Future<Output = Result<Option<WorkspaceEdit>, ResponseError>> {
+    let position = params.text_document_position;
+
+    let edit = match check_document_package(state, &position.text_document.uri) {
+        Some((driver, file_path)) => (state.workspace_root_finder)(&file_path)
+            .and_then(|workspace_root| {
+                let new_name = &params.new_name;
+                rename::rename(&driver, &file_path, &workspace_root, position.position, new_name)
+            })
+            .map_err(|message| ResponseError::new(ErrorCode::REQUEST_FAILED, message)),
+        None => Ok(None),
+    };
+
+    async move { edit }
+}
+
+fn on_inlay_hint(
+    state: &mut LspState,
+    params: InlayHintParams,
+) -> impl Future<Output = Result<Option<Vec<InlayHint>>, ResponseError>> {
+    let hints = params.text_document.uri.to_file_path().ok().and_then(|file_path| {
+        let mut driver = (state.driver_factory)(&file_path, &state.open_documents).ok()?;
+
+        // Programs with errors may not compile, so opcodes are only counted for those without
+        let opcode_counts = driver.check_crate(false).is_ok() && state.opcode_count_hints;
+
+        inlay_hints::inlay_hints(&driver, &file_path, params.range, opcode_counts)
+    });
+
+    async move { Ok(hints) }
+}
+
+fn on_formatting(
+    state: &mut LspState,
+    params: DocumentFormattingParams,
+) -> impl Future<Output = Result<Option<Vec<TextEdit>>, ResponseError>> {
+    let edits = params.text_document.uri.to_file_path().ok().and_then(|file_path| {
+        let source = match state.open_documents.get(&file_path) {
+            Some(source) => source.clone(),
+            None => std::fs::read_to_string(&file_path).ok()?,
+        };
+        // Files outside of a package are formatted with the default options
+        let config = (state.format_config_loader)(&file_path).unwrap_or_default();
+
+        // Files which don't parse are left as they are, the errors are shown as diagnostics
+        let formatted = formatter::format(&source, &config).ok()?;
+        if formatted == source {
+            return Some(Vec::new());
+        }
+        let end = position::offset_to_position(&source, source.len());
+        let range = Range { start: Position { line: 0, character: 0 }, end };
+        Some(vec![TextEdit { range, new_text: formatted }])
+    });
+
+    async move { Ok(edits) }
+}
+
 fn on_initialized(
     _state: &mut LspState,
     _params: InitializedParams,
@@ -115,30 +471,55 @@ fn on_initialized(
 }
 
 fn on_did_change_configuration(
-    _state: &mut LspState,
-    _params: DidChangeConfigurationParams,
+    state: &mut LspState,
+    params: DidChangeConfigurationParams,
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
+    update_settings(state, &params.settings);
     ControlFlow::Continue(())
 }
 
 fn on_did_open_text_document(
-    _state: &mut LspState,
-    _params: DidOpenTextDocumentParams,
+    state: &mut LspState,
+    params: DidOpenTextDocumentParams,
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
+    if let Ok(file_path) = params.text_document.uri.to_file_path() {
+        state.open_documents.insert(file_path.clone(), params.text_document.text);
+        publish_package_diagnostics(state, &file_path);
+    }
     ControlFlow::Continue(())
 }
 
 fn on_did_change_text_document(
-    _state: &mut LspState,
-    _params: DidChangeTextDocumentParams,
+    state: &mut LspState,
+    params: DidChangeTextDocumentParams,
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
+    // We only ask for full document syncs so the last change contains the entire document
+    let new_text = params.content_changes.into_iter().last().map(|change| change.text);
+
+    if let (Ok(file_path), Some(new_text)) = (params.text_document.uri.to_file_path(), new_text) {
+        state.open_documents.insert(file_path.clone(), new_text);
+        publish_package_diagnostics(state, &file_path);
+    }
     ControlFlow::Continue(())
 }
 
 fn on_did_close_text_document(
-    _state: &mut LspState,
-    _params: DidCloseTextDocumentParams,
+    state: &mut LspState,
+    params: DidCloseTextDocumentParams,
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
+    if let Ok(file_path) = params.text_document.uri.to_file_path() {
+        state.open_documents.remove(&file_path);
+    }
+    ControlFlow::Continue(())
+}
+
+fn on_did_save_text_document(
+    state: &mut LspState,
+    params: DidSaveTextDocumentParams,
+) -> ControlFlow<Result<(), async_lsp::Error>> {
+    if let Ok(file_path) = params.text_document.uri.to_file_path() {
+        publish_package_diagnostics(state, &file_path);
+    }
     ControlFlow::Continue(())
 }
 
@@ -146,18 +527,188 @@ fn on_exit(_state: &mut LspState, _params: ()) -> ControlFlow<Result<(), async_l
     ControlFlow::Continue(())
 }
 
+/// Checks the package containing `file_path` and publishes the diagnostics found for each of its
+/// documents, clearing the diagnostics of any documents in the package which no longer have any.
+fn publish_package_diagnostics(state: &mut LspState, file_path: &Path) {
+    let mut driver = match (state.driver_factory)(file_path, &state.open_documents) {
+        Ok(driver) => driver,
+        Err(message) => {
+            // Without a crate graph the error can only be attached to the document being checked
+            if let Ok(uri) = Url::from_file_path(file_path) {
+                let diagnostic = Diagnostic {
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    source: Some("nargo".to_owned()),
+                    message,
+                    ..Diagnostic::default()
+                };
+                publish_document_diagnostics(state, uri, vec![diagnostic]);
+            }
+            return;
+        }
+    };
+
+    let mut diagnostics = diagnostics::check_crate(&mut driver);
+
+    let fixed_documents: Vec<_> = state
+        .documents_with_diagnostics
+        .iter()
+        .filter(|uri| !diagnostics.contains_key(uri))
+        .filter(|uri| match uri.to_file_path() {
+            Ok(path) => driver.file_manager().find_file_id(&path).is_some(),
+            Err(()) => false,
+        })
+        .cloned()
+        .collect();
+
+    for uri in fixed_documents {
+        diagnostics.insert(uri, Vec::new());
+    }
+
+    for (uri, document_diagnostics) in diagnostics {
+        publish_document_diagnostics(state, uri, document_diagnostics);
+    }
+}
+
+/// Creates a driver for the package containing the given document and checks its crate so that
+/// the names within it are resolved. Returns `None` if the document isn't part of a package.
+fn check_document_package(state: &LspState, uri: &Url) -> Option<(Driver, PathBuf)> {
+    let file_path = uri.to_file_path().ok()?;
+    let driver = check_package(state, &file_path, &state.open_documents)?;
+    Some((driver, file_path))
+}
+
+/// Creates a driver for the package containing `file_path` using the given document contents in
+/// place of the files on disk, and checks its crate.
+fn check_package(
+    state: &LspState,
+    file_path: &Path,
+    documents: &HashMap<PathBuf, String>,
+) -> Option<Driver> {
+    let mut driver = (state.driver_factory)(file_path, documents).ok()?;
+
+    // Any errors are reported as diagnostics, the names which did resolve can still be used
+    let _ = driver.check_crate(false);
+
+    Some(driver)
+}
+
+/// Updates the server's settings from the initialization options or configuration sent by the
+/// client. Settings which aren't given are left unchanged.
+fn update_settings(state: &mut LspState, settings: &JsonValue) {
+    if let Some(opcode_count_hints) = settings.get("opcodeCountHints").and_then(JsonValue::as_bool)
+    {
+        state.opcode_count_hints = opcode_count_hints;
+    }
+}
+
+fn publish_document_diagnostics(state: &mut LspState, uri: Url, diagnostics: Vec<Diagnostic>) {
+    if diagnostics.is_empty() {
+        state.documents_with_diagnostics.remove(&uri);
+    } else {
+        state.documents_with_diagnostics.insert(uri.clone());
+    }
+
+    // The client may have already disconnected, in which case there is nobody to notify
+    let _ = state.client.publish_diagnostics(PublishDiagnosticsParams {
+        uri,
+        diagnostics,
+        version: None,
+    });
+}
+
 #[cfg(test)]
 mod lsp_tests {
     use tokio::test;
 
     use super::*;
 
+    fn test_state() -> LspState {
+        let client = ClientSocket::new_closed();
+        LspState::new(
+            &client,
+            Box::new(|_, _| Err("no packages in tests".to_owned())),
+            Box::new(|_, _, _| Err("no packages in tests".to_owned())),
+            Box::new(|_| Err("no packages in tests".to_owned())),
+            Box::new(|_| Err("no packages in tests".to_owned())),
+        )
+    }
+
     #[test]
     async fn test_on_initialize() {
-        let mut state = LspState::default();
+        let mut state = test_state();
         let params = InitializeParams::default();
         let response = on_initialize(&mut state, params).await.unwrap();
-        assert_eq!(response.capabilities, ServerCapabilities::default());
+        assert!(matches!(
+            response.capabilities.text_document_sync,
+            Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+                change: Some(TextDocumentSyncKind::FULL),
+                ..
+            }))
+        ));
+        assert_eq!(response.capabilities.definition_provider, Some(OneOf::Left(true)));
+        assert_eq!(
+            response.capabilities.hover_provider,
+            Some(HoverProviderCapability::Simple(true))
+        );
+        assert!(response.capabilities.completion_provider.is_some());
+        assert!(response.capabilities.code_lens_provider.is_some());
+        assert!(matches!(response.capabilities.inlay_hint_provider, Some(OneOf::Left(true))));
+        assert_eq!(response.capabilities.document_symbol_provider, Some(OneOf::Left(true)));
+        assert_eq!(response.capabilities.document_formatting_provider, Some(OneOf::Left(true)));
         assert!(response.server_info.is_none());
     }
+
+    #[test]
+    async fn test_opcode_count_hints_setting() {
+        let mut state = test_state();
+        assert!(!state.opcode_count_hints);
+
+        let params = InitializeParams {
+            initialization_options: Some(serde_json::json!({ "opcodeCountHints": true })),
+            ..InitializeParams::default()
+        };
+        on_initialize(&mut state, params).await.unwrap();
+        assert!(state.opcode_count_hints);
+
+        let params = DidChangeConfigurationParams {
+            settings: serde_json::json!({ "opcodeCountHints": false }),
+        };
+        let _ = on_did_change_configuration(&mut state, params);
+        assert!(!state.opcode_count_hints);
+    }
+
+    #[test]
+    async fn test_on_formatting() {
+        let mut state = test_state();
+        let uri = Url::parse("file:///not_a_package/main.nr").unwrap();
+        let file_path = uri.to_file_path().unwrap();
+        state.open_documents.insert(file_path, "fn main(){}".to_owned());
+
+        let params = DocumentFormattingParams {
+            text_document: lsp_types::TextDocumentIdentifier { uri },
+            options: Default::default(),
+            work_done_progress_params: Default::default(),
+        };
+        let edits = on_formatting(&mut state, params).await.unwrap().unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].new_text, "fn main() {}\n");
+        assert_eq!(edits[0].range.end, Position { line: 0, character: 11 });
+    }
+
+    #[test]
+    async fn test_goto_definition_outside_package() {
+        let mut state = test_state();
+        let params = GotoDefinitionParams {
+            text_document_position_params: lsp_types::TextDocumentPositionParams {
+                text_document: lsp_types::TextDocumentIdentifier {
+                    uri: Url::parse("file:///not_a_package/main.nr").unwrap(),
+                },
+                position: lsp_types::Position { line: 0, character: 0 },
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let response = on_goto_definition(&mut state, params).await.unwrap();
+        assert!(response.is_none());
+    }
 }
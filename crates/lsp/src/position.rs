@@ -0,0 +1,94 @@
+use lsp_types::{Position, Range};
+use noirc_errors::Span;
+
+/// Converts a byte offset into `source` into an LSP position.
+///
+/// LSP positions count characters within a line in UTF-16 code units.
+pub(crate) fn offset_to_position(source: &str, mut offset: usize) -> Position {
+    offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset += 1;
+    }
+    let mut line = 0;
+    let mut line_start = 0;
+
+    for (index, character) in source.char_indices() {
+        if index >= offset {
+            break;
+        }
+        if character == '\n' {
+            line += 1;
+            line_start = index + 1;
+        }
+    }
+
+    let character = source[line_start..offset].chars().map(char::len_utf16).sum::<usize>();
+    Position { line: line as u32, character: character as u32 }
+}
+
+/// Converts an LSP position into a byte offset into `source`.
+///
+/// Positions past the end of a line are moved back to the end of that line.
+pub(crate) fn position_to_offset(source: &str, position: Position) -> usize {
+    let line_start = match position.line.checked_sub(1) {
+        None => 0,
+        Some(previous_line) => match source.match_indices('\n').nth(previous_line as usize) {
+            Some((newline, _)) => newline + 1,
+            None => return source.len(),
+        },
+    };
+
+    let mut character = 0;
+    for (index, current) in source[line_start..].char_indices() {
+        if current == '\n' || character >= position.character as usize {
+            return line_start + index;
+        }
+        character += current.len_utf16();
+    }
+    source.len()
+}
+
+/// Converts a span within `source` into an LSP range.
+pub(crate) fn span_to_range(source: &str, span: Span) -> Range {
+    // Span ends are one character short, see `noirc_errors::reporter::convert_diagnostic`
+    let start = offset_to_position(source, span.start() as usize);
+    let end = offset_to_position(source, span.end() as usize + 1);
+    Range { start, end }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::Position;
+
+    use super::{offset_to_position, position_to_offset};
+
+    #[test]
+    fn converts_offsets_to_positions() {
+        let source = "fn main() {\n    let x = \"é\";\n}\n";
+
+        assert_eq!(offset_to_position(source, 0), Position { line: 0, character: 0 });
+        assert_eq!(offset_to_position(source, 12), Position { line: 1, character: 0 });
+        assert_eq!(offset_to_position(source, 16), Position { line: 1, character: 4 });
+
+        // `é` is two bytes in UTF-8 but a single UTF-16 code unit
+        let closing_quote = source.find("\";").unwrap();
+        assert_eq!(offset_to_position(source, closing_quote), Position { line: 1, character: 14 });
+
+        assert_eq!(offset_to_position(source, source.len()), Position { line: 3, character: 0 });
+    }
+
+    #[test]
+    fn converts_positions_to_offsets() {
+        let source = "fn main() {\n    let x = \"é\";\n}\n";
+
+        assert_eq!(position_to_offset(source, Position { line: 0, character: 0 }), 0);
+        assert_eq!(position_to_offset(source, Position { line: 1, character: 8 }), 20);
+
+        let closing_quote = source.find("\";").unwrap();
+        assert_eq!(position_to_offset(source, Position { line: 1, character: 14 }), closing_quote);
+
+        // Positions past the end of a line or the document are clamped
+        assert_eq!(position_to_offset(source, Position { line: 0, character: 100 }), 11);
+        assert_eq!(position_to_offset(source, Position { line: 10, character: 0 }), source.len());
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use lsp_types::{Location, Position, Url};
+use noirc_driver::Driver;
+use noirc_errors::Span;
+use noirc_frontend::node_interner::ReferenceId;
+
+use crate::position::{position_to_offset, span_to_range};
+
+/// Returns the declaration named at the given position in a document. The name may either be a
+/// reference to the declaration or the name given in the declaration itself.
+///
+/// The driver's crate must already have been checked so that its names are resolved.
+pub(crate) fn reference_at(
+    driver: &Driver,
+    file_path: &Path,
+    position: Position,
+) -> Option<ReferenceId> {
+    let file_id = driver.file_manager().find_file_id(file_path)?;
+    let source = driver.file_manager().fetch_file(file_id).source();
+    let offset = position_to_offset(source, position);
+
+    driver.context().def_interner.reference_at(file_id, offset as u32)
+}
+
+/// Returns the location of the name given in a declaration.
+pub(crate) fn declaration_location(driver: &Driver, id: ReferenceId) -> Option<Location> {
+    let context = driver.context();
+
+    let location = match (context.def_interner.declaration_location(id), id) {
+        (Some(location), _) => location,
+        // Modules declared with `mod foo;` are the file they are found in
+        (None, ReferenceId::Module(module_id)) => {
+            let def_map = context.def_map(module_id.krate)?;
+            let file_id = def_map.modules()[module_id.local_id.0].origin.file_id();
+            noirc_errors::Location::new(Span::default(), file_id)
+        }
+        (None, _) => return None,
+    };
+
+    to_lsp_location(driver, location)
+}
+
+/// Returns the location of each reference to a declaration, optionally including the name
+/// given in the declaration itself.
+pub(crate) fn reference_locations(
+    driver: &Driver,
+    id: ReferenceId,
+    include_declaration: bool,
+) -> Vec<Location> {
+    let mut locations = Vec::new();
+
+    if include_declaration {
+        locations.extend(declaration_location(driver, id));
+    }
+
+    for reference in driver.context().def_interner.find_references(id) {
+        locations.extend(to_lsp_location(driver, reference));
+    }
+
+    locations
+}
+
+/// Converts a location in the program into an LSP location.
+///
+/// The standard library is embedded into the compiler rather than being read from disk, so its
+/// files are written out to a temporary directory for the editor to open.
+fn to_lsp_location(driver: &Driver, location: noirc_errors::Location) -> Option<Location> {
+    let file = driver.file_manager().fetch_file(location.file);
+    let range = span_to_range(file.source(), location.span);
+
+    let uri = match Url::from_file_path(file.path()) {
+        Ok(uri) => uri,
+        Err(()) => {
+            let path = std::env::temp_dir().join("noir_lsp").join(file.path());
+            write_embedded_file(&path, file.source()).ok()?;
+            Url::from_file_path(path).ok()?
+        }
+    };
+
+    Some(Location { uri, range })
+}
+
+fn write_embedded_file(path: &Path, source: &str) -> std::io::Result<()> {
+    // Files are left untouched if they are already up to date as the editor may have them open
+    if std::fs::read_to_string(path).map_or(false, |existing| existing == source) {
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, source)
+}
@@ -0,0 +1,236 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use lsp_types::{Position, PrepareRenameResponse, TextEdit, Url, WorkspaceEdit};
+use noirc_driver::Driver;
+use noirc_errors::{Location, Span};
+use noirc_frontend::{
+    lexer::Lexer,
+    node_interner::{DefinitionKind, NodeInterner, ReferenceId},
+    token::Token,
+};
+
+use crate::position::{position_to_offset, span_to_range};
+
+/// Returns the range of the name at the given position and the name itself if the declaration
+/// it refers to can be renamed, or an error explaining why it can't be.
+///
+/// The driver's crate must already have been checked so that its names are resolved.
+pub(crate) fn prepare_rename(
+    driver: &Driver,
+    file_path: &Path,
+    workspace_root: &Path,
+    position: Position,
+) -> Result<Option<PrepareRenameResponse>, String> {
+    let (id, location) = match name_at(driver, file_path, position) {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+    check_renamable(driver, workspace_root, id)?;
+
+    let source = driver.file_manager().fetch_file(location.file).source();
+    let placeholder = span_text(source, location.span).to_owned();
+    let range = span_to_range(source, location.span);
+
+    Ok(Some(PrepareRenameResponse::RangeWithPlaceholder { range, placeholder }))
+}
+
+/// Returns the edits which rename the declaration named at the given position, along with every
+/// reference to it throughout the crate graph, to `new_name`.
+///
+/// Only declarations within `workspace_root` may be renamed. The driver's crate must already
+/// have been checked so that its names are resolved.
+pub(crate) fn rename(
+    driver: &Driver,
+    file_path: &Path,
+    workspace_root: &Path,
+    position: Position,
+    new_name: &str,
+) -> Result<Option<WorkspaceEdit>, String> {
+    let (id, _) = match name_at(driver, file_path, position) {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+    check_renamable(driver, workspace_root, id)?;
+
+    if !is_identifier(new_name) {
+        return Err(format!("`{new_name}` is not a valid name"));
+    }
+
+    let interner = &driver.context().def_interner;
+    let mut locations = interner.find_references(id);
+    locations.extend(interner.declaration_location(id));
+
+    // Clients reject overlapping edits, so names recorded more than once are only renamed once
+    let mut seen_locations = HashSet::new();
+    locations.retain(|location| seen_locations.insert(*location));
+
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+    for location in locations {
+        let file = driver.file_manager().fetch_file(location.file);
+        let uri = Url::from_file_path(file.path())
+            .map_err(|()| format!("cannot rename within {}", file.path().display()))?;
+
+        let old_name = span_text(file.source(), location.span);
+        let new_text = match shorthand_kind(interner, id, location) {
+            // `Foo { x }` names both the field and a variable, so only one of them is renamed
+            Some(Shorthand::Field) => format!("{new_name}: {old_name}"),
+            Some(Shorthand::Variable) => format!("{old_name}: {new_name}"),
+            None => new_name.to_owned(),
+        };
+
+        let range = span_to_range(file.source(), location.span);
+        changes.entry(uri).or_default().push(TextEdit { range, new_text });
+    }
+
+    Ok(Some(WorkspaceEdit { changes: Some(changes), ..WorkspaceEdit::default() }))
+}
+
+/// Returns the declaration named at the given position along with the location of that name.
+fn name_at(
+    driver: &Driver,
+    file_path: &Path,
+    position: Position,
+) -> Option<(ReferenceId, Location)> {
+    let file_id = driver.file_manager().find_file_id(file_path)?;
+    let source = driver.file_manager().fetch_file(file_id).source();
+    let offset = position_to_offset(source, position) as u32;
+    let interner = &driver.context().def_interner;
+
+    let id = interner.reference_at(file_id, offset)?;
+    let location = interner
+        .find_references(id)
+        .into_iter()
+        .chain(interner.declaration_location(id))
+        .filter(|location| location.file == file_id)
+        .find(|location| location.span.start() <= offset && offset <= location.span.end())?;
+
+    Some((id, location))
+}
+
+fn check_renamable(driver: &Driver, workspace_root: &Path, id: ReferenceId) -> Result<(), String> {
+    let interner = &driver.context().def_interner;
+
+    match id {
+        ReferenceId::Definition(definition_id) => {
+            if let DefinitionKind::GenericType(_) = interner.definition(definition_id).kind {
+                return Err("generic types cannot be renamed".to_owned());
+            }
+        }
+        ReferenceId::Module(_) => return Err("modules cannot be renamed".to_owned()),
+        ReferenceId::Struct(_) | ReferenceId::StructMember(..) => (),
+    }
+
+    // Declarations in the standard library are embedded into the compiler
+    let declaration = interner.declaration_location(id).ok_or("cannot find the declaration")?;
+    let declaration_path = driver.file_manager().fetch_file(declaration.file).path();
+    if declaration_path.is_relative() {
+        return Err("declarations in the standard library cannot be renamed".to_owned());
+    }
+    // Git dependencies are checked out into a cache shared by every package using them
+    if !canonical(declaration_path).starts_with(canonical(workspace_root)) {
+        return Err("declarations outside of the workspace cannot be renamed".to_owned());
+    }
+
+    Ok(())
+}
+
+/// Which of the declarations named by a name written in shorthand is being renamed.
+enum Shorthand {
+    Field,
+    Variable,
+}
+
+fn shorthand_kind(
+    interner: &NodeInterner,
+    id: ReferenceId,
+    location: Location,
+) -> Option<Shorthand> {
+    let others = interner.references_at_location(location);
+    let has_other = |is_kind: fn(&ReferenceId) -> bool| {
+        others.iter().any(|other| *other != id && is_kind(other))
+    };
+
+    match id {
+        ReferenceId::StructMember(..)
+            if has_other(|other| matches!(other, ReferenceId::Definition(_))) =>
+        {
+            Some(Shorthand::Field)
+        }
+        ReferenceId::Definition(_)
+            if has_other(|other| matches!(other, ReferenceId::StructMember(..))) =>
+        {
+            Some(Shorthand::Variable)
+        }
+        _ => None,
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn span_text(source: &str, span: Span) -> &str {
+    source.get(span.start() as usize..=span.end() as usize).unwrap_or_default()
+}
+
+/// Returns true if `name` lexes as a single identifier, rather than a keyword or anything else.
+fn is_identifier(name: &str) -> bool {
+    let (tokens, errors) = Lexer::lex(name);
+    let tokens: Vec<_> = tokens.0.iter().map(|token| token.token()).collect();
+    errors.is_empty() && matches!(tokens.as_slice(), [Token::Ident(_), Token::EOF])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use acvm::Language;
+    use lsp_types::Position;
+    use noirc_driver::Driver;
+    use noirc_frontend::graph::CrateType;
+
+    use super::{is_identifier, rename};
+
+    #[test]
+    fn validates_new_names() {
+        assert!(is_identifier("new_name"));
+        assert!(is_identifier("_x1"));
+        assert!(!is_identifier("fn"));
+        assert!(!is_identifier("1x"));
+        assert!(!is_identifier("two words"));
+        assert!(!is_identifier(""));
+    }
+
+    #[test]
+    fn rejects_declarations_outside_the_workspace() {
+        let main_path = Path::new("/workspace/src/main.nr");
+        let lib_path = Path::new("/nargo/github.com/noir-lang/lib/v1.0.0/src/lib.nr");
+        let mut driver = Driver::new(&Language::R1CS, Box::new(|_| true));
+        driver.add_file_overlay(
+            main_path.to_path_buf(),
+            "fn double(x: Field) -> Field {\n    lib::add(x, x)\n}\n".to_owned(),
+        );
+        driver.add_file_overlay(
+            lib_path.to_path_buf(),
+            "fn add(x: Field, y: Field) -> Field {\n    x + y\n}\n".to_owned(),
+        );
+        let local_crate = driver.create_local_crate(main_path, CrateType::Library);
+        let lib_crate = driver.create_non_local_crate(lib_path, CrateType::Library);
+        driver.add_dep(local_crate, lib_crate, "lib").unwrap();
+        driver.check_crate(false).unwrap();
+
+        let workspace_root = Path::new("/workspace");
+        let rename_at = |line, character| {
+            rename(&driver, main_path, workspace_root, Position { line, character }, "renamed")
+        };
+
+        assert!(rename_at(0, 4).unwrap().is_some());
+        assert_eq!(
+            rename_at(1, 10),
+            Err("declarations outside of the workspace cannot be renamed".to_owned())
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use iter_extended::vecmap;
+use lsp_types::{DocumentSymbol, Location, SymbolInformation, SymbolKind, Url};
+use noirc_driver::Driver;
+use noirc_errors::Span;
+use noirc_frontend::{parse_program, token::Attribute, Ident, NoirFunction, ParsedModule};
+
+use crate::position::span_to_range;
+
+/// Returns the outline of a document, with the symbols declared within each module, contract,
+/// struct and impl nested beneath it.
+///
+/// Documents are parsed on their own so that an outline can be given even if they are not part
+/// of a package or the package has errors.
+pub(crate) fn document_symbols(source: &str) -> Vec<DocumentSymbol> {
+    let (module, _) = parse_program(source);
+    module_symbols(source, &module)
+}
+
+/// Returns the symbols declared in each file of the driver's local crate whose names fuzzily
+/// match `query`. Files in `visited_files` are skipped and each file searched is added to it,
+/// so that packages sharing files are only searched once.
+///
+/// The driver's crate must already have been checked so that its modules are known.
+pub(crate) fn workspace_symbols(
+    driver: &Driver,
+    query: &str,
+    visited_files: &mut HashSet<PathBuf>,
+) -> Vec<SymbolInformation> {
+    let def_map = match driver.context().def_map(driver.local_crate()) {
+        Some(def_map) => def_map,
+        None => return Vec::new(),
+    };
+
+    let mut symbols = Vec::new();
+    for (_, module) in def_map.modules().iter() {
+        let file = driver.file_manager().fetch_file(module.origin.file_id());
+        let path = file.path().to_path_buf();
+
+        // Inline modules share the file they are declared in
+        if !visited_files.insert(path.clone()) {
+            continue;
+        }
+        let uri = match Url::from_file_path(&path) {
+            Ok(uri) => uri,
+            Err(()) => continue,
+        };
+
+        for symbol in document_symbols(file.source()) {
+            flatten_symbol(symbol, None, &uri, query, &mut symbols);
+        }
+    }
+    symbols
+}
+
+fn module_symbols(source: &str, module: &ParsedModule) -> Vec<DocumentSymbol> {
+    let mut symbols = Vec::new();
+
+    for submodule in &module.submodules {
+        let (kind, detail) = if submodule.is_contract {
+            (SymbolKind::NAMESPACE, "contract")
+        } else {
+            (SymbolKind::MODULE, "mod")
+        };
+        let children = module_symbols(source, &submodule.contents);
+        symbols.push(parent_symbol(source, &submodule.name, kind, Some(detail), children));
+    }
+
+    for decl in &module.module_decls {
+        let name = &decl.item;
+        symbols.push(symbol(source, name, name.span(), SymbolKind::MODULE, Some("mod")));
+    }
+
+    for noir_struct in &module.types {
+        let fields = vecmap(&noir_struct.fields, |field| {
+            let (name, typ) = &field.item;
+            let detail = typ.to_string();
+            symbol(source, name, name.span(), SymbolKind::FIELD, Some(&detail))
+        });
+
+        let mut struct_symbol =
+            symbol(source, &noir_struct.name, noir_struct.span, SymbolKind::STRUCT, None);
+        struct_symbol.children = Some(fields);
+        symbols.push(struct_symbol);
+    }
+
+    for noir_impl in &module.impls {
+        let name = Ident::new(format!("impl {}", noir_impl.object_type), noir_impl.type_span);
+        let methods = vecmap(&noir_impl.methods, |method| {
+            function_symbol(source, method, SymbolKind::METHOD)
+        });
+        symbols.push(parent_symbol(source, &name, SymbolKind::OBJECT, None, methods));
+    }
+
+    for global in &module.globals {
+        let global = &global.item;
+        let name = global.pattern.name_ident();
+        let span = name.span().merge(global.expression.span);
+        symbols.push(symbol(source, name, span, SymbolKind::CONSTANT, Some("global")));
+    }
+
+    for function in &module.functions {
+        symbols.push(function_symbol(source, function, SymbolKind::FUNCTION));
+    }
+
+    // Symbols are listed in the order they are declared
+    symbols.sort_by_key(|symbol| symbol.selection_range.start);
+    symbols
+}
+
+fn function_symbol(source: &str, function: &NoirFunction, kind: SymbolKind) -> DocumentSymbol {
+    let detail = match &function.def.attribute {
+        Some(Attribute::Test(_)) => Some("test"),
+        _ => None,
+    };
+    symbol(source, &function.def.name, function.def.span, kind, detail)
+}
+
+/// Creates the symbol for a declaration with nested declarations, such as a module. These have
+/// no span of their own, so the symbol spans from its name to the end of its last child.
+fn parent_symbol(
+    source: &str,
+    name: &Ident,
+    kind: SymbolKind,
+    detail: Option<&str>,
+    children: Vec<DocumentSymbol>,
+) -> DocumentSymbol {
+    let mut symbol = symbol(source, name, name.span(), kind, detail);
+    if let Some(end) = children.iter().map(|child| child.range.end).max() {
+        symbol.range.end = symbol.range.end.max(end);
+    }
+    symbol.children = Some(children);
+    symbol
+}
+
+fn symbol(
+    source: &str,
+    name: &Ident,
+    span: Span,
+    kind: SymbolKind,
+    detail: Option<&str>,
+) -> DocumentSymbol {
+    // `deprecated` has been replaced by `tags` but must still be given
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name: name.0.contents.clone(),
+        detail: detail.map(str::to_owned),
+        kind,
+        tags: None,
+        deprecated: None,
+        range: span_to_range(source, span),
+        selection_range: span_to_range(source, name.span()),
+        children: None,
+    }
+}
+
+/// Adds the symbol and its children to `symbols` if their names fuzzily match `query`, naming
+/// the symbol each is nested within as its container.
+fn flatten_symbol(
+    symbol: DocumentSymbol,
+    container_name: Option<String>,
+    uri: &Url,
+    query: &str,
+    symbols: &mut Vec<SymbolInformation>,
+) {
+    if fuzzy_match(&symbol.name, query) {
+        #[allow(deprecated)]
+        symbols.push(SymbolInformation {
+            name: symbol.name.clone(),
+            kind: symbol.kind,
+            tags: None,
+            deprecated: None,
+            location: Location { uri: uri.clone(), range: symbol.selection_range },
+            container_name,
+        });
+    }
+
+    for child in symbol.children.into_iter().flatten() {
+        flatten_symbol(child, Some(symbol.name.clone()), uri, query, symbols);
+    }
+}
+
+/// Returns true if each character of `query` appears in `name` in the same order, ignoring case.
+fn fuzzy_match(name: &str, query: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+    query.chars().flat_map(char::to_lowercase).all(|c| name.any(|n| n == c))
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::SymbolKind;
+
+    use super::{document_symbols, fuzzy_match};
+
+    #[test]
+    fn outlines_documents() {
+        let source = "
+            struct Point { x: Field, y: Field }
+
+            impl Point {
+                fn new() -> Self { Point { x: 0, y: 0 } }
+            }
+
+            contract Token {
+                global SUPPLY = 100;
+
+                fn mint() {}
+            }
+
+            fn main() {}
+
+            #[test]
+            fn test_main() {}
+        ";
+
+        let symbols = document_symbols(source);
+        let outline: Vec<_> =
+            symbols.iter().map(|symbol| (symbol.name.as_str(), symbol.kind)).collect();
+        assert_eq!(
+            outline,
+            vec![
+                ("Point", SymbolKind::STRUCT),
+                ("impl Point", SymbolKind::OBJECT),
+                ("Token", SymbolKind::NAMESPACE),
+                ("main", SymbolKind::FUNCTION),
+                ("test_main", SymbolKind::FUNCTION),
+            ]
+        );
+
+        let contract_children = symbols[2].children.as_ref().unwrap();
+        let contract_outline: Vec<_> =
+            contract_children.iter().map(|symbol| symbol.name.as_str()).collect();
+        assert_eq!(contract_outline, vec!["SUPPLY", "mint"]);
+        assert!(symbols[2].range.end >= contract_children[1].range.end);
+
+        assert_eq!(symbols[0].children.as_ref().unwrap().len(), 2);
+        assert_eq!(symbols[4].detail.as_deref(), Some("test"));
+    }
+
+    #[test]
+    fn fuzzy_matches_names() {
+        assert!(fuzzy_match("get_all_test_functions", "gatf"));
+        assert!(fuzzy_match("StructType", "sttype"));
+        assert!(fuzzy_match("anything", ""));
+        assert!(!fuzzy_match("main", "mian"));
+    }
+}
@@ -0,0 +1,49 @@
+use fm::{FileId, FileManager};
+use noirc_errors::debug_info::DebugInfo;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
+
+/// `DebugArtifact` relates the opcodes of a program, or of each function of a contract, back to
+/// the source code they were compiled from.
+///
+/// The source of each file referred to is included so that the artifact can be used without
+/// access to the project it was compiled from.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DebugArtifact {
+    /// The debug information of each circuit, in the order the circuits appear in the
+    /// corresponding build artifact.
+    pub debug_symbols: Vec<DebugInfo>,
+    pub file_map: BTreeMap<FileId, DebugFile>,
+}
+
+/// A source file referred to by the locations within a `DebugArtifact`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DebugFile {
+    pub path: PathBuf,
+    pub source: String,
+}
+
+impl DebugArtifact {
+    pub fn new(debug_symbols: Vec<DebugInfo>, file_manager: &FileManager) -> Self {
+        let files: BTreeSet<FileId> = debug_symbols
+            .iter()
+            .flat_map(|debug_info| debug_info.locations.values().flatten())
+            .map(|location| location.file)
+            .collect();
+
+        let file_map = files
+            .into_iter()
+            .map(|file_id| {
+                let file = file_manager.fetch_file(file_id);
+                let debug_file =
+                    DebugFile { path: file.path().to_path_buf(), source: file.source().to_owned() };
+                (file_id, debug_file)
+            })
+            .collect();
+
+        DebugArtifact { debug_symbols, file_map }
+    }
+}
@@ -8,6 +8,7 @@ use acvm::acir::circuit::Circuit;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 pub mod contract;
+pub mod debug;
 pub mod program;
 
 // TODO: move these down into ACVM.
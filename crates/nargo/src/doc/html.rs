@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use noirc_frontend::node_interner::StructId;
+
+use super::{
+    doc_lines, function_signature, global_signature, root_prefix, struct_pages, struct_signature,
+    summary, type_to_string, CrateDoc, FunctionDoc, ModuleDoc, SignatureFormat, StructDoc,
+};
+
+const STYLE: &str =
+    "body { font-family: sans-serif; max-width: 60em; margin: 0 auto; padding: 1em; }
+nav { margin-bottom: 1em; }
+pre { background: #f5f5f5; padding: 0.75em; overflow-x: auto; }
+code { font-family: monospace; }
+.item { margin-bottom: 1.5em; }
+dt { margin-top: 0.5em; }
+";
+
+/// Renders the documentation of the given crates as a static HTML site, returning the path of
+/// each file relative to the root of the site along with its contents.
+pub fn render_html(crates: &[CrateDoc]) -> Vec<(PathBuf, String)> {
+    let struct_pages = struct_pages(crates);
+    let mut files = vec![(PathBuf::from("style.css"), STYLE.to_owned())];
+
+    let mut body = String::from("<h1>Crates</h1>\n<dl>\n");
+    for krate in crates {
+        let link = format!("{}/index.html", krate.name);
+        body.push_str(&list_entry(&link, &krate.name, &krate.root.doc_comments));
+    }
+    body.push_str("</dl>\n");
+    files.push((PathBuf::from("index.html"), page("Crates", 0, String::new(), &body)));
+
+    for krate in crates {
+        render_module(&krate.root, &struct_pages, &mut files);
+    }
+    files
+}
+
+fn render_module(
+    module: &ModuleDoc,
+    struct_pages: &HashMap<StructId, String>,
+    files: &mut Vec<(PathBuf, String)>,
+) {
+    let depth = module.path.len();
+    let format = HtmlFormat { struct_pages, depth };
+    let title = module.path.join("::");
+
+    let kind = match (depth, module.is_contract) {
+        (_, true) => "Contract",
+        (1, false) => "Crate",
+        _ => "Module",
+    };
+    let mut body = format!("<h1>{kind} <code>{}</code></h1>\n", escape(&title));
+    body.push_str(&doc_html(&module.doc_comments));
+
+    if !module.modules.is_empty() {
+        body.push_str("<h2>Modules</h2>\n<dl>\n");
+        for child in &module.modules {
+            let name = child.path.last().expect("modules are within a crate");
+            let link = format!("{name}/index.html");
+            body.push_str(&list_entry(&link, name, &child.doc_comments));
+        }
+        body.push_str("</dl>\n");
+    }
+
+    if !module.structs.is_empty() {
+        body.push_str("<h2>Structs</h2>\n<dl>\n");
+        for struct_doc in &module.structs {
+            let link = format!("struct.{}.html", struct_doc.name);
+            body.push_str(&list_entry(&link, &struct_doc.name, &struct_doc.doc_comments));
+        }
+        body.push_str("</dl>\n");
+    }
+
+    if !module.globals.is_empty() {
+        body.push_str("<h2>Globals</h2>\n");
+        for global in &module.globals {
+            let id = format!("global.{}", global.name);
+            body.push_str(&item(&id, &global_signature(global, &format), &global.doc_comments));
+        }
+    }
+
+    if !module.functions.is_empty() {
+        body.push_str("<h2>Functions</h2>\n");
+        for function in &module.functions {
+            body.push_str(&function_item("fn", function, &format));
+        }
+    }
+
+    let path = PathBuf::from_iter(&module.path).join("index.html");
+    let nav = breadcrumbs(&module.path, depth, false);
+    files.push((path, page(&title, depth, nav, &body)));
+
+    for struct_doc in &module.structs {
+        render_struct(module, struct_doc, &format, files);
+    }
+    for child in &module.modules {
+        render_module(child, struct_pages, files);
+    }
+}
+
+fn render_struct(
+    module: &ModuleDoc,
+    struct_doc: &StructDoc,
+    format: &HtmlFormat,
+    files: &mut Vec<(PathBuf, String)>,
+) {
+    let title = format!("{}::{}", module.path.join("::"), struct_doc.name);
+    let mut body = format!("<h1>Struct <code>{}</code></h1>\n", escape(&title));
+    body.push_str(&format!("<pre><code>{}</code></pre>\n", struct_signature(struct_doc, format)));
+    body.push_str(&doc_html(&struct_doc.doc_comments));
+
+    if !struct_doc.fields.is_empty() {
+        body.push_str("<h2>Fields</h2>\n");
+        for field in &struct_doc.fields {
+            let id = format!("field.{}", field.name);
+            let typ = type_to_string(&field.typ, format);
+            let signature = format!("{}: {typ}", escape(&field.name));
+            body.push_str(&item(&id, &signature, &field.doc_comments));
+        }
+    }
+
+    if !struct_doc.methods.is_empty() {
+        body.push_str("<h2>Methods</h2>\n");
+        for method in &struct_doc.methods {
+            body.push_str(&function_item("method", method, format));
+        }
+    }
+
+    let path = PathBuf::from_iter(&module.path).join(format!("struct.{}.html", struct_doc.name));
+    let nav = breadcrumbs(&module.path, format.depth, true);
+    files.push((path, page(&title, format.depth, nav, &body)));
+}
+
+/// Links struct names to the pages documenting them, from a page in the module at `depth`.
+struct HtmlFormat<'a> {
+    struct_pages: &'a HashMap<StructId, String>,
+    depth: usize,
+}
+
+impl SignatureFormat for HtmlFormat<'_> {
+    fn text(&self, text: &str) -> String {
+        escape(text)
+    }
+
+    fn struct_name(&self, id: StructId, name: &str) -> String {
+        match self.struct_pages.get(&id) {
+            Some(page) => {
+                format!("<a href=\"{}{page}.html\">{}</a>", root_prefix(self.depth), escape(name))
+            }
+            None => escape(name),
+        }
+    }
+}
+
+fn page(title: &str, depth: usize, nav: String, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>{}</title>
+<link rel=\"stylesheet\" href=\"{}style.css\">
+</head>
+<body>
+<nav>{nav}</nav>
+<main>
+{body}</main>
+</body>
+</html>
+",
+        escape(title),
+        root_prefix(depth)
+    )
+}
+
+/// Returns links to the list of crates and to each module on the path to a page. The page is
+/// either the page of the last module on the path, or a page next to it.
+fn breadcrumbs(path: &[String], depth: usize, link_last: bool) -> String {
+    let mut links = vec![format!("<a href=\"{}index.html\">Crates</a>", root_prefix(depth))];
+    for (index, name) in path.iter().enumerate() {
+        if index + 1 == path.len() && !link_last {
+            links.push(escape(name));
+        } else {
+            let prefix = root_prefix(path.len() - index - 1);
+            links.push(format!("<a href=\"{prefix}index.html\">{}</a>", escape(name)));
+        }
+    }
+    links.join(" / ")
+}
+
+fn list_entry(link: &str, name: &str, doc_comments: &[String]) -> String {
+    format!(
+        "<dt><a href=\"{link}\"><code>{}</code></a></dt>\n<dd>{}</dd>\n",
+        escape(name),
+        inline_html(&summary(doc_comments))
+    )
+}
+
+fn function_item(kind: &str, function: &FunctionDoc, format: &HtmlFormat) -> String {
+    let id = format!("{kind}.{}", function.name);
+    item(&id, &function_signature(function, format), &function.doc_comments)
+}
+
+fn item(id: &str, signature: &str, doc_comments: &[String]) -> String {
+    format!(
+        "<div class=\"item\" id=\"{}\">\n<pre><code>{signature}</code></pre>\n{}</div>\n",
+        escape(id),
+        doc_html(doc_comments)
+    )
+}
+
+/// Renders the Markdown of a doc comment, supporting paragraphs, fenced code blocks and inline
+/// code. Any other Markdown is shown as written.
+fn doc_html(doc_comments: &[String]) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code_block: Option<Vec<&str>> = None;
+
+    for line in doc_lines(doc_comments) {
+        let is_fence = line.trim_start().starts_with("```");
+        match code_block.take() {
+            Some(code) if is_fence => html.push_str(&code_block_html(&code)),
+            Some(mut code) => {
+                code.push(line);
+                code_block = Some(code);
+            }
+            None if is_fence => {
+                push_paragraph(&mut html, &mut paragraph);
+                code_block = Some(Vec::new());
+            }
+            None if line.trim().is_empty() => push_paragraph(&mut html, &mut paragraph),
+            None => paragraph.push(line.trim()),
+        }
+    }
+
+    // An unclosed code block runs to the end of the doc comment
+    if let Some(code) = code_block {
+        html.push_str(&code_block_html(&code));
+    }
+    push_paragraph(&mut html, &mut paragraph);
+    html
+}
+
+fn code_block_html(lines: &[&str]) -> String {
+    format!("<pre><code>{}</code></pre>\n", escape(&lines.join("\n")))
+}
+
+fn push_paragraph(html: &mut String, paragraph: &mut Vec<&str>) {
+    if !paragraph.is_empty() {
+        html.push_str(&format!("<p>{}</p>\n", inline_html(&paragraph.join(" "))));
+        paragraph.clear();
+    }
+}
+
+/// Escapes a line of Markdown, turning the text between each pair of backticks into code.
+fn inline_html(text: &str) -> String {
+    let parts: Vec<_> = text.split('`').collect();
+    if parts.len() % 2 == 0 {
+        // The backticks aren't paired, so they don't delimit any code
+        return escape(text);
+    }
+
+    let parts = parts.iter().enumerate().map(|(index, part)| {
+        if index % 2 == 1 {
+            format!("<code>{}</code>", escape(part))
+        } else {
+            escape(part)
+        }
+    });
+    parts.collect()
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::doc_html;
+
+    #[test]
+    fn renders_doc_comments() {
+        let doc_comments = vec![
+            " Returns `a < b`.".to_owned(),
+            "".to_owned(),
+            " ```".to_owned(),
+            " assert(lt(1, 2));".to_owned(),
+            " ```".to_owned(),
+        ];
+        assert_eq!(
+            doc_html(&doc_comments),
+            "<p>Returns <code>a &lt; b</code>.</p>\n<pre><code>assert(lt(1, 2));</code></pre>\n"
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use noirc_frontend::node_interner::StructId;
+
+use super::{
+    doc_lines, function_signature, global_signature, root_prefix, struct_pages, struct_signature,
+    summary, type_to_string, CrateDoc, FunctionDoc, ModuleDoc, SignatureFormat, StructDoc,
+};
+
+/// Renders the documentation of the given crates as Markdown files, returning the path of each
+/// file relative to the root of the documentation along with its contents.
+pub fn render_markdown(crates: &[CrateDoc]) -> Vec<(PathBuf, String)> {
+    let struct_pages = struct_pages(crates);
+
+    let mut index = String::from("# Crates\n\n");
+    for krate in crates {
+        let link = format!("{}/index.md", krate.name);
+        index.push_str(&list_entry(&link, &krate.name, &krate.root.doc_comments));
+    }
+    let mut files = vec![(PathBuf::from("index.md"), index)];
+
+    for krate in crates {
+        render_module(&krate.root, &struct_pages, &mut files);
+    }
+    files
+}
+
+fn render_module(
+    module: &ModuleDoc,
+    struct_pages: &HashMap<StructId, String>,
+    files: &mut Vec<(PathBuf, String)>,
+) {
+    let depth = module.path.len();
+    let kind = match (depth, module.is_contract) {
+        (_, true) => "Contract",
+        (1, false) => "Crate",
+        _ => "Module",
+    };
+    let mut page = format!("# {kind} `{}`\n\n", module.path.join("::"));
+    page.push_str(&doc_markdown(&module.doc_comments));
+
+    if !module.modules.is_empty() {
+        page.push_str("## Modules\n\n");
+        for child in &module.modules {
+            let name = child.path.last().expect("modules are within a crate");
+            let link = format!("{name}/index.md");
+            page.push_str(&list_entry(&link, name, &child.doc_comments));
+        }
+        page.push('\n');
+    }
+
+    if !module.structs.is_empty() {
+        page.push_str("## Structs\n\n");
+        for struct_doc in &module.structs {
+            let link = format!("struct.{}.md", struct_doc.name);
+            page.push_str(&list_entry(&link, &struct_doc.name, &struct_doc.doc_comments));
+        }
+        page.push('\n');
+    }
+
+    if !module.globals.is_empty() {
+        page.push_str("## Globals\n\n");
+        for global in &module.globals {
+            let signature = global_signature(global, &PlainFormat);
+            page.push_str(&item(&global.name, &signature, &global.doc_comments));
+        }
+    }
+
+    if !module.functions.is_empty() {
+        page.push_str("## Functions\n\n");
+        for function in &module.functions {
+            page.push_str(&function_item(function));
+        }
+    }
+
+    files.push((PathBuf::from_iter(&module.path).join("index.md"), page));
+
+    let format = MarkdownFormat { struct_pages, depth };
+    for struct_doc in &module.structs {
+        render_struct(module, struct_doc, &format, files);
+    }
+    for child in &module.modules {
+        render_module(child, struct_pages, files);
+    }
+}
+
+fn render_struct(
+    module: &ModuleDoc,
+    struct_doc: &StructDoc,
+    format: &MarkdownFormat,
+    files: &mut Vec<(PathBuf, String)>,
+) {
+    let mut page = format!("# Struct `{}::{}`\n\n", module.path.join("::"), struct_doc.name);
+    page.push_str(&code_block(&struct_signature(struct_doc, &PlainFormat)));
+    page.push_str(&doc_markdown(&struct_doc.doc_comments));
+
+    if !struct_doc.fields.is_empty() {
+        page.push_str("## Fields\n\n");
+        for field in &struct_doc.fields {
+            // Types are written outside of a code block so that the structs within them link
+            let typ = type_to_string(&field.typ, format);
+            page.push_str(&format!("### `{}`: {typ}\n\n", field.name));
+            page.push_str(&doc_markdown(&field.doc_comments));
+        }
+    }
+
+    if !struct_doc.methods.is_empty() {
+        page.push_str("## Methods\n\n");
+        for method in &struct_doc.methods {
+            page.push_str(&function_item(method));
+        }
+    }
+
+    let path = PathBuf::from_iter(&module.path).join(format!("struct.{}.md", struct_doc.name));
+    files.push((path, page));
+}
+
+/// Writes signatures as they are written in the source, for code blocks.
+struct PlainFormat;
+
+impl SignatureFormat for PlainFormat {
+    fn text(&self, text: &str) -> String {
+        text.to_owned()
+    }
+
+    fn struct_name(&self, _id: StructId, name: &str) -> String {
+        name.to_owned()
+    }
+}
+
+/// Links struct names to the pages documenting them, from a page in the module at `depth`.
+struct MarkdownFormat<'a> {
+    struct_pages: &'a HashMap<StructId, String>,
+    depth: usize,
+}
+
+impl SignatureFormat for MarkdownFormat<'_> {
+    fn text(&self, text: &str) -> String {
+        text.to_owned()
+    }
+
+    fn struct_name(&self, id: StructId, name: &str) -> String {
+        match self.struct_pages.get(&id) {
+            Some(page) => format!("[{name}]({}{page}.md)", root_prefix(self.depth)),
+            None => name.to_owned(),
+        }
+    }
+}
+
+fn list_entry(link: &str, name: &str, doc_comments: &[String]) -> String {
+    let summary = summary(doc_comments);
+    if summary.is_empty() {
+        format!("- [`{name}`]({link})\n")
+    } else {
+        format!("- [`{name}`]({link}): {summary}\n")
+    }
+}
+
+fn function_item(function: &FunctionDoc) -> String {
+    item(&function.name, &function_signature(function, &PlainFormat), &function.doc_comments)
+}
+
+fn item(name: &str, signature: &str, doc_comments: &[String]) -> String {
+    format!("### `{name}`\n\n{}{}", code_block(signature), doc_markdown(doc_comments))
+}
+
+fn code_block(code: &str) -> String {
+    format!("```noir\n{code}\n```\n\n")
+}
+
+/// Doc comments are already written in Markdown, so they only need their lines joining.
+fn doc_markdown(doc_comments: &[String]) -> String {
+    if doc_comments.is_empty() {
+        return String::new();
+    }
+    let lines: Vec<_> = doc_lines(doc_comments).collect();
+    format!("{}\n\n", lines.join("\n").trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use acvm::Language;
+    use noirc_driver::Driver;
+    use noirc_frontend::graph::CrateType;
+
+    use super::render_markdown;
+    use crate::doc::collect_docs;
+
+    const SOURCE: &str = "mod shapes;
+
+/// Returns the point at the origin.
+fn origin<T>(zero: T) -> shapes::Point<T> {
+    shapes::Point { x: zero, y: zero }
+}
+
+mod lines {
+    /// A line between two points.
+    struct Line {
+        start: crate::shapes::Point<Field>,
+        end: crate::shapes::Point<Field>,
+    }
+}
+";
+
+    const SHAPES: &str = "/// A point on a curve.
+struct Point<T> {
+    /// The horizontal coordinate.
+    x: T,
+    y: T,
+}
+";
+
+    #[test]
+    fn renders_signatures_and_links() {
+        let root = Path::new("/docs/src/lib.nr");
+        let mut driver = Driver::new(&Language::R1CS, Box::new(|_| true));
+        driver.add_file_overlay(root.to_path_buf(), SOURCE.to_owned());
+        driver.add_file_overlay(PathBuf::from("/docs/src/shapes.nr"), SHAPES.to_owned());
+        driver.create_local_crate(root, CrateType::Library);
+        driver.check_crate(false).unwrap();
+
+        let files = render_markdown(&collect_docs(&driver, "docs"));
+        let page = |path: &str| {
+            let (_, contents) = files.iter().find(|(file, _)| file == Path::new(path)).unwrap();
+            contents.as_str()
+        };
+
+        assert_eq!(page("index.md"), "# Crates\n\n- [`docs`](docs/index.md)\n");
+        assert_eq!(
+            page("docs/index.md"),
+            "# Crate `docs`\n\n\
+             ## Modules\n\n\
+             - [`lines`](lines/index.md)\n\
+             - [`shapes`](shapes/index.md)\n\n\
+             ## Functions\n\n\
+             ### `origin`\n\n\
+             ```noir\nfn origin<T>(zero: T) -> Point<T>\n```\n\n\
+             Returns the point at the origin.\n\n"
+        );
+        assert_eq!(
+            page("docs/shapes/struct.Point.md"),
+            "# Struct `docs::shapes::Point`\n\n\
+             ```noir\nstruct Point<T> {\n    x: T,\n    y: T,\n}\n```\n\n\
+             A point on a curve.\n\n\
+             ## Fields\n\n\
+             ### `x`: T\n\n\
+             The horizontal coordinate.\n\n\
+             ### `y`: T\n\n"
+        );
+        // Structs in field types link to their page, relative to the page of the field's struct
+        assert_eq!(
+            page("docs/lines/struct.Line.md"),
+            "# Struct `docs::lines::Line`\n\n\
+             ```noir\nstruct Line {\n    start: Point<Field>,\n    end: Point<Field>,\n}\n```\n\n\
+             A line between two points.\n\n\
+             ## Fields\n\n\
+             ### `start`: [Point](../../docs/shapes/struct.Point.md)<Field>\n\n\
+             ### `end`: [Point](../../docs/shapes/struct.Point.md)<Field>\n\n"
+        );
+    }
+}
@@ -0,0 +1,407 @@
+//! Generates the documentation of a package and of every crate it depends on, including the
+//! standard library, from the doc comments written on their items.
+//!
+//! The items of each checked crate are first collected into a [`CrateDoc`], which is then
+//! rendered as either a static HTML site or a set of Markdown files. Both lay the documentation
+//! out the same way: a page for each module, at `<crate>/<module path>/index`, and a page for
+//! each struct next to the page of the module it is declared in.
+
+mod html;
+mod markdown;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+
+use noirc_abi::{AbiDistinctness, AbiVisibility};
+use noirc_driver::Driver;
+use noirc_frontend::{
+    hir::def_map::{CrateDefMap, LocalModuleId, ModuleDefId, ModuleId},
+    hir_def::stmt::HirPattern,
+    node_interner::{FuncId, NodeInterner, ReferenceId, StmtId, StructId},
+    token::Attribute,
+    ContractFunctionType, Type, TypeBinding,
+};
+
+pub use self::html::render_html;
+pub use self::markdown::render_markdown;
+
+/// The documentation of a crate. The crates it depends on are documented separately.
+pub struct CrateDoc {
+    pub name: String,
+    pub root: ModuleDoc,
+}
+
+pub struct ModuleDoc {
+    /// The path to this module, starting with the name of its crate.
+    pub path: Vec<String>,
+    pub is_contract: bool,
+    pub doc_comments: Vec<String>,
+    pub modules: Vec<ModuleDoc>,
+    pub structs: Vec<StructDoc>,
+    pub globals: Vec<GlobalDoc>,
+    pub functions: Vec<FunctionDoc>,
+}
+
+pub struct StructDoc {
+    pub id: StructId,
+    pub name: String,
+    pub generics: Vec<String>,
+    pub fields: Vec<FieldDoc>,
+    pub methods: Vec<FunctionDoc>,
+    pub doc_comments: Vec<String>,
+}
+
+pub struct FieldDoc {
+    pub name: String,
+    pub typ: Type,
+    pub doc_comments: Vec<String>,
+}
+
+pub struct GlobalDoc {
+    pub name: String,
+    pub typ: Type,
+    pub doc_comments: Vec<String>,
+}
+
+pub struct FunctionDoc {
+    pub name: String,
+    /// The keywords written before `fn`, such as `unconstrained`.
+    pub modifiers: Vec<&'static str>,
+    pub generics: Vec<String>,
+    pub parameters: Vec<ParameterDoc>,
+    /// The keywords written before the return type, such as `pub`.
+    pub return_modifiers: Vec<&'static str>,
+    pub return_type: Type,
+    pub doc_comments: Vec<String>,
+}
+
+pub struct ParameterDoc {
+    pub pattern: String,
+    pub is_public: bool,
+    pub typ: Type,
+}
+
+/// Collects the documentation of the local crate, which is given the name `name`, and of every
+/// crate it depends on directly or indirectly.
+///
+/// Dependencies are documented under the name they were first depended upon with. The crates
+/// must already have been checked so that the types of their globals are known.
+pub fn collect_docs(driver: &Driver, name: &str) -> Vec<CrateDoc> {
+    let context = driver.context();
+    let interner = &context.def_interner;
+
+    let mut crates = Vec::new();
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([(driver.local_crate(), name.to_owned())]);
+    while let Some((crate_id, name)) = queue.pop_front() {
+        if !visited.insert(crate_id) {
+            continue;
+        }
+        let def_map = match context.def_map(crate_id) {
+            Some(def_map) => def_map,
+            None => continue,
+        };
+
+        let mut dependencies: Vec<_> = def_map.extern_prelude().iter().collect();
+        dependencies.sort_by_key(|(name, _)| *name);
+        queue.extend(dependencies.into_iter().map(|(name, root)| (root.krate, name.clone())));
+
+        let root = collect_module(interner, def_map, def_map.root(), vec![name.clone()]);
+        crates.push(CrateDoc { name, root });
+    }
+    crates
+}
+
+fn collect_module(
+    interner: &NodeInterner,
+    def_map: &CrateDefMap,
+    local_id: LocalModuleId,
+    path: Vec<String>,
+) -> ModuleDoc {
+    let module = &def_map.modules()[local_id.0];
+    let id = ModuleId { krate: def_map.krate(), local_id };
+
+    let mut doc = ModuleDoc {
+        path,
+        is_contract: module.is_contract,
+        doc_comments: doc_comments(interner, ReferenceId::Module(id)),
+        modules: Vec::new(),
+        structs: Vec::new(),
+        globals: Vec::new(),
+        functions: Vec::new(),
+    };
+
+    let definitions = module.definitions();
+    for (name, (item, _)) in definitions.types().iter().chain(definitions.values()) {
+        match *item {
+            ModuleDefId::ModuleId(child) => {
+                let mut path = doc.path.clone();
+                path.push(name.0.contents.clone());
+                doc.modules.push(collect_module(interner, def_map, child.local_id, path));
+            }
+            ModuleDefId::TypeId(id) => doc.structs.push(collect_struct(interner, id)),
+            ModuleDefId::FunctionId(id) => doc.functions.extend(collect_function(interner, id)),
+            ModuleDefId::GlobalId(id) => doc.globals.extend(collect_global(interner, id)),
+        }
+    }
+
+    // Items are kept in hash maps, so they are sorted to keep the documentation stable
+    doc.modules.sort_by(|first, second| first.path.cmp(&second.path));
+    doc.structs.sort_by(|first, second| first.name.cmp(&second.name));
+    doc.globals.sort_by(|first, second| first.name.cmp(&second.name));
+    doc.functions.sort_by(|first, second| first.name.cmp(&second.name));
+    doc
+}
+
+fn collect_struct(interner: &NodeInterner, id: StructId) -> StructDoc {
+    let shared_struct = interner.get_struct(id);
+    let struct_type = shared_struct.borrow();
+
+    // Fields and methods are shown with the struct's generics as they were declared
+    let generics: Vec<_> = struct_type
+        .generics
+        .iter()
+        .zip(&struct_type.generic_names)
+        .map(|((_, var), name)| Type::NamedGeneric(var.clone(), Rc::new(name.clone())))
+        .collect();
+
+    let fields = struct_type.get_fields(&generics).into_iter().enumerate();
+    let fields = fields.map(|(index, (name, typ))| {
+        let doc_comments = doc_comments(interner, ReferenceId::StructMember(id, index));
+        FieldDoc { name, typ, doc_comments }
+    });
+
+    let methods = interner.methods_of_type(&Type::Struct(shared_struct.clone(), generics));
+    let mut methods: Vec<_> =
+        methods.into_iter().filter_map(|(_, method)| collect_function(interner, method)).collect();
+    methods.sort_by(|first, second| first.name.cmp(&second.name));
+
+    StructDoc {
+        id,
+        name: struct_type.name.0.contents.clone(),
+        generics: struct_type.generic_names.clone(),
+        fields: fields.collect(),
+        methods,
+        doc_comments: doc_comments(interner, ReferenceId::Struct(id)),
+    }
+}
+
+/// Returns the documentation of a function, or None for test functions as they aren't part of
+/// the API of their crate.
+fn collect_function(interner: &NodeInterner, id: FuncId) -> Option<FunctionDoc> {
+    let meta = interner.function_meta(&id);
+    if matches!(meta.attributes, Some(Attribute::Test(_))) {
+        return None;
+    }
+
+    let mut modifiers = Vec::new();
+    if meta.is_unconstrained {
+        modifiers.push("unconstrained");
+    }
+    if meta.contract_function_type == Some(ContractFunctionType::Open) {
+        modifiers.push("open");
+    }
+
+    let parameters = meta.parameters.iter().map(|parameter| ParameterDoc {
+        pattern: interner.pattern_to_string(&parameter.0),
+        is_public: parameter.2 == AbiVisibility::Public,
+        typ: parameter.1.clone(),
+    });
+
+    let mut return_modifiers = Vec::new();
+    if meta.return_distinctness == AbiDistinctness::Distinct {
+        return_modifiers.push("distinct");
+    }
+    if meta.return_visibility == AbiVisibility::Public {
+        return_modifiers.push("pub");
+    }
+
+    let definition_id = interner.function_definition_id(id);
+    Some(FunctionDoc {
+        name: interner.function_name(&id).to_owned(),
+        modifiers,
+        generics: meta.generic_names.clone(),
+        parameters: parameters.collect(),
+        return_modifiers,
+        return_type: meta.return_type().clone(),
+        doc_comments: doc_comments(interner, ReferenceId::Definition(definition_id)),
+    })
+}
+
+fn collect_global(interner: &NodeInterner, id: StmtId) -> Option<GlobalDoc> {
+    let ident = match interner.let_statement(&id).pattern {
+        HirPattern::Identifier(ident) => ident,
+        _ => return None,
+    };
+    Some(GlobalDoc {
+        name: interner.definition_name(ident.id).to_owned(),
+        typ: interner.id_type(ident.id),
+        doc_comments: doc_comments(interner, ReferenceId::Definition(ident.id)),
+    })
+}
+
+fn doc_comments(interner: &NodeInterner, id: ReferenceId) -> Vec<String> {
+    interner.doc_comments(id).map(<[String]>::to_vec).unwrap_or_default()
+}
+
+/// Returns the path of the page documenting each struct, relative to the root of the
+/// documentation and without an extension.
+fn struct_pages(crates: &[CrateDoc]) -> HashMap<StructId, String> {
+    fn add_module(module: &ModuleDoc, pages: &mut HashMap<StructId, String>) {
+        for struct_doc in &module.structs {
+            let page = format!("{}/struct.{}", module.path.join("/"), struct_doc.name);
+            pages.insert(struct_doc.id, page);
+        }
+        for child in &module.modules {
+            add_module(child, pages);
+        }
+    }
+
+    let mut pages = HashMap::new();
+    for krate in crates {
+        add_module(&krate.root, &mut pages);
+    }
+    pages
+}
+
+/// How the text and struct names within signatures are written by a renderer, so that it can
+/// escape the text and link each struct name to the page documenting it.
+trait SignatureFormat {
+    fn text(&self, text: &str) -> String;
+
+    fn struct_name(&self, id: StructId, name: &str) -> String;
+}
+
+fn type_to_string(typ: &Type, format: &impl SignatureFormat) -> String {
+    match typ {
+        Type::Struct(struct_type, args) => {
+            let struct_type = struct_type.borrow();
+            let name = format.struct_name(struct_type.id, &struct_type.name.0.contents);
+            if args.is_empty() {
+                name
+            } else {
+                let args: Vec<_> = args.iter().map(|arg| type_to_string(arg, format)).collect();
+                format!("{name}{}{}{}", format.text("<"), args.join(", "), format.text(">"))
+            }
+        }
+        Type::Array(length, element) => {
+            let element = type_to_string(element, format);
+            format!("[{element}; {}]", format.text(&length.to_string()))
+        }
+        Type::Tuple(elements) => {
+            let elements: Vec<_> = elements.iter().map(|typ| type_to_string(typ, format)).collect();
+            format!("({})", elements.join(", "))
+        }
+        Type::Function(args, ret) => {
+            let args: Vec<_> = args.iter().map(|arg| type_to_string(arg, format)).collect();
+            let ret = type_to_string(ret, format);
+            format!("fn({}){}{ret}", args.join(", "), format.text(" -> "))
+        }
+        Type::Vec(element) => {
+            let element = type_to_string(element, format);
+            format!("Vec{}{element}{}", format.text("<"), format.text(">"))
+        }
+        Type::Forall(_, typ) => type_to_string(typ, format),
+        Type::TypeVariable(binding) | Type::NamedGeneric(binding, _) => match &*binding.borrow() {
+            TypeBinding::Bound(typ) => type_to_string(typ, format),
+            TypeBinding::Unbound(_) => format.text(&typ.to_string()),
+        },
+        other => format.text(&other.to_string()),
+    }
+}
+
+fn generics_to_string(generics: &[String], format: &impl SignatureFormat) -> String {
+    if generics.is_empty() {
+        String::new()
+    } else {
+        format!("{}{}{}", format.text("<"), format.text(&generics.join(", ")), format.text(">"))
+    }
+}
+
+fn function_signature(function: &FunctionDoc, format: &impl SignatureFormat) -> String {
+    let mut signature = String::new();
+    for modifier in &function.modifiers {
+        signature.push_str(&format!("{modifier} "));
+    }
+    signature.push_str(&format!("fn {}", format.text(&function.name)));
+    signature.push_str(&generics_to_string(&function.generics, format));
+
+    let parameters: Vec<_> = function
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let visibility = if parameter.is_public { "pub " } else { "" };
+            let typ = type_to_string(&parameter.typ, format);
+            format!("{}: {visibility}{typ}", format.text(&parameter.pattern))
+        })
+        .collect();
+    signature.push_str(&format!("({})", parameters.join(", ")));
+
+    if function.return_type != Type::Unit {
+        signature.push_str(&format.text(" -> "));
+        for modifier in &function.return_modifiers {
+            signature.push_str(&format!("{modifier} "));
+        }
+        signature.push_str(&type_to_string(&function.return_type, format));
+    }
+    signature
+}
+
+fn struct_signature(struct_doc: &StructDoc, format: &impl SignatureFormat) -> String {
+    let generics = generics_to_string(&struct_doc.generics, format);
+    let mut signature = format!("struct {}{generics} {{", format.text(&struct_doc.name));
+    if struct_doc.fields.is_empty() {
+        signature.push('}');
+        return signature;
+    }
+
+    for field in &struct_doc.fields {
+        let typ = type_to_string(&field.typ, format);
+        signature.push_str(&format!("\n    {}: {typ},", format.text(&field.name)));
+    }
+    signature.push_str("\n}");
+    signature
+}
+
+fn global_signature(global: &GlobalDoc, format: &impl SignatureFormat) -> String {
+    format!("global {}: {}", format.text(&global.name), type_to_string(&global.typ, format))
+}
+
+/// Returns the lines of a doc comment without the space which usually follows the `///`.
+fn doc_lines(doc_comments: &[String]) -> impl Iterator<Item = &str> {
+    doc_comments.iter().map(|line| line.strip_prefix(' ').unwrap_or(line))
+}
+
+/// Returns the first paragraph of a doc comment on a single line, to summarize the item in the
+/// lists of the items of a module.
+fn summary(doc_comments: &[String]) -> String {
+    let lines = doc_lines(doc_comments).map(str::trim).skip_while(|line| line.is_empty());
+    let lines: Vec<_> = lines.take_while(|line| !line.is_empty()).collect();
+    lines.join(" ")
+}
+
+/// Returns the prefix of a relative link from a page in the module at the given depth, counting
+/// the crate's root module as one deep, back to the root of the documentation.
+fn root_prefix(depth: usize) -> String {
+    "../".repeat(depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{doc_lines, summary};
+
+    #[test]
+    fn summarizes_the_first_paragraph() {
+        let doc_comments = vec![
+            "".to_owned(),
+            " Adds two".to_owned(),
+            " numbers.".to_owned(),
+            "".to_owned(),
+            " Overflows are not checked.".to_owned(),
+        ];
+        assert_eq!(summary(&doc_comments), "Adds two numbers.");
+
+        let lines: Vec<_> = doc_lines(&doc_comments).collect();
+        assert_eq!(lines, vec!["", "Adds two", "numbers.", "", "Overflows are not checked."]);
+    }
+}
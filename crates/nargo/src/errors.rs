@@ -1,6 +1,8 @@
 use acvm::pwg::OpcodeResolutionError;
 use thiserror::Error;
 
+use crate::ops::ForeignCallError;
+
 #[derive(Debug, Error)]
 pub enum NargoError {
     /// Error while compiling Noir into ACIR.
@@ -10,4 +12,16 @@ pub enum NargoError {
     /// ACIR circuit solving error
     #[error(transparent)]
     SolvingError(#[from] OpcodeResolutionError),
+
+    /// The opcode at the given index within the circuit was not satisfied by the witness.
+    #[error("Failed constraint")]
+    UnsatisfiedConstraint { opcode_index: usize },
+
+    /// A foreign call made by the circuit couldn't be resolved.
+    #[error(transparent)]
+    ForeignCallError(#[from] ForeignCallError),
+
+    /// Random values can't be generated for the parameter with the given name when fuzzing.
+    #[error("Cannot generate random values for parameter `{0}`")]
+    UnfuzzableParameter(String),
 }
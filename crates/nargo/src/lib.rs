@@ -8,8 +8,11 @@
 //! Noir Package Manager abbreviated is npm, which is already taken.
 
 pub mod artifacts;
+pub mod doc;
 mod errors;
+pub mod lockfile;
 pub mod manifest;
 pub mod ops;
+pub mod workspace;
 
 pub use self::errors::NargoError;
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+
+use crate::manifest::{GitReference, InvalidPackageError};
+
+/// The version of the `Nargo.lock` format written by this version of nargo.
+pub const LOCKFILE_VERSION: u32 = 1;
+
+const LOCKFILE_HEADER: &str = "# This file is automatically generated by nargo.
+# It is not intended for manual editing.
+";
+
+/// The contents of a `Nargo.lock` file, which pins each git dependency of a package
+/// to the commit its tag, branch or rev resolved to when it was first fetched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+/// A git dependency pinned by `Nargo.lock`.
+///
+/// Packages are identified by their `source` and by whichever of `tag`, `branch` and `rev`
+/// they were depended upon with, the `name` being the one they were first depended upon with.
+/// Packages in different directories of the same repository share a single entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+    /// The URL of the git repository the package was fetched from
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    /// The hash of the commit the reference resolved to
+    pub commit: String,
+    /// The SHA-256 checksum of the package's files at that commit
+    pub checksum: String,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Lockfile { version: LOCKFILE_VERSION, packages: Vec::new() }
+    }
+}
+
+impl LockedPackage {
+    pub fn new(
+        name: String,
+        source: String,
+        reference: &GitReference,
+        commit: String,
+        checksum: String,
+    ) -> Self {
+        let (mut tag, mut branch, mut rev) = (None, None, None);
+        match reference {
+            GitReference::Tag(name) => tag = Some(name.clone()),
+            GitReference::Branch(name) => branch = Some(name.clone()),
+            GitReference::Rev(hash) => rev = Some(hash.clone()),
+            GitReference::DefaultBranch => (),
+        }
+        LockedPackage { name, source, tag, branch, rev, commit, checksum }
+    }
+
+    /// Returns the reference the package was depended upon with.
+    pub fn reference(&self) -> GitReference {
+        match (&self.tag, &self.branch, &self.rev) {
+            (Some(tag), _, _) => GitReference::Tag(tag.clone()),
+            (None, Some(branch), _) => GitReference::Branch(branch.clone()),
+            (None, None, Some(rev)) => GitReference::Rev(rev.clone()),
+            (None, None, None) => GitReference::DefaultBranch,
+        }
+    }
+}
+
+impl Lockfile {
+    pub fn from_toml_str(toml_as_string: &str) -> Result<Self, InvalidPackageError> {
+        let lockfile = toml::from_str::<Lockfile>(toml_as_string)
+            .map_err(InvalidPackageError::MalformedLockfile)?;
+        if lockfile.version != LOCKFILE_VERSION {
+            return Err(InvalidPackageError::UnsupportedLockfileVersion(lockfile.version));
+        }
+        Ok(lockfile)
+    }
+
+    /// Serializes the lockfile, sorting its packages so that the output is deterministic.
+    pub fn to_toml_string(&self) -> String {
+        let mut lockfile = self.clone();
+        lockfile.sort();
+        let toml = toml::to_string(&lockfile).expect("lockfile should serialize to TOML");
+        format!("{LOCKFILE_HEADER}\n{toml}")
+    }
+
+    /// Returns the pinned package fetched from `source` at `reference`, if any.
+    pub fn get(&self, source: &str, reference: &GitReference) -> Option<&LockedPackage> {
+        self.packages
+            .iter()
+            .find(|package| package.source == source && &package.reference() == reference)
+    }
+
+    /// Sorts the packages by name, then by source and reference.
+    pub fn sort(&mut self) {
+        self.packages.sort_by(|a, b| {
+            (&a.name, &a.source, &a.tag, &a.branch, &a.rev)
+                .cmp(&(&b.name, &b.source, &b.tag, &b.branch, &b.rev))
+        });
+    }
+}
+
+#[test]
+fn lockfile_round_trips() {
+    let lockfile = Lockfile {
+        version: LOCKFILE_VERSION,
+        packages: vec![
+            LockedPackage::new(
+                "rand".to_owned(),
+                "https://github.com/rust-lang-nursery/rand".to_owned(),
+                &GitReference::Tag("v0.1.0".to_owned()),
+                "1f2d3c4b5a69788796a5b4c3d2e1f0a9b8c7d6e5".to_owned(),
+                "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08".to_owned(),
+            ),
+            LockedPackage::new(
+                "merkle".to_owned(),
+                "https://github.com/noir-lang/libs".to_owned(),
+                &GitReference::Branch("main".to_owned()),
+                "4b825dc642cb6eb9a060e54bf8d69288fbee4904".to_owned(),
+                "60303ae22b998861bce3b28f33eec1be758a213c86c93c076dbe9f558c11c752".to_owned(),
+            ),
+        ],
+    };
+
+    let toml = lockfile.to_toml_string();
+    assert!(toml.starts_with(LOCKFILE_HEADER));
+    assert!(!toml.contains("rev ="));
+
+    let parsed = Lockfile::from_toml_str(&toml).unwrap();
+    assert_eq!(parsed.packages.len(), 2);
+    for package in &lockfile.packages {
+        assert_eq!(parsed.get(&package.source, &package.reference()), Some(package));
+    }
+}
+
+#[test]
+fn rejects_unsupported_lockfile_version() {
+    let src = r#"
+        version = 2
+    "#;
+
+    assert!(matches!(
+        Lockfile::from_toml_str(src),
+        Err(InvalidPackageError::UnsupportedLockfileVersion(2))
+    ));
+}
@@ -12,6 +12,22 @@ pub enum InvalidPackageError {
     #[error("Nargo.toml is badly formed, could not parse.\n\n {0}")]
     MalformedManifestFile(#[from] toml::de::Error),
 
+    /// Package lockfile is unreadable.
+    #[error("Nargo.lock is badly formed, could not parse.\n\n {0}")]
+    MalformedLockfile(toml::de::Error),
+
+    /// Package lockfile was written by a newer version of nargo.
+    #[error("Nargo.lock has version {0}, which this version of nargo does not support")]
+    UnsupportedLockfileVersion(u32),
+
+    /// Manifest describes a workspace where a package was expected.
+    #[error("{} is the manifest of a workspace. Run this command from within one of its packages.", .0.display())]
+    UnexpectedWorkspace(PathBuf),
+
+    /// Two packages in the same workspace have the same name.
+    #[error("workspace contains more than one package named `{0}`")]
+    DuplicatePackageName(String),
+
     /// Package does not contain Noir source files.
     #[error("cannot find src directory in path {}", .0.display())]
     NoSourceDir(PathBuf),
@@ -1,13 +1,41 @@
+use noirc_frontend::formatter;
 use serde::Deserialize;
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Component, Path, PathBuf},
+};
 
 mod errors;
 pub use self::errors::InvalidPackageError;
 
+/// The contents of a `Nargo.toml`, which describes either a single package
+/// or a workspace of several packages.
+#[derive(Debug, Clone)]
+pub enum Manifest {
+    Package(PackageManifest),
+    Workspace(WorkspaceManifest),
+}
+
+impl Manifest {
+    pub fn from_toml_str(toml_as_string: &str) -> Result<Self, InvalidPackageError> {
+        let toml = toml::from_str::<toml::Table>(toml_as_string)?;
+        let manifest = if toml.contains_key("workspace") {
+            Manifest::Workspace(toml::Value::Table(toml).try_into()?)
+        } else {
+            Manifest::Package(toml::Value::Table(toml).try_into()?)
+        };
+        Ok(manifest)
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct PackageManifest {
     pub package: PackageMetadata,
     pub dependencies: BTreeMap<String, Dependency>,
+    /// Options for `nargo fmt`, set in the `[fmt]` section.
+    #[serde(default)]
+    pub fmt: formatter::Config,
 }
 
 impl PackageManifest {
@@ -27,6 +55,8 @@ impl PackageManifest {
 #[allow(dead_code)]
 #[derive(Debug, Deserialize, Clone)]
 pub struct PackageMetadata {
+    /// The name of the package within its workspace, defaulting to the name of its directory
+    pub name: Option<String>,
     // Note: a package name is not needed unless there is a registry
     authors: Vec<String>,
     // If not compiler version is supplied, the latest is used
@@ -40,12 +70,132 @@ pub struct PackageMetadata {
 }
 
 #[derive(Debug, Deserialize, Clone)]
-#[serde(untagged)]
+pub struct WorkspaceManifest {
+    pub workspace: WorkspaceConfig,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct WorkspaceConfig {
+    /// The directories of the packages within the workspace, relative to its root
+    pub members: Vec<PathBuf>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(try_from = "RawDependency")]
 /// Enum representing the different types of ways to
 /// supply a source for the dependency
 pub enum Dependency {
-    Github { git: String, tag: String },
-    Path { path: String },
+    Github {
+        git: String,
+        reference: GitReference,
+        /// The directory of the package within the repository, if it isn't at its root
+        directory: Option<PathBuf>,
+    },
+    Path {
+        path: String,
+    },
+}
+
+/// Writes the dependency as it would appear in `Nargo.toml`.
+impl fmt::Display for Dependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dependency::Github { git, reference, directory } => {
+                write!(f, "{{ git = {git:?}")?;
+                match reference {
+                    GitReference::Tag(tag) => write!(f, ", tag = {tag:?}")?,
+                    GitReference::Branch(branch) => write!(f, ", branch = {branch:?}")?,
+                    GitReference::Rev(rev) => write!(f, ", rev = {rev:?}")?,
+                    GitReference::DefaultBranch => (),
+                }
+                if let Some(directory) = directory {
+                    write!(f, ", directory = {:?}", directory.display().to_string())?;
+                }
+                write!(f, " }}")
+            }
+            Dependency::Path { path } => write!(f, "{{ path = {path:?} }}"),
+        }
+    }
+}
+
+/// The commit of a git repository which a dependency refers to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GitReference {
+    Tag(String),
+    Branch(String),
+    /// A commit hash
+    Rev(String),
+    /// Whichever commit the repository's default branch points to
+    DefaultBranch,
+}
+
+impl fmt::Display for GitReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitReference::Tag(tag) => write!(f, "tag {tag}"),
+            GitReference::Branch(branch) => write!(f, "branch {branch}"),
+            GitReference::Rev(rev) => write!(f, "rev {rev}"),
+            GitReference::DefaultBranch => write!(f, "default branch"),
+        }
+    }
+}
+
+/// A dependency as written in `Nargo.toml`, before its keys are checked for consistency.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDependency {
+    Github {
+        git: String,
+        tag: Option<String>,
+        branch: Option<String>,
+        rev: Option<String>,
+        directory: Option<PathBuf>,
+    },
+    Path {
+        path: String,
+    },
+}
+
+impl TryFrom<RawDependency> for Dependency {
+    type Error = String;
+
+    fn try_from(dependency: RawDependency) -> Result<Self, Self::Error> {
+        let (git, tag, branch, rev, directory) = match dependency {
+            RawDependency::Github { git, tag, branch, rev, directory } => {
+                (git, tag, branch, rev, directory)
+            }
+            RawDependency::Path { path } => return Ok(Dependency::Path { path }),
+        };
+
+        let reference = match (tag, branch, rev) {
+            (Some(tag), None, None) => GitReference::Tag(tag),
+            (None, Some(branch), None) => GitReference::Branch(branch),
+            (None, None, Some(rev)) => GitReference::Rev(rev),
+            (None, None, None) => GitReference::DefaultBranch,
+            _ => {
+                return Err(format!(
+                    "dependency on {git} may only specify one of `tag`, `branch` and `rev`"
+                ))
+            }
+        };
+
+        if let Some(directory) = &directory {
+            if !is_within_repository(directory) {
+                return Err(format!(
+                    "directory {} of dependency on {git} must be within the repository",
+                    directory.display()
+                ));
+            }
+        }
+
+        Ok(Dependency::Github { git, reference, directory })
+    }
+}
+
+fn is_within_repository(directory: &Path) -> bool {
+    directory
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
 }
 
 #[test]
@@ -64,3 +214,89 @@ fn parse_standard_toml() {
 
     assert!(PackageManifest::from_toml_str(src).is_ok());
 }
+
+#[test]
+fn parse_git_references() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        tagged = { git = "https://github.com/noir-lang/libs", tag = "v0.1.0" }
+        branch = { git = "https://github.com/noir-lang/libs", branch = "main", directory = "crates/merkle" }
+        rev = { git = "https://github.com/noir-lang/libs", rev = "4b825dc642cb6eb9a060e54bf8d69288fbee4904" }
+        head = { git = "https://github.com/noir-lang/libs" }
+    "#;
+
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    let reference = |name: &str| match &manifest.dependencies[name] {
+        Dependency::Github { reference, .. } => reference.clone(),
+        Dependency::Path { .. } => panic!("expected a git dependency"),
+    };
+    assert_eq!(reference("tagged"), GitReference::Tag("v0.1.0".to_owned()));
+    assert_eq!(reference("branch"), GitReference::Branch("main".to_owned()));
+    assert_eq!(
+        reference("rev"),
+        GitReference::Rev("4b825dc642cb6eb9a060e54bf8d69288fbee4904".to_owned())
+    );
+    assert_eq!(reference("head"), GitReference::DefaultBranch);
+    assert!(matches!(
+        &manifest.dependencies["branch"],
+        Dependency::Github { directory: Some(directory), .. } if directory == Path::new("crates/merkle")
+    ));
+}
+
+#[test]
+fn reject_ambiguous_git_dependencies() {
+    let both = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        libs = { git = "https://github.com/noir-lang/libs", tag = "v0.1.0", branch = "main" }
+    "#;
+    assert!(PackageManifest::from_toml_str(both).is_err());
+
+    let outside = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        libs = { git = "https://github.com/noir-lang/libs", directory = "../other" }
+    "#;
+    assert!(PackageManifest::from_toml_str(outside).is_err());
+}
+
+#[test]
+fn parse_fmt_config() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+
+        [fmt]
+        max_width = 80
+    "#;
+
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    assert_eq!(manifest.fmt, formatter::Config { max_width: 80, tab_spaces: 4 });
+}
+
+#[test]
+fn parse_workspace_toml() {
+    let src = r#"
+        [workspace]
+        members = ["circuits/transfer", "libs/merkle"]
+    "#;
+
+    match Manifest::from_toml_str(src).unwrap() {
+        Manifest::Workspace(manifest) => {
+            assert_eq!(
+                manifest.workspace.members,
+                vec![PathBuf::from("circuits/transfer"), PathBuf::from("libs/merkle")]
+            );
+        }
+        Manifest::Package(_) => panic!("expected a workspace manifest"),
+    }
+}
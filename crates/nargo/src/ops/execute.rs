@@ -1,19 +1,152 @@
-use acvm::pwg::{solve, PartialWitnessGeneratorStatus};
-use acvm::PartialWitnessGenerator;
+use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput, Opcode, OracleData};
+use acvm::acir::native_types::Expression;
+use acvm::pwg::{solve, OpcodeResolutionError, PartialWitnessGeneratorStatus};
 use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap, pwg::block::Blocks};
+use acvm::{FieldElement, PartialWitnessGenerator};
 
 use crate::NargoError;
 
+use super::{ForeignCallError, ForeignCallResolver};
+
 pub fn execute_circuit(
     backend: &impl PartialWitnessGenerator,
     circuit: Circuit,
     mut initial_witness: WitnessMap,
+    foreign_calls: &mut impl ForeignCallResolver,
 ) -> Result<WitnessMap, NargoError> {
     let mut blocks = Blocks::default();
-    let solver_status = solve(backend, &mut initial_witness, &mut blocks, circuit.opcodes)?;
-    if matches!(solver_status, PartialWitnessGeneratorStatus::RequiresOracleData { .. }) {
-        todo!("Add oracle support to nargo execute")
+    let mut unsolved_opcodes = circuit.opcodes.clone();
+    loop {
+        let solver_status =
+            match solve(backend, &mut initial_witness, &mut blocks, unsolved_opcodes) {
+                Ok(solver_status) => solver_status,
+                Err(OpcodeResolutionError::UnsatisfiedConstrain) => {
+                    // The solver doesn't tell which opcode failed, but the witness it was solving
+                    // is left in place so the opcodes can be checked against it.
+                    let error = match find_unsatisfied_opcode(&circuit.opcodes, &initial_witness) {
+                        Some(opcode_index) => NargoError::UnsatisfiedConstraint { opcode_index },
+                        None => OpcodeResolutionError::UnsatisfiedConstrain.into(),
+                    };
+                    return Err(error);
+                }
+                Err(error) => return Err(error.into()),
+            };
+
+        match solver_status {
+            PartialWitnessGeneratorStatus::Solved => return Ok(initial_witness),
+            PartialWitnessGeneratorStatus::RequiresOracleData {
+                required_oracle_data,
+                unsolved_opcodes: remaining_opcodes,
+                unresolved_brillig_calls,
+            } => {
+                // The compiler doesn't generate foreign calls from Brillig code yet, so there is
+                // no way to resume the Brillig code waiting on them.
+                if !unresolved_brillig_calls.is_empty() {
+                    return Err(ForeignCallError::UnsupportedBrilligCall.into());
+                }
+
+                // Oracles are solved by handing them back to the solver with their outputs set.
+                unsolved_opcodes = Vec::with_capacity(required_oracle_data.len());
+                for oracle in required_oracle_data {
+                    let oracle = resolve_oracle(oracle, foreign_calls)?;
+                    unsolved_opcodes.push(Opcode::Oracle(oracle));
+                }
+                unsolved_opcodes.extend(remaining_opcodes);
+            }
+        }
+    }
+}
+
+/// Sets the output values of `oracle` to the result of calling it with its input values.
+fn resolve_oracle(
+    mut oracle: OracleData,
+    foreign_calls: &mut impl ForeignCallResolver,
+) -> Result<OracleData, ForeignCallError> {
+    let output_values = foreign_calls.resolve(&oracle.name, &oracle.input_values)?;
+    if output_values.len() != oracle.outputs.len() {
+        return Err(ForeignCallError::OutputCountMismatch {
+            name: oracle.name,
+            expected: oracle.outputs.len(),
+            found: output_values.len(),
+        });
+    }
+    oracle.output_values = output_values;
+    Ok(oracle)
+}
+
+/// Returns the index of the first opcode which is known not to hold for the partially solved
+/// `witness`. Only arithmetic opcodes and range constraints are checked.
+fn find_unsatisfied_opcode(opcodes: &[Opcode], witness: &WitnessMap) -> Option<usize> {
+    opcodes.iter().position(|opcode| match opcode {
+        Opcode::Arithmetic(expression) => {
+            evaluate(expression, witness).map_or(false, |value| !value.is_zero())
+        }
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+            input: FunctionInput { witness: input, num_bits },
+        }) => witness.get(input).map_or(false, |value| value.num_bits() > *num_bits),
+        _ => false,
+    })
+}
+
+/// Evaluates `expression`, if all of the witnesses it refers to are known.
+fn evaluate(expression: &Expression, witness: &WitnessMap) -> Option<FieldElement> {
+    let mut value = expression.q_c;
+    for (coefficient, lhs, rhs) in &expression.mul_terms {
+        value = value + *coefficient * *witness.get(lhs)? * *witness.get(rhs)?;
+    }
+    for (coefficient, term) in &expression.linear_combinations {
+        value = value + *coefficient * *witness.get(term)?;
     }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::{
+        circuit::{
+            opcodes::{BlackBoxFuncCall, FunctionInput},
+            Opcode,
+        },
+        native_types::{Expression, Witness, WitnessMap},
+    };
+    use acvm::FieldElement;
+
+    use super::find_unsatisfied_opcode;
 
-    Ok(initial_witness)
+    #[test]
+    fn finds_unsatisfied_opcodes() {
+        let mut witness = WitnessMap::new();
+        witness.insert(Witness(1), FieldElement::from(2_u128));
+        witness.insert(Witness(2), FieldElement::from(300_u128));
+
+        // w1 - 2 = 0 holds, w1 - w2 = 0 doesn't
+        let w1_is_two = Expression {
+            mul_terms: Vec::new(),
+            linear_combinations: vec![(FieldElement::one(), Witness(1))],
+            q_c: -FieldElement::from(2_u128),
+        };
+        let w1_is_w2 = Expression {
+            mul_terms: Vec::new(),
+            linear_combinations: vec![
+                (FieldElement::one(), Witness(1)),
+                (-FieldElement::one(), Witness(2)),
+            ],
+            q_c: FieldElement::zero(),
+        };
+        let range = |witness, num_bits| {
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+                input: FunctionInput { witness: Witness(witness), num_bits },
+            })
+        };
+
+        let opcodes = vec![Opcode::Arithmetic(w1_is_two.clone()), range(2, 16), range(2, 8)];
+        assert_eq!(find_unsatisfied_opcode(&opcodes, &witness), Some(2));
+
+        let opcodes = vec![Opcode::Arithmetic(w1_is_two), Opcode::Arithmetic(w1_is_w2)];
+        assert_eq!(find_unsatisfied_opcode(&opcodes, &witness), Some(1));
+
+        // Opcodes referring to unknown witnesses can't be checked
+        let opcodes = vec![range(3, 1)];
+        assert_eq!(find_unsatisfied_opcode(&opcodes, &witness), None);
+    }
 }
@@ -0,0 +1,223 @@
+use std::sync::{Mutex, PoisonError};
+
+use acvm::FieldElement;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// Resolves the calls a circuit makes to functions outside of it, such as oracles, while it is
+/// being executed.
+pub trait ForeignCallResolver {
+    /// Returns the values produced by calling the foreign function `name` with `inputs`.
+    fn resolve(
+        &mut self,
+        name: &str,
+        inputs: &[FieldElement],
+    ) -> Result<Vec<FieldElement>, ForeignCallError>;
+}
+
+/// Shares a resolver between threads, such as those running tests in parallel. The resolver is
+/// only locked while it resolves each call, so the calls of different threads are interleaved.
+impl<R: ForeignCallResolver> ForeignCallResolver for &Mutex<R> {
+    fn resolve(
+        &mut self,
+        name: &str,
+        inputs: &[FieldElement],
+    ) -> Result<Vec<FieldElement>, ForeignCallError> {
+        // A thread panicking while it held the lock doesn't leave the resolver mid-call
+        self.lock().unwrap_or_else(PoisonError::into_inner).resolve(name, inputs)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ForeignCallError {
+    #[error("Foreign call `{0}` could not be resolved")]
+    Unresolved(String),
+
+    #[error("Foreign call `{name}` returned {found} values, expected {expected}")]
+    OutputCountMismatch { name: String, expected: usize, found: usize },
+
+    #[error("Foreign call `{name}` failed: {message}")]
+    Failed { name: String, message: String },
+
+    #[error("Invalid foreign call mocks: {0}")]
+    InvalidMocks(String),
+
+    /// The solver stopped on foreign calls made by Brillig code, which aren't supported yet.
+    #[error("Brillig foreign calls are not supported")]
+    UnsupportedBrilligCall,
+}
+
+/// Resolves the foreign calls which are built into Nargo, passing any others on to an external
+/// resolver if one was given.
+///
+/// The built in foreign calls are:
+/// - `print` and `println`, which write their inputs to stdout and return nothing.
+pub struct DefaultForeignCallResolver {
+    show_output: bool,
+    external: Option<Box<dyn ForeignCallResolver + Send>>,
+}
+
+impl DefaultForeignCallResolver {
+    /// Creates a resolver which only handles the built in foreign calls.
+    /// Printed values are discarded unless `show_output` is set.
+    pub fn new(show_output: bool) -> Self {
+        DefaultForeignCallResolver { show_output, external: None }
+    }
+
+    /// Passes the foreign calls which aren't built in on to `resolver`.
+    pub fn with_external(mut self, resolver: Box<dyn ForeignCallResolver + Send>) -> Self {
+        self.external = Some(resolver);
+        self
+    }
+}
+
+impl ForeignCallResolver for DefaultForeignCallResolver {
+    fn resolve(
+        &mut self,
+        name: &str,
+        inputs: &[FieldElement],
+    ) -> Result<Vec<FieldElement>, ForeignCallError> {
+        match (name, &mut self.external) {
+            ("print" | "println", _) => {
+                if self.show_output {
+                    let values: Vec<_> =
+                        inputs.iter().map(|input| format!("0x{}", input.to_hex())).collect();
+                    let separator = if name == "println" { "\n" } else { "" };
+                    print!("{}{separator}", values.join(", "));
+                }
+                Ok(Vec::new())
+            }
+            (_, Some(external)) => external.resolve(name, inputs),
+            (_, None) => Err(ForeignCallError::Unresolved(name.to_owned())),
+        }
+    }
+}
+
+/// Resolves foreign calls with canned responses, so that programs which rely on them can be
+/// executed in tests.
+///
+/// Mocks are read from TOML, where each `[[mock]]` table gives the `name` of the foreign call,
+/// the `outputs` to return and optionally the `inputs` the call must be made with:
+///
+/// ```toml
+/// [[mock]]
+/// name = "get_price"
+/// inputs = ["1"]
+/// outputs = ["0x64"]
+/// ```
+///
+/// The first mock matching a call is used.
+#[derive(Debug, Clone, Default)]
+pub struct MockForeignCallResolver {
+    mocks: Vec<ForeignCallMock>,
+}
+
+#[derive(Debug, Clone)]
+struct ForeignCallMock {
+    name: String,
+    /// The inputs this mock responds to, or `None` if it responds to any inputs.
+    inputs: Option<Vec<FieldElement>>,
+    outputs: Vec<FieldElement>,
+}
+
+#[derive(Deserialize)]
+struct MockFile {
+    #[serde(default)]
+    mock: Vec<MockEntry>,
+}
+
+#[derive(Deserialize)]
+struct MockEntry {
+    name: String,
+    inputs: Option<Vec<String>>,
+    #[serde(default)]
+    outputs: Vec<String>,
+}
+
+impl MockForeignCallResolver {
+    pub fn from_toml(toml: &str) -> Result<Self, ForeignCallError> {
+        let file: MockFile = toml::from_str(toml)
+            .map_err(|error| ForeignCallError::InvalidMocks(error.to_string()))?;
+
+        let mut mocks = Vec::with_capacity(file.mock.len());
+        for entry in file.mock {
+            let inputs = entry.inputs.as_deref().map(parse_fields).transpose()?;
+            let outputs = parse_fields(&entry.outputs)?;
+            mocks.push(ForeignCallMock { name: entry.name, inputs, outputs });
+        }
+        Ok(MockForeignCallResolver { mocks })
+    }
+}
+
+impl ForeignCallResolver for MockForeignCallResolver {
+    fn resolve(
+        &mut self,
+        name: &str,
+        inputs: &[FieldElement],
+    ) -> Result<Vec<FieldElement>, ForeignCallError> {
+        self.mocks
+            .iter()
+            .find(|mock| {
+                mock.name == name
+                    && mock.inputs.as_ref().map_or(true, |mock_inputs| mock_inputs == inputs)
+            })
+            .map(|mock| mock.outputs.clone())
+            .ok_or_else(|| ForeignCallError::Unresolved(name.to_owned()))
+    }
+}
+
+fn parse_fields(values: &[String]) -> Result<Vec<FieldElement>, ForeignCallError> {
+    values
+        .iter()
+        .map(|value| {
+            FieldElement::try_from_str(value).ok_or_else(|| {
+                ForeignCallError::InvalidMocks(format!("`{value}` is not a valid field element"))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::FieldElement;
+
+    use super::{ForeignCallError, ForeignCallResolver, MockForeignCallResolver};
+
+    #[test]
+    fn resolves_calls_with_matching_mocks() {
+        let mut mocks = MockForeignCallResolver::from_toml(
+            r#"
+            [[mock]]
+            name = "get_price"
+            inputs = ["1"]
+            outputs = ["100"]
+
+            [[mock]]
+            name = "get_price"
+            outputs = ["0x05"]
+            "#,
+        )
+        .unwrap();
+
+        let one = FieldElement::one();
+        let two = FieldElement::from(2_u128);
+        assert_eq!(mocks.resolve("get_price", &[one]).unwrap(), vec![FieldElement::from(100_u128)]);
+        assert_eq!(mocks.resolve("get_price", &[two]).unwrap(), vec![FieldElement::from(5_u128)]);
+        assert!(matches!(
+            mocks.resolve("get_time", &[]),
+            Err(ForeignCallError::Unresolved(name)) if name == "get_time"
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_mock_values() {
+        let result = MockForeignCallResolver::from_toml(
+            r#"
+            [[mock]]
+            name = "get_price"
+            outputs = ["one hundred"]
+            "#,
+        );
+        assert!(matches!(result, Err(ForeignCallError::InvalidMocks(_))));
+    }
+}
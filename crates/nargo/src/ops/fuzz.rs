@@ -0,0 +1,289 @@
+use acvm::FieldElement;
+use noirc_abi::{input_parser::InputValue, Abi, AbiType, InputMap};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::NargoError;
+
+/// The number of random inputs a property is checked against by default.
+pub const DEFAULT_FUZZ_RUNS: usize = 256;
+
+/// The maximum number of times a property is rechecked while shrinking a failing input.
+const MAX_SHRINK_CHECKS: usize = 4096;
+
+#[derive(Debug, Clone)]
+pub struct FuzzOptions {
+    /// The number of random inputs to check the property against.
+    pub runs: usize,
+    /// The seed to generate inputs from, so that a failure can be reproduced.
+    /// A random seed is chosen if none is given.
+    pub seed: Option<u64>,
+}
+
+impl Default for FuzzOptions {
+    fn default() -> Self {
+        FuzzOptions { runs: DEFAULT_FUZZ_RUNS, seed: None }
+    }
+}
+
+/// The smallest input found for which a property doesn't hold.
+#[derive(Debug, Clone)]
+pub struct FuzzFailure {
+    pub inputs: InputMap,
+    /// The message the property failed with for `inputs`.
+    pub message: String,
+    /// The seed the failing input was generated from.
+    pub seed: u64,
+}
+
+/// Checks `property` against inputs randomly generated to match the parameters of `abi`.
+///
+/// If the property fails for any input, that input is shrunk towards zero for as long as the
+/// property keeps failing and the smallest failing input is returned.
+pub fn fuzz(
+    abi: &Abi,
+    options: &FuzzOptions,
+    mut property: impl FnMut(&InputMap) -> Result<(), String>,
+) -> Result<Option<FuzzFailure>, NargoError> {
+    for parameter in &abi.parameters {
+        if !is_fuzzable(&parameter.typ) {
+            return Err(NargoError::UnfuzzableParameter(parameter.name.clone()));
+        }
+    }
+
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    for _ in 0..options.runs {
+        let inputs: InputMap = abi
+            .parameters
+            .iter()
+            .map(|parameter| (parameter.name.clone(), generate_value(&mut rng, &parameter.typ)))
+            .collect();
+
+        if let Err(message) = property(&inputs) {
+            let (inputs, message) = shrink(inputs, message, &mut property);
+            return Ok(Some(FuzzFailure { inputs, message, seed }));
+        }
+    }
+    Ok(None)
+}
+
+/// Inputs can only be generated for arrays of single values, as they are represented by a flat
+/// list of fields.
+fn is_fuzzable(typ: &AbiType) -> bool {
+    match typ {
+        AbiType::Field | AbiType::Integer { .. } | AbiType::Boolean | AbiType::String { .. } => {
+            true
+        }
+        AbiType::Array { typ, .. } => max_value(typ).is_some(),
+        AbiType::Struct { fields } => fields.iter().all(|(_, typ)| is_fuzzable(typ)),
+    }
+}
+
+/// Returns the largest value of a type represented by a single field.
+fn max_value(typ: &AbiType) -> Option<BigUint> {
+    match typ {
+        AbiType::Field => Some(FieldElement::modulus() - BigUint::one()),
+        AbiType::Integer { width, .. } => Some((BigUint::one() << *width) - BigUint::one()),
+        AbiType::Boolean => Some(BigUint::one()),
+        AbiType::Array { .. } | AbiType::Struct { .. } | AbiType::String { .. } => None,
+    }
+}
+
+fn generate_value(rng: &mut StdRng, typ: &AbiType) -> InputValue {
+    match typ {
+        AbiType::Array { length, typ } => {
+            InputValue::Vec((0..*length).map(|_| generate_field(rng, typ)).collect())
+        }
+        AbiType::String { length } => {
+            InputValue::String((0..*length).map(|_| rng.gen_range(' '..='~')).collect())
+        }
+        AbiType::Struct { fields } => InputValue::Struct(
+            fields.iter().map(|(name, typ)| (name.clone(), generate_value(rng, typ))).collect(),
+        ),
+        AbiType::Field | AbiType::Integer { .. } | AbiType::Boolean => {
+            InputValue::Field(generate_field(rng, typ))
+        }
+    }
+}
+
+fn generate_field(rng: &mut StdRng, typ: &AbiType) -> FieldElement {
+    let max = max_value(typ).expect("only fuzzable types are generated");
+
+    // Bugs are often found at the edges of a type's range, so these are tried more often
+    let value = if rng.gen_ratio(1, 4) {
+        match rng.gen_range(0..3) {
+            0 => BigUint::zero(),
+            1 => BigUint::one(),
+            _ => max,
+        }
+    } else {
+        let mut bytes = vec![0; max.to_bytes_be().len()];
+        rng.fill(bytes.as_mut_slice());
+        BigUint::from_bytes_be(&bytes) % (max + BigUint::one())
+    };
+    FieldElement::from_be_bytes_reduce(&value.to_bytes_be())
+}
+
+/// Repeatedly replaces the failing inputs with smaller ones which still fail the property,
+/// until no smaller inputs fail or the limit on rechecks is reached.
+fn shrink(
+    mut inputs: InputMap,
+    mut message: String,
+    property: &mut impl FnMut(&InputMap) -> Result<(), String>,
+) -> (InputMap, String) {
+    let mut checks = 0;
+
+    loop {
+        let smaller_failure =
+            shrink_inputs(&inputs).take(MAX_SHRINK_CHECKS - checks).find_map(|candidate| {
+                checks += 1;
+                let message = property(&candidate).err()?;
+                Some((candidate, message))
+            });
+
+        match smaller_failure {
+            Some((smaller_inputs, smaller_message)) => {
+                inputs = smaller_inputs;
+                message = smaller_message;
+            }
+            None => return (inputs, message),
+        }
+    }
+}
+
+/// Returns the inputs which can be made by shrinking a single value within `inputs`.
+///
+/// Candidates are made lazily as there can be a great many of them for large arrays.
+fn shrink_inputs(inputs: &InputMap) -> impl Iterator<Item = InputMap> + '_ {
+    inputs.iter().flat_map(move |(name, value)| {
+        shrink_value(value).map(move |smaller_value| {
+            let mut candidate = inputs.clone();
+            candidate.insert(name.clone(), smaller_value);
+            candidate
+        })
+    })
+}
+
+fn shrink_value(value: &InputValue) -> Box<dyn Iterator<Item = InputValue> + '_> {
+    match value {
+        InputValue::Field(field) => {
+            Box::new(shrink_field(*field).into_iter().map(InputValue::Field))
+        }
+        InputValue::Vec(elements) => {
+            Box::new(elements.iter().enumerate().flat_map(move |(index, element)| {
+                shrink_field(*element).into_iter().map(move |smaller_element| {
+                    let mut candidate = elements.clone();
+                    candidate[index] = smaller_element;
+                    InputValue::Vec(candidate)
+                })
+            }))
+        }
+        // Each character is shrunk to a space, the smallest printable character
+        InputValue::String(string) => Box::new(
+            string.char_indices().filter(|(_, char)| *char != ' ').map(move |(index, char)| {
+                let mut candidate = string.clone();
+                candidate.replace_range(index..index + char.len_utf8(), " ");
+                InputValue::String(candidate)
+            }),
+        ),
+        InputValue::Struct(fields) => Box::new(fields.iter().flat_map(move |(name, value)| {
+            shrink_value(value).map(move |smaller_value| {
+                let mut candidate = fields.clone();
+                candidate.insert(name.clone(), smaller_value);
+                InputValue::Struct(candidate)
+            })
+        })),
+    }
+}
+
+/// Returns values smaller than `field`, starting with zero and then moving from halfway
+/// towards it to one less than it, so that the smallest failing value is found in as few
+/// steps as possible.
+fn shrink_field(field: FieldElement) -> Vec<FieldElement> {
+    let value = BigUint::from_bytes_be(&field.to_be_bytes());
+    if value.is_zero() {
+        return Vec::new();
+    }
+
+    let mut candidates = vec![BigUint::zero()];
+    let mut distance = &value >> 1u32;
+    while !distance.is_zero() {
+        candidates.push(&value - &distance);
+        distance >>= 1u32;
+    }
+    candidates.push(&value - BigUint::one());
+    candidates.dedup();
+
+    candidates
+        .into_iter()
+        .map(|candidate| FieldElement::from_be_bytes_reduce(&candidate.to_bytes_be()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::FieldElement;
+    use noirc_abi::{input_parser::InputValue, Abi, AbiParameter, AbiType, AbiVisibility, Sign};
+
+    use super::{fuzz, FuzzOptions};
+
+    fn abi(parameters: Vec<(&str, AbiType)>) -> Abi {
+        Abi {
+            parameters: parameters
+                .into_iter()
+                .map(|(name, typ)| AbiParameter {
+                    name: name.to_owned(),
+                    typ,
+                    visibility: AbiVisibility::Private,
+                })
+                .collect(),
+            param_witnesses: BTreeMap::new(),
+            return_type: None,
+            return_witnesses: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn generates_inputs_matching_the_abi() {
+        let abi = abi(vec![
+            ("x", AbiType::Integer { sign: Sign::Unsigned, width: 3 }),
+            ("flags", AbiType::Array { length: 4, typ: Box::new(AbiType::Boolean) }),
+            ("name", AbiType::String { length: 5 }),
+        ]);
+        let options = FuzzOptions { runs: 100, seed: Some(0) };
+
+        let failure = fuzz(&abi, &options, |inputs| {
+            let matches =
+                abi.parameters.iter().all(|param| inputs[&param.name].matches_abi(&param.typ));
+            if matches {
+                Ok(())
+            } else {
+                Err("inputs don't match the ABI".to_owned())
+            }
+        })
+        .unwrap();
+        assert!(failure.is_none());
+    }
+
+    #[test]
+    fn shrinks_to_the_smallest_failing_input() {
+        let abi = abi(vec![("x", AbiType::Integer { sign: Sign::Unsigned, width: 32 })]);
+        let options = FuzzOptions { runs: 100, seed: Some(0) };
+
+        let failure = fuzz(&abi, &options, |inputs| match &inputs["x"] {
+            InputValue::Field(x) if x.to_u128() >= 1000 => Err("x is too large".to_owned()),
+            _ => Ok(()),
+        })
+        .unwrap()
+        .expect("a value of at least 1000 should be generated");
+
+        assert_eq!(failure.inputs["x"], InputValue::Field(FieldElement::from(1000_u128)));
+        assert_eq!(failure.message, "x is too large");
+        assert_eq!(failure.seed, 0);
+    }
+}
@@ -1,11 +1,17 @@
 pub use self::codegen_verifier::codegen_verifier;
 pub use self::execute::execute_circuit;
+pub use self::foreign_calls::{
+    DefaultForeignCallResolver, ForeignCallError, ForeignCallResolver, MockForeignCallResolver,
+};
+pub use self::fuzz::{fuzz, FuzzFailure, FuzzOptions, DEFAULT_FUZZ_RUNS};
 pub use self::preprocess::{preprocess_contract_function, preprocess_program};
 pub use self::prove::prove_execution;
 pub use self::verify::verify_proof;
 
 mod codegen_verifier;
 mod execute;
+mod foreign_calls;
+mod fuzz;
 mod preprocess;
 mod prove;
 mod verify;
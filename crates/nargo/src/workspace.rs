@@ -1,4 +1,45 @@
-// We will say that a cargo unit must contain either a binary or a library
-// Then we use workspace to allow more than one. In the future, do not allow there to be
-// both a binary and a library.
-// - library will be default
+use std::path::{Path, PathBuf};
+
+use noirc_frontend::graph::CrateType;
+
+use crate::manifest::PackageManifest;
+
+/// A package of Noir source files, described by its `Nargo.toml`.
+///
+/// A package must contain either a binary or a library.
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub name: String,
+    /// The directory containing the package's `Nargo.toml`
+    pub root_dir: PathBuf,
+    /// The path of the package's `main.nr` or `lib.nr`
+    pub entry_path: PathBuf,
+    pub crate_type: CrateType,
+    pub manifest: PackageManifest,
+}
+
+/// A set of packages which are built together, sharing their `target` directory,
+/// their `Nargo.lock` and the resolution of their dependencies.
+///
+/// A package which isn't a member of a `[workspace]` is a workspace of its own.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    /// The directory containing the workspace's `Nargo.toml`
+    pub root_dir: PathBuf,
+    pub members: Vec<Package>,
+}
+
+impl Workspace {
+    /// Returns the member with the given name, if any.
+    pub fn member(&self, name: &str) -> Option<&Package> {
+        self.members.iter().find(|package| package.name == name)
+    }
+
+    /// Returns the member whose root is `dir`, if any.
+    pub fn member_at(&self, dir: &Path) -> Option<&Package> {
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        self.members.iter().find(|package| {
+            package.root_dir.canonicalize().unwrap_or_else(|_| package.root_dir.clone()) == dir
+        })
+    }
+}
@@ -3,17 +3,21 @@ use acvm::Backend;
 use clap::Args;
 use iter_extended::btree_map;
 use noirc_abi::{AbiParameter, AbiType, MAIN_RETURN_NAME};
-use noirc_driver::CompileOptions;
+use noirc_driver::{CompileOptions, Driver};
 use noirc_errors::reporter::ReportedErrors;
 use std::path::{Path, PathBuf};
 
-use super::NargoConfig;
-use super::{compile_cmd::setup_driver, fs::write_to_file};
+use super::{compile_cmd::setup_workspace_driver, fs::write_to_file};
+use super::{NargoConfig, PackageOptions};
 use crate::constants::{PROVER_INPUT_FILE, VERIFIER_INPUT_FILE};
+use crate::workspace::resolve_workspace;
 
 /// Checks the constraint system for errors
 #[derive(Debug, Clone, Args)]
 pub(crate) struct CheckCommand {
+    #[clap(flatten)]
+    packages: PackageOptions,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -23,18 +27,26 @@ pub(crate) fn run<B: Backend>(
     args: CheckCommand,
     config: NargoConfig,
 ) -> Result<(), CliError<B>> {
-    check_from_path(backend, config.program_dir, &args.compile_options)?;
+    let workspace = resolve_workspace(&config.program_dir)?;
+    let packages = args.packages.select(&workspace, &config.program_dir)?;
+
+    let (mut driver, crate_ids) = setup_workspace_driver(backend, &packages)?;
+    for (package, crate_id) in packages.iter().zip(crate_ids) {
+        driver.select_local_crate(crate_id);
+        check_package(&mut driver, &package.root_dir, &args.compile_options)?;
+    }
     println!("Constraint system successfully built!");
     Ok(())
 }
 
-fn check_from_path<B: Backend, P: AsRef<Path>>(
-    backend: &B,
-    program_dir: P,
+/// Checks the driver's local crate, then writes templates of the inputs to its `main` function
+/// to the package at `program_dir` if they don't exist yet.
+fn check_package<B: Backend>(
+    driver: &mut Driver,
+    program_dir: &Path,
     compile_options: &CompileOptions,
 ) -> Result<(), CliError<B>> {
-    let mut driver = setup_driver(backend, program_dir.as_ref())?;
-    check_crate_and_report_errors(&mut driver, compile_options.deny_warnings)?;
+    check_crate_and_report_errors(driver, compile_options.deny_warnings)?;
 
     // XXX: We can have a --overwrite flag to determine if you want to overwrite the Prover/Verifier.toml files
     if let Some((parameters, return_type)) = driver.compute_function_signature() {
@@ -42,7 +54,7 @@ fn check_from_path<B: Backend, P: AsRef<Path>>(
         // For now it is hard-coded to be toml.
         //
         // Check for input.toml and verifier.toml
-        let path_to_root = PathBuf::from(program_dir.as_ref());
+        let path_to_root = PathBuf::from(program_dir);
         let path_to_prover_input = path_to_root.join(format!("{PROVER_INPUT_FILE}.toml"));
         let path_to_verifier_input = path_to_root.join(format!("{VERIFIER_INPUT_FILE}.toml"));
 
@@ -100,15 +112,26 @@ fn create_input_toml_template(
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
+    use acvm::Backend;
     use noirc_abi::{AbiParameter, AbiType, AbiVisibility, Sign};
     use noirc_driver::CompileOptions;
 
-    use super::create_input_toml_template;
+    use super::{check_package, create_input_toml_template};
+    use crate::{cli::compile_cmd::setup_driver, errors::CliError};
 
     const TEST_DATA_DIR: &str = "tests/target_tests_data";
 
+    fn check_from_path<B: Backend, P: AsRef<Path>>(
+        backend: &B,
+        program_dir: P,
+        compile_options: &CompileOptions,
+    ) -> Result<(), CliError<B>> {
+        let mut driver = setup_driver(backend, program_dir.as_ref())?;
+        check_package(&mut driver, program_dir.as_ref(), compile_options)
+    }
+
     #[test]
     fn valid_toml_template() {
         let typed_param = |name: &str, typ: AbiType| AbiParameter {
@@ -160,7 +183,7 @@ d2 = ["", "", ""]
         for path in paths.flatten() {
             let path = path.path();
             assert!(
-                super::check_from_path(&backend, path.clone(), &config).is_ok(),
+                check_from_path(&backend, path.clone(), &config).is_ok(),
                 "path: {}",
                 path.display()
             );
@@ -179,7 +202,7 @@ d2 = ["", "", ""]
         for path in paths.flatten() {
             let path = path.path();
             assert!(
-                super::check_from_path(&backend, path.clone(), &config).is_err(),
+                check_from_path(&backend, path.clone(), &config).is_err(),
                 "path: {}",
                 path.display()
             );
@@ -198,7 +221,7 @@ d2 = ["", "", ""]
         for path in paths.flatten() {
             let path = path.path();
             assert!(
-                super::check_from_path(&backend, path.clone(), &config).is_ok(),
+                check_from_path(&backend, path.clone(), &config).is_ok(),
                 "path: {}",
                 path.display()
             );
@@ -9,8 +9,8 @@ use super::fs::{
 };
 use super::NargoConfig;
 use crate::{
-    cli::compile_cmd::compile_circuit, constants::CONTRACT_DIR, constants::TARGET_DIR,
-    errors::CliError,
+    cli::compile_cmd::compile_circuit, constants::CONTRACT_DIR, errors::CliError,
+    workspace::target_dir,
 };
 use acvm::Backend;
 use clap::Args;
@@ -33,9 +33,8 @@ pub(crate) fn run<B: Backend>(
     config: NargoConfig,
 ) -> Result<(), CliError<B>> {
     // TODO(#1201): Should this be a utility function?
-    let circuit_build_path = args
-        .circuit_name
-        .map(|circuit_name| config.program_dir.join(TARGET_DIR).join(circuit_name));
+    let circuit_build_path =
+        args.circuit_name.map(|circuit_name| target_dir(&config.program_dir).join(circuit_name));
 
     let common_reference_string = read_cached_common_reference_string();
 
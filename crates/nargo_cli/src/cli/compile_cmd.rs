@@ -1,8 +1,10 @@
 use acvm::Backend;
-use iter_extended::try_vecmap;
-use nargo::artifacts::contract::PreprocessedContract;
+use iter_extended::{try_vecmap, vecmap};
+use nargo::artifacts::{contract::PreprocessedContract, debug::DebugArtifact};
+use nargo::workspace::Package;
 use noirc_driver::{CompileOptions, CompiledProgram, Driver, ErrorsAndWarnings, Warnings};
 use noirc_errors::reporter::ReportedErrors;
+use noirc_frontend::graph::{CrateId, CrateType};
 use std::path::Path;
 
 use clap::Args;
@@ -10,16 +12,18 @@ use clap::Args;
 use nargo::ops::{preprocess_contract_function, preprocess_program};
 
 use crate::resolver::DependencyResolutionError;
-use crate::{constants::TARGET_DIR, errors::CliError, resolver::Resolver};
+use crate::{
+    constants::TARGET_DIR, errors::CliError, resolver::Resolver, workspace::resolve_workspace,
+};
 
 use super::fs::{
     common_reference_string::{
         read_cached_common_reference_string, update_common_reference_string,
         write_cached_common_reference_string,
     },
-    program::{save_contract_to_file, save_program_to_file},
+    program::{save_contract_to_file, save_debug_artifact_to_file, save_program_to_file},
 };
-use super::NargoConfig;
+use super::{check_cmd::check_crate_and_report_errors, NargoConfig, PackageOptions};
 
 // TODO(#1388): pull this from backend.
 const BACKEND_IDENTIFIER: &str = "acvm-backend-barretenberg";
@@ -27,13 +31,16 @@ const BACKEND_IDENTIFIER: &str = "acvm-backend-barretenberg";
 /// Compile the program and its secret execution trace into ACIR format
 #[derive(Debug, Clone, Args)]
 pub(crate) struct CompileCommand {
-    /// The name of the ACIR file
-    circuit_name: String,
+    /// The name of the ACIR file, defaulting to the name of the package
+    circuit_name: Option<String>,
 
     /// Compile each contract function used within the program
     #[arg(short, long)]
     contracts: bool,
 
+    #[clap(flatten)]
+    packages: PackageOptions,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -43,21 +50,73 @@ pub(crate) fn run<B: Backend>(
     args: CompileCommand,
     config: NargoConfig,
 ) -> Result<(), CliError<B>> {
-    let circuit_dir = config.program_dir.join(TARGET_DIR);
+    let workspace = resolve_workspace(&config.program_dir)?;
+    let packages = args.packages.select(&workspace, &config.program_dir)?;
+    if args.circuit_name.is_some() && packages.len() > 1 {
+        return Err(CliError::Generic(
+            "a circuit name can only be given when compiling a single package".to_owned(),
+        ));
+    }
+
+    // Every package in the workspace writes its build artifacts to the same directory
+    let circuit_dir = workspace.root_dir.join(TARGET_DIR);
 
     let mut common_reference_string = read_cached_common_reference_string();
 
-    // If contracts is set we're compiling every function in a 'contract' rather than just 'main'.
-    if args.contracts {
-        let mut driver = setup_driver(backend, &config.program_dir)?;
+    let (mut driver, crate_ids) = setup_workspace_driver(backend, &packages)?;
+    for (package, crate_id) in packages.iter().zip(crate_ids) {
+        driver.select_local_crate(crate_id);
+        let circuit_name = args.circuit_name.as_deref().unwrap_or(&package.name);
+
+        // Libraries can't be compiled into programs, so they are only checked when compiling
+        // several packages at once.
+        if !args.contracts && packages.len() > 1 && package.crate_type == CrateType::Library {
+            check_crate_and_report_errors(&mut driver, args.compile_options.deny_warnings)?;
+            continue;
+        }
+
+        common_reference_string = compile_package(
+            backend,
+            &mut driver,
+            circuit_name,
+            &circuit_dir,
+            args.contracts,
+            &args.compile_options,
+            common_reference_string,
+        )?;
+    }
 
-        let result = driver.compile_contracts(&args.compile_options);
-        let contracts = report_errors(result, &driver, args.compile_options.deny_warnings)?;
+    write_cached_common_reference_string(&common_reference_string);
+
+    Ok(())
+}
+
+/// Compiles the driver's local crate, writing its build artifacts to `circuit_dir`.
+///
+/// Returns the common reference string, updated to be large enough for the compiled circuits.
+fn compile_package<B: Backend>(
+    backend: &B,
+    driver: &mut Driver,
+    circuit_name: &str,
+    circuit_dir: &Path,
+    contracts: bool,
+    compile_options: &CompileOptions,
+    mut common_reference_string: Vec<u8>,
+) -> Result<Vec<u8>, CliError<B>> {
+    // If contracts is set we're compiling every function in a 'contract' rather than just 'main'.
+    if contracts {
+        let result = driver.compile_contracts(compile_options);
+        let contracts = report_errors(result, driver, compile_options.deny_warnings)?;
 
         // TODO(#1389): I wonder if it is incorrect for nargo-core to know anything about contracts.
         // As can be seen here, It seems like a leaky abstraction where ContractFunctions (essentially CompiledPrograms)
         // are compiled via nargo-core and then the PreprocessedContract is constructed here.
         // This is due to EACH function needing it's own CRS, PKey, and VKey from the backend.
+        let debug_artifacts = vecmap(&contracts, |contract| {
+            let debug_symbols = vecmap(&contract.functions, |func| func.debug.clone());
+            DebugArtifact::new(debug_symbols, driver.file_manager())
+        });
+
         let preprocessed_contracts: Result<Vec<PreprocessedContract>, CliError<B>> =
             try_vecmap(contracts, |contract| {
                 let preprocessed_contract_functions = try_vecmap(contract.functions, |func| {
@@ -78,27 +137,27 @@ pub(crate) fn run<B: Backend>(
                     functions: preprocessed_contract_functions,
                 })
             });
-        for contract in preprocessed_contracts? {
-            save_contract_to_file(
-                &contract,
-                &format!("{}-{}", &args.circuit_name, contract.name),
-                &circuit_dir,
-            );
+        for (contract, debug_artifact) in preprocessed_contracts?.into_iter().zip(debug_artifacts) {
+            let circuit_name = format!("{circuit_name}-{}", contract.name);
+            save_contract_to_file(&contract, &circuit_name, circuit_dir);
+            save_debug_artifact_to_file(&debug_artifact, &circuit_name, circuit_dir);
         }
     } else {
-        let program = compile_circuit(backend, &config.program_dir, &args.compile_options)?;
+        let result = driver.compile_main(compile_options);
+        let program = report_errors(result, driver, compile_options.deny_warnings)?;
+
         common_reference_string =
             update_common_reference_string(backend, &common_reference_string, &program.circuit)
                 .map_err(CliError::CommonReferenceStringError)?;
 
+        let debug_artifact = DebugArtifact::new(vec![program.debug.clone()], driver.file_manager());
         let preprocessed_program = preprocess_program(backend, &common_reference_string, program)
             .map_err(CliError::ProofSystemCompilerError)?;
-        save_program_to_file(&preprocessed_program, &args.circuit_name, circuit_dir);
+        save_program_to_file(&preprocessed_program, circuit_name, circuit_dir);
+        save_debug_artifact_to_file(&debug_artifact, circuit_name, circuit_dir);
     }
 
-    write_cached_common_reference_string(&common_reference_string);
-
-    Ok(())
+    Ok(common_reference_string)
 }
 
 pub(super) fn setup_driver<B: Backend>(
@@ -113,6 +172,19 @@ pub(super) fn setup_driver<B: Backend>(
     )
 }
 
+/// Resolves the given packages of a workspace, along with their dependencies, into a single
+/// driver. Returns the driver along with the local crate of each package.
+pub(super) fn setup_workspace_driver<B: Backend>(
+    backend: &B,
+    packages: &[&Package],
+) -> Result<(Driver, Vec<CrateId>), DependencyResolutionError> {
+    // TODO(#1102): Remove need for driver to be aware of backend.
+    let mut driver =
+        Driver::new(&backend.np_language(), Box::new(|op| B::default().supports_opcode(op)));
+    let crate_ids = Resolver::resolve_packages(&mut driver, packages)?;
+    Ok((driver, crate_ids))
+}
+
 pub(crate) fn compile_circuit<B: Backend>(
     backend: &B,
     program_dir: &Path,
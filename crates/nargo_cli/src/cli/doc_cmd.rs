@@ -0,0 +1,61 @@
+use acvm::Backend;
+use clap::{Args, ValueEnum};
+use nargo::doc::{collect_docs, render_html, render_markdown};
+
+use super::{
+    check_cmd::check_crate_and_report_errors,
+    compile_cmd::setup_driver,
+    fs::{create_named_dir, write_to_file},
+    NargoConfig,
+};
+use crate::{errors::CliError, workspace::target_dir};
+
+/// Generate the documentation of a package and its dependencies from their doc comments
+#[derive(Debug, Clone, Args)]
+pub(crate) struct DocCommand {
+    /// The format to write the documentation in
+    #[arg(long, value_enum, default_value_t = DocFormat::Html)]
+    format: DocFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DocFormat {
+    /// A static HTML site, starting from `index.html`
+    Html,
+    /// Markdown files, starting from `index.md`
+    Markdown,
+}
+
+pub(crate) fn run<B: Backend>(
+    backend: &B,
+    args: DocCommand,
+    config: NargoConfig,
+) -> Result<(), CliError<B>> {
+    let mut driver = setup_driver(backend, &config.program_dir)?;
+    check_crate_and_report_errors(&mut driver, false)?;
+
+    // Packages don't have a name of their own, so they are named after their directory
+    let name = config
+        .program_dir
+        .file_name()
+        .map_or_else(|| "crate".to_owned(), |name| name.to_string_lossy().into_owned());
+
+    let crates = collect_docs(&driver, &name);
+    let files = match args.format {
+        DocFormat::Html => render_html(&crates),
+        DocFormat::Markdown => render_markdown(&crates),
+    };
+
+    let doc_dir = target_dir(&config.program_dir).join("doc");
+    for (path, contents) in files {
+        let path = doc_dir.join(path);
+        if let Some(dir) = path.parent() {
+            create_named_dir(dir, "doc");
+        }
+        write_to_file(contents.as_bytes(), &path);
+    }
+
+    let index = if args.format == DocFormat::Html { "index.html" } else { "index.md" };
+    println!("Documentation written to {}", doc_dir.join(index).display());
+    Ok(())
+}
@@ -3,16 +3,20 @@ use std::path::Path;
 use acvm::acir::{circuit::Circuit, native_types::WitnessMap};
 use acvm::Backend;
 use clap::Args;
+use nargo::{ops::DefaultForeignCallResolver, NargoError};
 use noirc_abi::input_parser::{Format, InputValue};
 use noirc_abi::{Abi, InputMap};
-use noirc_driver::{CompileOptions, CompiledProgram};
+use noirc_driver::{CompileOptions, CompiledProgram, Driver};
+use noirc_errors::{debug_info::DebugInfo, CustomDiagnostic, FileDiagnostic};
 
+use super::foreign_calls::ForeignCallOptions;
 use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
 use super::NargoConfig;
 use crate::{
-    cli::compile_cmd::compile_circuit,
-    constants::{PROVER_INPUT_FILE, TARGET_DIR},
+    cli::compile_cmd::{report_errors, setup_driver},
+    constants::PROVER_INPUT_FILE,
     errors::CliError,
+    workspace::target_dir,
 };
 
 /// Executes a circuit to calculate its return value
@@ -25,6 +29,9 @@ pub(crate) struct ExecuteCommand {
     #[clap(long, short, default_value = PROVER_INPUT_FILE)]
     prover_name: String,
 
+    #[clap(flatten)]
+    foreign_call_options: ForeignCallOptions,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -34,15 +41,22 @@ pub(crate) fn run<B: Backend>(
     args: ExecuteCommand,
     config: NargoConfig,
 ) -> Result<(), CliError<B>> {
-    let (return_value, solved_witness) =
-        execute_with_path(backend, &config.program_dir, args.prover_name, &args.compile_options)?;
+    let mut foreign_calls =
+        args.foreign_call_options.resolver::<B>(args.compile_options.show_output)?;
+    let (return_value, solved_witness) = execute_with_path(
+        backend,
+        &config.program_dir,
+        args.prover_name,
+        &args.compile_options,
+        &mut foreign_calls,
+    )?;
 
     println!("Circuit witness successfully solved");
     if let Some(return_value) = return_value {
         println!("Circuit output: {return_value:?}");
     }
     if let Some(witness_name) = args.witness_name {
-        let witness_dir = config.program_dir.join(TARGET_DIR);
+        let witness_dir = target_dir(&config.program_dir);
 
         let witness_path = save_witness_to_dir(solved_witness, &witness_name, witness_dir)?;
 
@@ -51,19 +65,25 @@ pub(crate) fn run<B: Backend>(
     Ok(())
 }
 
-fn execute_with_path<B: Backend>(
+pub(crate) fn execute_with_path<B: Backend>(
     backend: &B,
     program_dir: &Path,
     prover_name: String,
     compile_options: &CompileOptions,
+    foreign_calls: &mut DefaultForeignCallResolver,
 ) -> Result<(Option<InputValue>, WitnessMap), CliError<B>> {
-    let CompiledProgram { abi, circuit } = compile_circuit(backend, program_dir, compile_options)?;
+    let mut driver = setup_driver(backend, program_dir)?;
+    let result = driver.compile_main(compile_options);
+    let CompiledProgram { abi, circuit, debug } =
+        report_errors(result, &driver, compile_options.deny_warnings)?;
 
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(program_dir, prover_name.as_str(), Format::Toml, &abi)?;
 
-    let solved_witness = execute_program(backend, circuit, &abi, &inputs_map)?;
+    let debug_data = Some((&debug, &driver));
+    let solved_witness =
+        execute_program(backend, circuit, &abi, &inputs_map, debug_data, foreign_calls)?;
 
     let public_abi = abi.public_abi();
     let (_, return_value) = public_abi.decode(&solved_witness)?;
@@ -71,15 +91,66 @@ fn execute_with_path<B: Backend>(
     Ok((return_value, solved_witness))
 }
 
+/// Executes the circuit with the given inputs, resolving any foreign calls it makes with
+/// `foreign_calls`.
+///
+/// If the circuit's debug information and the driver it was compiled with are given, a failing
+/// constraint is reported against the code it was generated from.
 pub(crate) fn execute_program<B: Backend>(
     backend: &B,
     circuit: Circuit,
     abi: &Abi,
     inputs_map: &InputMap,
+    debug_data: Option<(&DebugInfo, &Driver)>,
+    foreign_calls: &mut DefaultForeignCallResolver,
 ) -> Result<WitnessMap, CliError<B>> {
     let initial_witness = abi.encode(inputs_map, None)?;
 
-    let solved_witness = nargo::ops::execute_circuit(backend, circuit, initial_witness)?;
+    nargo::ops::execute_circuit(backend, circuit, initial_witness, foreign_calls).map_err(|error| {
+        let diagnostic = debug_data.and_then(|(debug, driver)| {
+            Some((execution_error_diagnostic(&error, debug)?, driver.file_manager()))
+        });
+        match diagnostic {
+            Some((diagnostic, files)) => {
+                noirc_errors::reporter::report_all(files, &[diagnostic], false).into()
+            }
+            None => error.into(),
+        }
+    })
+}
+
+/// Returns a diagnostic pointing at the code which produced the constraint that execution
+/// failed on, along with the calls leading to it, if this is known.
+pub(crate) fn execution_error_diagnostic(
+    error: &NargoError,
+    debug: &DebugInfo,
+) -> Option<FileDiagnostic> {
+    let opcode_index = match error {
+        NargoError::UnsatisfiedConstraint { opcode_index } => *opcode_index,
+        _ => return None,
+    };
+    let call_stack = debug.opcode_call_stack(opcode_index)?;
+    let location = call_stack.last()?;
+
+    let message = execution_error_message(error, debug);
+    let diagnostic = CustomDiagnostic::simple_error(message, String::new(), location.span);
+    Some(diagnostic.in_file(location.file).with_call_stack(call_stack.to_vec()))
+}
 
-    Ok(solved_witness)
+/// Describes an execution failure, including the message of the assertion which failed if it
+/// was given one. A failing constraint which has no call stack in the debug information is
+/// described as such, rather than being silently reported without its location.
+pub(crate) fn execution_error_message(error: &NargoError, debug: &DebugInfo) -> String {
+    let opcode_index = match error {
+        NargoError::UnsatisfiedConstraint { opcode_index } => *opcode_index,
+        _ => return error.to_string(),
+    };
+    let message = match debug.opcode_assert_message(opcode_index) {
+        Some(message) => format!("{error}: {message}"),
+        None => error.to_string(),
+    };
+    match debug.opcode_call_stack(opcode_index) {
+        Some(_) => message,
+        None => format!("{message} (the code it was generated from is unknown)"),
+    }
 }
@@ -0,0 +1,80 @@
+use std::path::{Path, PathBuf};
+
+use acvm::Backend;
+use clap::Args;
+use noirc_frontend::formatter;
+
+use super::{fs::write_to_file, NargoConfig};
+use crate::{
+    constants::{PKG_FILE, SRC_DIR},
+    errors::{CliError, FilesystemError},
+    manifest::parse,
+};
+
+/// Format the Noir files in a package
+#[derive(Debug, Clone, Args)]
+pub(crate) struct FmtCommand {
+    /// Check that the files are formatted without changing them, failing if any aren't
+    #[clap(long)]
+    check: bool,
+}
+
+pub(crate) fn run<B: Backend>(
+    _backend: &B,
+    args: FmtCommand,
+    config: NargoConfig,
+) -> Result<(), CliError<B>> {
+    let manifest = parse(config.program_dir.join(PKG_FILE))
+        .map_err(|err| CliError::Generic(err.to_string()))?;
+
+    let mut unformatted_files = Vec::new();
+    for path in find_noir_files(&config.program_dir.join(SRC_DIR)) {
+        let source = std::fs::read_to_string(&path)
+            .map_err(|_| FilesystemError::PathNotValid(path.clone()))?;
+
+        let formatted = formatter::format(&source, &manifest.fmt).map_err(|_| {
+            CliError::Generic(format!(
+                "Could not format {} as it failed to parse, run `nargo check` for details",
+                path.display()
+            ))
+        })?;
+        if formatted == source {
+            continue;
+        }
+
+        if args.check {
+            unformatted_files.push(path);
+        } else {
+            write_to_file(formatted.as_bytes(), &path);
+        }
+    }
+
+    if unformatted_files.is_empty() {
+        return Ok(());
+    }
+    let files = unformatted_files.iter().map(|path| format!("\n    {}", path.display()));
+    Err(CliError::Generic(format!(
+        "The following files are not formatted, run `nargo fmt` to format them:{}",
+        files.collect::<String>()
+    )))
+}
+
+/// Returns the paths of the Noir source files within `dir` and its subdirectories, in sorted
+/// order.
+fn find_noir_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return files,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(find_noir_files(&path));
+        } else if path.extension().map_or(false, |extension| extension == "nr") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
@@ -0,0 +1,168 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+use acvm::{Backend, FieldElement};
+use clap::Args;
+use nargo::{
+    ops::{
+        DefaultForeignCallResolver, ForeignCallError, ForeignCallResolver, MockForeignCallResolver,
+    },
+    NargoError,
+};
+use serde::Deserialize;
+
+use crate::errors::{CliError, FilesystemError};
+
+/// Options for resolving the foreign calls, such as oracles, which a circuit makes while it
+/// is being executed.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct ForeignCallOptions {
+    /// Resolve foreign calls by sending JSON-RPC requests to this command, which is started
+    /// with the requests written to its stdin and the responses read from its stdout
+    #[clap(long, conflicts_with = "oracle_mocks")]
+    oracle_resolver: Option<String>,
+
+    /// Resolve foreign calls with the mocked responses in this TOML file
+    #[clap(long)]
+    oracle_mocks: Option<PathBuf>,
+}
+
+impl ForeignCallOptions {
+    /// Creates a resolver for the built in foreign calls, which passes any others on to the
+    /// resolver chosen by these options.
+    pub(crate) fn resolver<B: Backend>(
+        &self,
+        show_output: bool,
+    ) -> Result<DefaultForeignCallResolver, CliError<B>> {
+        let resolver = DefaultForeignCallResolver::new(show_output);
+        if let Some(command) = &self.oracle_resolver {
+            let external = JsonRpcForeignCallResolver::spawn(command).map_err(|error| {
+                CliError::Generic(format!("Failed to start oracle resolver `{command}`: {error}"))
+            })?;
+            Ok(resolver.with_external(Box::new(external)))
+        } else if let Some(path) = &self.oracle_mocks {
+            let mocks = std::fs::read_to_string(path)
+                .map_err(|_| FilesystemError::PathNotValid(path.clone()))?;
+            let mocks = MockForeignCallResolver::from_toml(&mocks).map_err(NargoError::from)?;
+            Ok(resolver.with_external(Box::new(mocks)))
+        } else {
+            Ok(resolver)
+        }
+    }
+}
+
+/// Resolves foreign calls by sending them as JSON-RPC requests to a local process.
+///
+/// Each request is written to the process's stdin on a single line, with the method being the
+/// name of the foreign call and the params being its inputs as hex strings:
+///
+/// ```json
+/// {"jsonrpc":"2.0","id":1,"method":"get_price","params":["0x01"]}
+/// ```
+///
+/// The process must write a response for each request to its stdout on a single line, with the
+/// result being the outputs of the call as hex or decimal strings:
+///
+/// ```json
+/// {"jsonrpc":"2.0","id":1,"result":["0x64"]}
+/// ```
+struct JsonRpcForeignCallResolver {
+    process: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcResponse {
+    id: u64,
+    result: Option<Vec<String>>,
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcError {
+    message: String,
+}
+
+impl JsonRpcForeignCallResolver {
+    /// Starts `command`, which is split on whitespace into the program to run and its arguments.
+    fn spawn(command: &str) -> std::io::Result<Self> {
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "the command is empty")
+        })?;
+
+        let mut process = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = process.stdin.take().expect("stdin should be piped");
+        let stdout = BufReader::new(process.stdout.take().expect("stdout should be piped"));
+
+        Ok(JsonRpcForeignCallResolver { process, stdin, stdout, next_id: 0 })
+    }
+
+    fn call(&mut self, name: &str, inputs: &[FieldElement]) -> Result<Vec<FieldElement>, String> {
+        self.next_id += 1;
+        let params: Vec<_> = inputs.iter().map(|input| format!("0x{}", input.to_hex())).collect();
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id,
+            "method": name,
+            "params": params,
+        });
+        writeln!(self.stdin, "{request}").map_err(|error| error.to_string())?;
+        self.stdin.flush().map_err(|error| error.to_string())?;
+
+        let mut line = String::new();
+        let bytes_read = self.stdout.read_line(&mut line).map_err(|error| error.to_string())?;
+        if bytes_read == 0 {
+            return Err("the oracle resolver exited without responding".to_owned());
+        }
+
+        let response: JsonRpcResponse =
+            serde_json::from_str(&line).map_err(|error| format!("invalid response: {error}"))?;
+        if response.id != self.next_id {
+            return Err(format!(
+                "expected a response to request {}, received one to request {}",
+                self.next_id, response.id
+            ));
+        }
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(error.message),
+            (Some(outputs), None) => outputs
+                .iter()
+                .map(|output| {
+                    FieldElement::try_from_str(output)
+                        .ok_or_else(|| format!("`{output}` is not a valid field element"))
+                })
+                .collect(),
+            (None, None) => Err("the response has neither a result nor an error".to_owned()),
+        }
+    }
+}
+
+impl ForeignCallResolver for JsonRpcForeignCallResolver {
+    fn resolve(
+        &mut self,
+        name: &str,
+        inputs: &[FieldElement],
+    ) -> Result<Vec<FieldElement>, ForeignCallError> {
+        self.call(name, inputs)
+            .map_err(|message| ForeignCallError::Failed { name: name.to_owned(), message })
+    }
+}
+
+impl Drop for JsonRpcForeignCallResolver {
+    fn drop(&mut self) {
+        // The process may already have exited, in which case there's nothing to clean up
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
@@ -1,6 +1,8 @@
 use std::path::{Path, PathBuf};
 
-use nargo::artifacts::{contract::PreprocessedContract, program::PreprocessedProgram};
+use nargo::artifacts::{
+    contract::PreprocessedContract, debug::DebugArtifact, program::PreprocessedProgram,
+};
 
 use crate::errors::FilesystemError;
 
@@ -20,6 +22,16 @@ pub(crate) fn save_contract_to_file<P: AsRef<Path>>(
 ) -> PathBuf {
     save_build_artifact_to_file(compiled_contract, circuit_name, circuit_dir)
 }
+
+/// Saves the debug artifact of the build artifact named `circuit_name` alongside it.
+pub(crate) fn save_debug_artifact_to_file<P: AsRef<Path>>(
+    debug_artifact: &DebugArtifact,
+    circuit_name: &str,
+    circuit_dir: P,
+) -> PathBuf {
+    save_build_artifact_to_file(debug_artifact, &format!("debug_{circuit_name}"), circuit_dir)
+}
+
 fn save_build_artifact_to_file<P: AsRef<Path>, T: ?Sized + serde::Serialize>(
     build_artifact: &T,
     circuit_name: &str,
@@ -20,27 +20,32 @@ pub(crate) fn run<B: Backend>(
     args: GatesCommand,
     config: NargoConfig,
 ) -> Result<(), CliError<B>> {
-    count_gates_with_path(backend, config.program_dir, &args.compile_options)
+    let (num_opcodes, exact_circuit_size) =
+        count_gates_with_path(backend, config.program_dir, &args.compile_options)?;
+
+    println!(
+        "Total ACIR opcodes generated for language {:?}: {}",
+        backend.np_language(),
+        num_opcodes
+    );
+    println!("Backend circuit size: {exact_circuit_size}");
+
+    Ok(())
 }
 
-fn count_gates_with_path<B: Backend, P: AsRef<Path>>(
+/// Returns the number of ACIR opcodes in the program's circuit, along with the size of the
+/// circuit once it has been compiled by the backend.
+pub(crate) fn count_gates_with_path<B: Backend, P: AsRef<Path>>(
     backend: &B,
     program_dir: P,
     compile_options: &CompileOptions,
-) -> Result<(), CliError<B>> {
+) -> Result<(usize, u32), CliError<B>> {
     let compiled_program = compile_circuit(backend, program_dir.as_ref(), compile_options)?;
     let num_opcodes = compiled_program.circuit.opcodes.len();
 
-    println!(
-        "Total ACIR opcodes generated for language {:?}: {}",
-        backend.np_language(),
-        num_opcodes
-    );
-
     let exact_circuit_size = backend
         .get_exact_circuit_size(&compiled_program.circuit)
         .map_err(CliError::ProofSystemCompilerError)?;
-    println!("Backend circuit size: {exact_circuit_size}");
 
-    Ok(())
+    Ok((num_opcodes, exact_circuit_size))
 }
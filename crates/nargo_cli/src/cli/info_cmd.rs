@@ -0,0 +1,420 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+use acvm::{
+    acir::{
+        circuit::{Circuit, Opcode},
+        BlackBoxFunc,
+    },
+    Backend,
+};
+use clap::Args;
+use iter_extended::try_vecmap;
+use noirc_driver::{CompileOptions, Driver};
+use noirc_errors::{debug_info::DebugInfo, Location};
+use noirc_frontend::graph::CrateType;
+use serde::Serialize;
+
+use crate::{
+    cli::{
+        check_cmd::check_crate_and_report_errors,
+        compile_cmd::{report_errors, setup_driver, setup_workspace_driver},
+    },
+    errors::CliError,
+    workspace::resolve_workspace,
+};
+
+use super::{NargoConfig, PackageOptions};
+
+/// Provides detailed information on the circuit generated for a program or for each function of
+/// its contracts
+#[derive(Debug, Clone, Args)]
+pub(crate) struct InfoCommand {
+    /// Report on each contract function within the program instead of `main`
+    #[arg(short, long)]
+    contracts: bool,
+
+    /// Output the information as JSON
+    #[arg(long)]
+    json: bool,
+
+    #[clap(flatten)]
+    packages: PackageOptions,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+}
+
+pub(crate) fn run<B: Backend>(
+    backend: &B,
+    args: InfoCommand,
+    config: NargoConfig,
+) -> Result<(), CliError<B>> {
+    let workspace = resolve_workspace(&config.program_dir)?;
+    let packages = args.packages.select(&workspace, &config.program_dir)?;
+    let deny_warnings = args.compile_options.deny_warnings;
+
+    let mut report = InfoReport { programs: Vec::new(), contracts: Vec::new() };
+    let (mut driver, crate_ids) = setup_workspace_driver(backend, &packages)?;
+    for (package, crate_id) in packages.iter().zip(crate_ids) {
+        driver.select_local_crate(crate_id);
+
+        if args.contracts {
+            let result = driver.compile_contracts(&args.compile_options);
+            let contracts = report_errors(result, &driver, deny_warnings)?;
+
+            let contracts = try_vecmap(contracts, |contract| {
+                let functions = try_vecmap(contract.functions, |function| {
+                    let sources = SourceBreakdown::new(
+                        &driver,
+                        &function.debug,
+                        function.bytecode.opcodes.len(),
+                        &package.root_dir,
+                    );
+                    circuit_info(backend, function.name, &function.bytecode, sources)
+                })?;
+                Ok(ContractInfo { name: contract.name, functions })
+            })?;
+            report.contracts.extend(contracts);
+        } else if packages.len() > 1 && package.crate_type == CrateType::Library {
+            // Libraries don't have a circuit of their own, so they are only checked
+            check_crate_and_report_errors(&mut driver, deny_warnings)?;
+        } else {
+            let result = driver.compile_main(&args.compile_options);
+            let program = report_errors(result, &driver, deny_warnings)?;
+            let sources = SourceBreakdown::new(
+                &driver,
+                &program.debug,
+                program.circuit.opcodes.len(),
+                &package.root_dir,
+            );
+            // Programs are named after their package, to tell apart those of a workspace
+            let info = circuit_info(backend, package.name.clone(), &program.circuit, sources)?;
+            report.programs.push(info);
+        }
+    }
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&report).expect("Info reports should serialize");
+        println!("{json}");
+    } else {
+        let mut print = |line: String| println!("{line}");
+        print(format!("Opcodes generated for language {:?}", backend.np_language()));
+        for program in &report.programs {
+            write_circuit_info(program, "", &mut print);
+        }
+        for contract in &report.contracts {
+            print(format!("Contract {}", contract.name));
+            for function in &contract.functions {
+                write_circuit_info(function, "  ", &mut print);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct InfoReport {
+    programs: Vec<CircuitInfo>,
+    contracts: Vec<ContractInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContractInfo {
+    name: String,
+    functions: Vec<CircuitInfo>,
+}
+
+/// Information on the circuit generated for a single function.
+#[derive(Debug, Serialize)]
+pub(crate) struct CircuitInfo {
+    pub(crate) name: String,
+    pub(crate) acir_opcodes: OpcodeCounts,
+    /// The ACIR opcodes attributed to the functions and lines they were generated from
+    pub(crate) sources: SourceBreakdown,
+    /// The size of the circuit once it has been compiled by the backend
+    pub(crate) circuit_size: u32,
+}
+
+/// Compiles the program of the package at `package_root` and returns information on its circuit.
+pub(crate) fn program_info<B: Backend>(
+    backend: &B,
+    name: String,
+    package_root: &Path,
+    compile_options: &CompileOptions,
+) -> Result<CircuitInfo, CliError<B>> {
+    let mut driver = setup_driver(backend, package_root)?;
+    let result = driver.compile_main(compile_options);
+    let program = report_errors(result, &driver, compile_options.deny_warnings)?;
+
+    let opcode_count = program.circuit.opcodes.len();
+    let sources = SourceBreakdown::new(&driver, &program.debug, opcode_count, package_root);
+    circuit_info(backend, name, &program.circuit, sources)
+}
+
+fn circuit_info<B: Backend>(
+    backend: &B,
+    name: String,
+    circuit: &Circuit,
+    sources: SourceBreakdown,
+) -> Result<CircuitInfo, CliError<B>> {
+    let circuit_size =
+        backend.get_exact_circuit_size(circuit).map_err(CliError::ProofSystemCompilerError)?;
+
+    let acir_opcodes = OpcodeCounts::new(&circuit.opcodes);
+    Ok(CircuitInfo { name, acir_opcodes, sources, circuit_size })
+}
+
+/// Describes `info` line by line, passing each line to `output`.
+pub(crate) fn write_circuit_info(info: &CircuitInfo, indent: &str, output: &mut dyn FnMut(String)) {
+    let counts = &info.acir_opcodes;
+    output(format!("{indent}{}", info.name));
+    output(format!("{indent}  ACIR opcodes: {}", counts.total));
+
+    let mut kinds = vec![
+        ("arithmetic", counts.arithmetic),
+        ("range", counts.range),
+        ("directive", counts.directive),
+        ("memory", counts.memory),
+        ("oracle", counts.oracle),
+        ("brillig", counts.brillig),
+    ];
+    kinds.extend(counts.black_box.iter().map(|(name, count)| (name.as_str(), *count)));
+    for (kind, count) in kinds {
+        if count > 0 {
+            output(format!("{indent}    {kind}: {count}"));
+        }
+    }
+
+    let sources = &info.sources;
+    if !sources.functions.is_empty() {
+        output(format!("{indent}  ACIR opcodes by function:"));
+        for function in &sources.functions {
+            let FunctionOpcodes { name, location, opcodes } = function;
+            output(format!("{indent}    {name} ({location}): {opcodes}"));
+        }
+        output(format!("{indent}  ACIR opcodes by line:"));
+        for line in &sources.lines {
+            output(format!("{indent}    {}: {}", line.location, line.opcodes));
+        }
+    }
+    if sources.unattributed > 0 {
+        let unattributed = sources.unattributed;
+        output(format!("{indent}  ACIR opcodes without a source location: {unattributed}"));
+    }
+
+    output(format!("{indent}  Backend circuit size: {}", info.circuit_size));
+}
+
+/// The number of opcodes of each kind within a circuit.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct OpcodeCounts {
+    pub(crate) total: usize,
+    pub(crate) arithmetic: usize,
+    /// Range constraints, which are black box function calls counted apart from the others
+    pub(crate) range: usize,
+    /// The number of calls to each other black box function, by name
+    pub(crate) black_box: BTreeMap<String, usize>,
+    pub(crate) directive: usize,
+    pub(crate) memory: usize,
+    pub(crate) oracle: usize,
+    pub(crate) brillig: usize,
+}
+
+impl OpcodeCounts {
+    pub(crate) fn new(opcodes: &[Opcode]) -> OpcodeCounts {
+        let mut counts = OpcodeCounts::default();
+        for opcode in opcodes {
+            counts.add(opcode);
+        }
+        counts
+    }
+
+    pub(crate) fn add(&mut self, opcode: &Opcode) {
+        self.total += 1;
+        match opcode {
+            Opcode::Arithmetic(_) => self.arithmetic += 1,
+            Opcode::BlackBoxFuncCall(call) => match call.get_black_box_func() {
+                BlackBoxFunc::RANGE => self.range += 1,
+                func => *self.black_box.entry(func.name().to_owned()).or_default() += 1,
+            },
+            Opcode::Directive(_) => self.directive += 1,
+            Opcode::Block(_) | Opcode::ROM(_) | Opcode::RAM(_) => self.memory += 1,
+            Opcode::Oracle(_) => self.oracle += 1,
+            Opcode::Brillig(_) => self.brillig += 1,
+        }
+    }
+}
+
+/// The opcodes of a circuit attributed to the source code they were generated from.
+///
+/// Opcodes are attributed to the innermost location of their call stack, so the opcodes of an
+/// inlined function count towards that function and its lines rather than towards its callers.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct SourceBreakdown {
+    /// The number of opcodes generated within each function, most first
+    pub(crate) functions: Vec<FunctionOpcodes>,
+    /// The number of opcodes generated by each line, most first
+    pub(crate) lines: Vec<LineOpcodes>,
+    /// The number of opcodes which couldn't be attributed to any source code
+    pub(crate) unattributed: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct FunctionOpcodes {
+    pub(crate) name: String,
+    /// Where the function is declared, as `file:line`
+    pub(crate) location: String,
+    pub(crate) opcodes: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct LineOpcodes {
+    /// The line, as `file:line`
+    pub(crate) location: String,
+    pub(crate) opcodes: usize,
+}
+
+impl SourceBreakdown {
+    /// Attributes the opcodes of a circuit compiled by `driver` using its debug information.
+    /// Files are named relative to `root_dir` where possible.
+    pub(crate) fn new(
+        driver: &Driver,
+        debug: &DebugInfo,
+        opcode_count: usize,
+        root_dir: &Path,
+    ) -> SourceBreakdown {
+        let context = driver.context();
+        let describe = |location: &Location| describe_line(driver, location, root_dir);
+
+        let mut breakdown = SourceBreakdown::default();
+        let mut functions = HashMap::new();
+        let mut lines = HashMap::new();
+        for opcode_index in 0..opcode_count {
+            let location = match debug.opcode_call_stack(opcode_index).and_then(<[_]>::last) {
+                Some(location) => location,
+                None => {
+                    breakdown.unattributed += 1;
+                    continue;
+                }
+            };
+
+            *lines.entry(describe(location)).or_insert(0) += 1;
+            if let Some(func_id) =
+                context.def_interner.function_at(location.file, location.span.start())
+            {
+                *functions.entry(func_id).or_insert(0) += 1;
+            }
+        }
+
+        breakdown.functions = functions
+            .into_iter()
+            .map(|(func_id, opcodes)| FunctionOpcodes {
+                name: context.def_interner.function_name(&func_id).to_owned(),
+                location: describe(&context.def_interner.function_meta(&func_id).location),
+                opcodes,
+            })
+            .collect();
+        breakdown
+            .functions
+            .sort_by(|a, b| b.opcodes.cmp(&a.opcodes).then_with(|| a.location.cmp(&b.location)));
+
+        breakdown.lines = lines
+            .into_iter()
+            .map(|(location, opcodes)| LineOpcodes { location, opcodes })
+            .collect();
+        breakdown
+            .lines
+            .sort_by(|a, b| b.opcodes.cmp(&a.opcodes).then_with(|| a.location.cmp(&b.location)));
+        breakdown
+    }
+}
+
+/// Describes the line of `location` as `file:line`.
+fn describe_line(driver: &Driver, location: &Location, root_dir: &Path) -> String {
+    let file = driver.file_manager().fetch_file(location.file);
+    let path = file.path().strip_prefix(root_dir).unwrap_or_else(|_| file.path());
+    let offset = (location.span.start() as usize).min(file.source().len());
+    let line = file.source().as_bytes()[..offset].iter().filter(|byte| **byte == b'\n').count() + 1;
+    format!("{}:{line}", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        acir::{
+            circuit::{
+                opcodes::{BlackBoxFuncCall, FunctionInput},
+                Opcode,
+            },
+            native_types::{Expression, Witness},
+        },
+        Language,
+    };
+    use noirc_driver::{CompileOptions, Driver};
+    use noirc_frontend::graph::CrateType;
+
+    use super::{OpcodeCounts, SourceBreakdown};
+    use crate::resolver::add_std_lib;
+
+    const PROGRAM: &str = "fn main(x: Field) {
+    let y = x + 1;
+    check(y);
+}
+
+fn check(x: Field) {
+    assert(x * x == 4);
+}
+";
+
+    #[test]
+    fn counts_opcodes_by_kind() {
+        let input = || FunctionInput { witness: Witness(1), num_bits: 8 };
+        let opcodes = vec![
+            Opcode::Arithmetic(Expression::default()),
+            Opcode::Arithmetic(Expression::default()),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input: input() }),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::AND {
+                lhs: input(),
+                rhs: input(),
+                output: Witness(2),
+            }),
+        ];
+
+        let counts = OpcodeCounts::new(&opcodes);
+        assert_eq!(counts.total, 4);
+        assert_eq!(counts.arithmetic, 2);
+        assert_eq!(counts.range, 1);
+        assert_eq!(counts.black_box.len(), 1);
+        assert_eq!(counts.black_box.values().sum::<usize>(), 1);
+    }
+
+    #[test]
+    fn attributes_opcodes_to_functions_and_lines() {
+        let dir = tempdir::TempDir::new("info").unwrap();
+        let main = dir.path().join("main.nr");
+        std::fs::write(&main, PROGRAM).unwrap();
+
+        let mut driver = Driver::new(&Language::PLONKCSat { width: 3 }, Box::new(|_| true));
+        driver.create_local_crate(&main, CrateType::Binary);
+        add_std_lib(&mut driver);
+        let (program, _) = driver.compile_main(&CompileOptions::default()).unwrap();
+
+        let opcode_count = program.circuit.opcodes.len();
+        let sources = SourceBreakdown::new(&driver, &program.debug, opcode_count, dir.path());
+
+        // The assertion is inlined into `main`, but its opcodes still count towards `check`
+        let check = sources.functions.iter().find(|function| function.name == "check").unwrap();
+        assert_eq!(check.location, "main.nr:6");
+        assert!(check.opcodes > 0);
+
+        let assertion = sources.lines.iter().find(|line| line.location == "main.nr:7").unwrap();
+        assert_eq!(assertion.opcodes, check.opcodes);
+
+        let attributed: usize = sources.lines.iter().map(|line| line.opcodes).sum();
+        assert_eq!(attributed + sources.unattributed, opcode_count);
+    }
+}
@@ -4,13 +4,35 @@ use async_lsp::{
     panic::CatchUnwindLayer, server::LifecycleLayer, stdio::PipeStdin, tracing::TracingLayer,
 };
 use clap::Args;
-use noir_lsp::NargoLspService;
-use noirc_driver::CompileOptions;
+use nargo::ops::{DefaultForeignCallResolver, FuzzOptions};
+use noir_lsp::{NargoCommand, NargoLspService};
+use noirc_driver::{CompileOptions, Driver};
+use noirc_frontend::formatter;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 use tokio::io::BufReader;
 use tower::ServiceBuilder;
 
-use super::NargoConfig;
-use crate::errors::CliError;
+use super::{
+    check_cmd::check_crate_and_report_errors,
+    compile_cmd::setup_driver,
+    execute_cmd::execute_with_path,
+    info_cmd::{program_info, write_circuit_info},
+    prove_cmd::prove_with_path,
+    test_cmd::{run_test, TestStatus},
+    NargoConfig,
+};
+use crate::{
+    constants::{PKG_FILE, PROOFS_DIR, PROVER_INPUT_FILE, VERIFIER_INPUT_FILE},
+    errors::CliError,
+    find_package_root,
+    manifest::parse,
+    nargo_crates,
+    resolver::Resolver,
+    workspace::workspace_root,
+};
 
 #[derive(Debug, Clone, Args)]
 pub(crate) struct LspCommand {
@@ -18,10 +40,10 @@ pub(crate) struct LspCommand {
     compile_options: CompileOptions,
 }
 
-pub(crate) fn run<B: Backend>(
+pub(crate) fn run<B: Backend + 'static>(
     // Backend is currently unused, but we might want to use it to inform the lsp in the future
     _backend: &B,
-    _args: LspCommand,
+    args: LspCommand,
     _config: NargoConfig,
 ) -> Result<(), CliError<B>> {
     use tokio::runtime::Builder;
@@ -29,7 +51,17 @@ pub(crate) fn run<B: Backend>(
     let runtime = Builder::new_current_thread().enable_all().build().unwrap();
 
     let (server, _) = async_lsp::Frontend::new_server(|client| {
-        let router = NargoLspService::new();
+        let compile_options = args.compile_options.clone();
+        let router = NargoLspService::new(
+            &client,
+            Box::new(setup_driver_for_file::<B>),
+            Box::new(move |file_path, command, output| {
+                run_lsp_command::<B>(file_path, command, &compile_options, output)
+                    .map_err(|err| err.to_string())
+            }),
+            Box::new(load_format_config),
+            Box::new(find_workspace_root),
+        );
 
         ServiceBuilder::new()
             .layer(TracingLayer::default())
@@ -46,3 +78,138 @@ pub(crate) fn run<B: Backend>(
         server.run(stdin, stdout).await.map_err(CliError::LspError)
     })
 }
+
+/// Resolves the package containing `file_path` so that the lsp can check it, using the
+/// contents of the documents open in the editor in place of the files on disk.
+fn setup_driver_for_file<B: Backend>(
+    file_path: &Path,
+    open_documents: &HashMap<PathBuf, String>,
+) -> Result<Driver, String> {
+    let package_root = find_package_root(file_path).map_err(|err| err.to_string())?;
+
+    let backend = B::default();
+    let mut driver = Driver::new(
+        &backend.np_language(),
+        // TODO(#1102): Remove need for driver to be aware of backend.
+        Box::new(|op| B::default().supports_opcode(op)),
+    );
+    for (path, contents) in open_documents {
+        driver.add_file_overlay(path.clone(), contents.clone());
+    }
+
+    Resolver::resolve_package(&mut driver, &package_root).map_err(|err| err.to_string())?;
+    Ok(driver)
+}
+
+/// Returns the root of the workspace containing `file_path`.
+///
+/// Git dependencies are checked out into a cache shared by every package, so a file within it
+/// has no workspace of its own.
+fn find_workspace_root(file_path: &Path) -> Result<PathBuf, String> {
+    let package_root = find_package_root(file_path).map_err(|err| err.to_string())?;
+    if package_root.starts_with(nargo_crates()) {
+        return Err("files in git dependencies cannot be edited".to_owned());
+    }
+    Ok(workspace_root(&package_root))
+}
+
+/// Reads the formatting options from the manifest of the package containing `file_path`.
+fn load_format_config(file_path: &Path) -> Result<formatter::Config, String> {
+    let package_root = find_package_root(file_path).map_err(|err| err.to_string())?;
+    let manifest = parse(package_root.join(PKG_FILE)).map_err(|err| err.to_string())?;
+    Ok(manifest.fmt)
+}
+
+/// Runs a command requested through a code lens on the package containing `file_path`.
+///
+/// Standard output is used to communicate with the editor, so anything the command would
+/// usually print is passed to `output` instead.
+fn run_lsp_command<B: Backend>(
+    file_path: &Path,
+    command: &NargoCommand,
+    compile_options: &CompileOptions,
+    output: &mut dyn FnMut(String),
+) -> Result<(), CliError<B>> {
+    let backend = B::default();
+    let package_root =
+        find_package_root(file_path).map_err(|err| CliError::Generic(err.to_string()))?;
+    // Printing would be mistaken for a message to the editor
+    let mut foreign_calls = DefaultForeignCallResolver::new(false);
+
+    match command {
+        NargoCommand::Test { test_name } => {
+            let mut driver = setup_driver(&backend, &package_root)?;
+            check_crate_and_report_errors(&mut driver, compile_options.deny_warnings)?;
+
+            let test_function = driver
+                .get_all_test_functions_in_crate_matching(test_name)
+                .into_iter()
+                .find(|test_function| driver.function_name(test_function.id) == test_name)
+                .ok_or_else(|| CliError::Generic(format!("Could not find test '{test_name}'")))?;
+
+            output(format!("Testing {test_name}..."));
+            let fuzz_options = FuzzOptions::default();
+            let result = run_test(
+                &backend,
+                &test_function,
+                &driver,
+                &package_root,
+                compile_options,
+                &fuzz_options,
+                &mut foreign_calls,
+            );
+            match result.status {
+                TestStatus::Pass => output("ok".to_owned()),
+                TestStatus::Fail { message } => return Err(CliError::Generic(message)),
+            }
+        }
+        NargoCommand::Execute => {
+            let (return_value, _) = execute_with_path(
+                &backend,
+                &package_root,
+                PROVER_INPUT_FILE.to_owned(),
+                compile_options,
+                &mut foreign_calls,
+            )?;
+
+            output("Circuit witness successfully solved".to_owned());
+            if let Some(return_value) = return_value {
+                output(format!("Circuit output: {return_value:?}"));
+            }
+        }
+        NargoCommand::Prove => {
+            // Proofs are saved to a file as printing them would be mistaken for a message
+            let proof_name = package_root
+                .file_name()
+                .map_or_else(|| "proof".to_owned(), |name| name.to_string_lossy().into_owned());
+            let proof_path = prove_with_path(
+                &backend,
+                Some(proof_name),
+                PROVER_INPUT_FILE.to_owned(),
+                VERIFIER_INPUT_FILE.to_owned(),
+                package_root.clone(),
+                package_root.join(PROOFS_DIR),
+                None,
+                false,
+                compile_options,
+                &mut foreign_calls,
+            )?;
+
+            if let Some(proof_path) = proof_path {
+                output(format!("Proof saved to {}", proof_path.display()));
+            }
+        }
+        NargoCommand::Info => {
+            // The same report as `nargo info`, line by line
+            let package_name = package_root
+                .file_name()
+                .map_or_else(|| "main".to_owned(), |name| name.to_string_lossy().into_owned());
+            let info = program_info(&backend, package_name, &package_root, compile_options)?;
+
+            output(format!("Opcodes generated for language {:?}", backend.np_language()));
+            write_circuit_info(&info, "", output);
+        }
+    }
+
+    Ok(())
+}
@@ -1,23 +1,36 @@
+use acvm::Backend;
 use clap::{Args, Parser, Subcommand};
 use const_format::formatcp;
+use nargo::workspace::{Package, Workspace};
 use noirc_driver::CompileOptions;
 use std::path::{Path, PathBuf};
 
 use color_eyre::eyre;
 
-use crate::find_package_root;
+use crate::{
+    errors::CliError,
+    find_package_root,
+    lockfile::{lock_dependencies, LockOptions},
+    workspace::resolve_workspace,
+};
 
+mod foreign_calls;
 mod fs;
 
 mod check_cmd;
 mod codegen_verifier_cmd;
 mod compile_cmd;
+mod doc_cmd;
 mod execute_cmd;
+mod fmt_cmd;
 mod gates_cmd;
+mod info_cmd;
 mod lsp_cmd;
 mod new_cmd;
 mod prove_cmd;
 mod test_cmd;
+mod tree_cmd;
+mod update_cmd;
 mod verify_cmd;
 
 const GIT_HASH: &str = env!("GIT_COMMIT");
@@ -42,6 +55,48 @@ struct NargoCli {
 pub(crate) struct NargoConfig {
     #[arg(short, long, hide=true, default_value_os_t = std::env::current_dir().unwrap())]
     program_dir: PathBuf,
+
+    /// Require Nargo.lock to be up to date, failing rather than pinning or updating dependencies
+    #[arg(long, global = true)]
+    locked: bool,
+
+    /// Use only dependencies which are pinned in Nargo.lock and already downloaded
+    #[arg(long, global = true)]
+    offline: bool,
+}
+
+/// Options choosing which packages of a workspace a command runs on
+#[derive(Args, Clone, Debug)]
+pub(crate) struct PackageOptions {
+    /// Run on every package in the workspace
+    #[arg(long, conflicts_with = "package")]
+    workspace: bool,
+
+    /// Run on the package with the given name
+    #[arg(long)]
+    package: Option<String>,
+}
+
+impl PackageOptions {
+    /// Returns the packages of the workspace to run on. Without either option, this is the
+    /// package at `program_dir`, or every package when run from the root of the workspace.
+    pub(crate) fn select<'a, B: Backend>(
+        &self,
+        workspace: &'a Workspace,
+        program_dir: &Path,
+    ) -> Result<Vec<&'a Package>, CliError<B>> {
+        if let Some(name) = &self.package {
+            return match workspace.member(name) {
+                Some(package) => Ok(vec![package]),
+                None => Err(CliError::Generic(format!("workspace has no package named `{name}`"))),
+            };
+        }
+
+        match workspace.member_at(program_dir) {
+            Some(package) if !self.workspace => Ok(vec![package]),
+            _ => Ok(workspace.members.iter().collect()),
+        }
+    }
 }
 
 #[non_exhaustive]
@@ -50,13 +105,18 @@ enum NargoCommand {
     Check(check_cmd::CheckCommand),
     CodegenVerifier(codegen_verifier_cmd::CodegenVerifierCommand),
     Compile(compile_cmd::CompileCommand),
+    Doc(doc_cmd::DocCommand),
     New(new_cmd::NewCommand),
     Execute(execute_cmd::ExecuteCommand),
+    Fmt(fmt_cmd::FmtCommand),
     Prove(prove_cmd::ProveCommand),
     Verify(verify_cmd::VerifyCommand),
     Test(test_cmd::TestCommand),
     Gates(gates_cmd::GatesCommand),
+    Info(info_cmd::InfoCommand),
     Lsp(lsp_cmd::LspCommand),
+    Tree(tree_cmd::TreeCommand),
+    Update(update_cmd::UpdateCommand),
 }
 
 pub fn start_cli() -> eyre::Result<()> {
@@ -67,19 +127,38 @@ pub fn start_cli() -> eyre::Result<()> {
         config.program_dir = find_package_root(&config.program_dir)?;
     }
 
+    // Pin the git dependencies of the whole workspace before anything resolves them.
+    // `nargo update` re-pins them itself.
+    if !matches!(
+        command,
+        NargoCommand::New(_)
+            | NargoCommand::Fmt(_)
+            | NargoCommand::Lsp(_)
+            | NargoCommand::Update(_)
+    ) {
+        let workspace = resolve_workspace(&config.program_dir)?;
+        let lock_options = LockOptions { locked: config.locked, offline: config.offline };
+        lock_dependencies(&workspace, lock_options)?;
+    }
+
     let backend = crate::backends::ConcreteBackend::default();
 
     match command {
         NargoCommand::New(args) => new_cmd::run(&backend, args, config),
         NargoCommand::Check(args) => check_cmd::run(&backend, args, config),
         NargoCommand::Compile(args) => compile_cmd::run(&backend, args, config),
+        NargoCommand::Doc(args) => doc_cmd::run(&backend, args, config),
         NargoCommand::Execute(args) => execute_cmd::run(&backend, args, config),
+        NargoCommand::Fmt(args) => fmt_cmd::run(&backend, args, config),
         NargoCommand::Prove(args) => prove_cmd::run(&backend, args, config),
         NargoCommand::Verify(args) => verify_cmd::run(&backend, args, config),
         NargoCommand::Test(args) => test_cmd::run(&backend, args, config),
         NargoCommand::Gates(args) => gates_cmd::run(&backend, args, config),
+        NargoCommand::Info(args) => info_cmd::run(&backend, args, config),
         NargoCommand::CodegenVerifier(args) => codegen_verifier_cmd::run(&backend, args, config),
         NargoCommand::Lsp(args) => lsp_cmd::run(&backend, args, config),
+        NargoCommand::Tree(args) => tree_cmd::run(&backend, args, config),
+        NargoCommand::Update(args) => update_cmd::run(&backend, args, config),
     }?;
 
     Ok(())
@@ -130,12 +209,19 @@ pub fn prove_and_verify(program_dir: &Path, experimental_ssa: bool) -> bool {
     )
     .expect("Should read inputs");
 
-    let solved_witness =
-        match execute_cmd::execute_program(&backend, bytecode.clone(), &abi, &inputs_map) {
-            Ok(witness) => witness,
-            // Failure to execute is an invalid proof
-            Err(_) => return false,
-        };
+    let mut foreign_calls = nargo::ops::DefaultForeignCallResolver::new(false);
+    let solved_witness = match execute_cmd::execute_program(
+        &backend,
+        bytecode.clone(),
+        &abi,
+        &inputs_map,
+        None,
+        &mut foreign_calls,
+    ) {
+        Ok(witness) => witness,
+        // Failure to execute is an invalid proof
+        Err(_) => return false,
+    };
 
     let public_abi = abi.public_abi();
     let (public_inputs, return_value) =
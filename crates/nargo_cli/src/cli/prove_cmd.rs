@@ -3,13 +3,14 @@ use std::path::{Path, PathBuf};
 use acvm::Backend;
 use clap::Args;
 use nargo::artifacts::program::PreprocessedProgram;
-use nargo::ops::{preprocess_program, prove_execution, verify_proof};
+use nargo::ops::{preprocess_program, prove_execution, verify_proof, DefaultForeignCallResolver};
 use noirc_abi::input_parser::Format;
 use noirc_driver::CompileOptions;
 
 use super::NargoConfig;
 use super::{
-    compile_cmd::compile_circuit,
+    compile_cmd::{report_errors, setup_driver},
+    foreign_calls::ForeignCallOptions,
     fs::{
         common_reference_string::{
             read_cached_common_reference_string, update_common_reference_string,
@@ -22,8 +23,9 @@ use super::{
 };
 use crate::{
     cli::execute_cmd::execute_program,
-    constants::{PROOFS_DIR, PROVER_INPUT_FILE, TARGET_DIR, VERIFIER_INPUT_FILE},
+    constants::{PROOFS_DIR, PROVER_INPUT_FILE, VERIFIER_INPUT_FILE},
     errors::CliError,
+    workspace::target_dir,
 };
 
 /// Create proof for this program. The proof is returned as a hex encoded string.
@@ -47,6 +49,9 @@ pub(crate) struct ProveCommand {
     #[arg(long)]
     verify: bool,
 
+    #[clap(flatten)]
+    foreign_call_options: ForeignCallOptions,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -58,9 +63,11 @@ pub(crate) fn run<B: Backend>(
 ) -> Result<(), CliError<B>> {
     let proof_dir = config.program_dir.join(PROOFS_DIR);
 
-    let circuit_build_path = args
-        .circuit_name
-        .map(|circuit_name| config.program_dir.join(TARGET_DIR).join(circuit_name));
+    let circuit_build_path =
+        args.circuit_name.map(|circuit_name| target_dir(&config.program_dir).join(circuit_name));
+
+    let mut foreign_calls =
+        args.foreign_call_options.resolver::<B>(args.compile_options.show_output)?;
 
     prove_with_path(
         backend,
@@ -72,6 +79,7 @@ pub(crate) fn run<B: Backend>(
         circuit_build_path,
         args.verify,
         &args.compile_options,
+        &mut foreign_calls,
     )?;
 
     Ok(())
@@ -88,10 +96,12 @@ pub(crate) fn prove_with_path<B: Backend, P: AsRef<Path>>(
     circuit_build_path: Option<PathBuf>,
     check_proof: bool,
     compile_options: &CompileOptions,
+    foreign_calls: &mut DefaultForeignCallResolver,
 ) -> Result<Option<PathBuf>, CliError<B>> {
     let common_reference_string = read_cached_common_reference_string();
 
-    let (common_reference_string, preprocessed_program) = match circuit_build_path {
+    // Failing constraints can only be reported against the source code if it is compiled here
+    let (common_reference_string, preprocessed_program, debug_data) = match circuit_build_path {
         Some(circuit_build_path) => {
             let program = read_program_from_file(circuit_build_path)?;
             let common_reference_string = update_common_reference_string(
@@ -100,16 +110,20 @@ pub(crate) fn prove_with_path<B: Backend, P: AsRef<Path>>(
                 &program.bytecode,
             )
             .map_err(CliError::CommonReferenceStringError)?;
-            (common_reference_string, program)
+            (common_reference_string, program, None)
         }
         None => {
-            let program = compile_circuit(backend, program_dir.as_ref(), compile_options)?;
+            let mut driver = setup_driver(backend, program_dir.as_ref())?;
+            let result = driver.compile_main(compile_options);
+            let program = report_errors(result, &driver, compile_options.deny_warnings)?;
+
             let common_reference_string =
                 update_common_reference_string(backend, &common_reference_string, &program.circuit)
                     .map_err(CliError::CommonReferenceStringError)?;
+            let debug = program.debug.clone();
             let program = preprocess_program(backend, &common_reference_string, program)
                 .map_err(CliError::ProofSystemCompilerError)?;
-            (common_reference_string, program)
+            (common_reference_string, program, Some((debug, driver)))
         }
     };
 
@@ -122,7 +136,9 @@ pub(crate) fn prove_with_path<B: Backend, P: AsRef<Path>>(
     let (inputs_map, _) =
         read_inputs_from_file(&program_dir, prover_name.as_str(), Format::Toml, &abi)?;
 
-    let solved_witness = execute_program(backend, bytecode.clone(), &abi, &inputs_map)?;
+    let debug_data = debug_data.as_ref().map(|(debug, driver)| (debug, driver));
+    let solved_witness =
+        execute_program(backend, bytecode.clone(), &abi, &inputs_map, debug_data, foreign_calls)?;
 
     // Write public inputs into Verifier.toml
     let public_abi = abi.public_abi();
@@ -1,18 +1,46 @@
-use std::{io::Write, path::Path};
+use std::{
+    io::Write,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Sender},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 use acvm::{acir::native_types::WitnessMap, Backend};
-use clap::Args;
-use nargo::ops::execute_circuit;
-use noirc_driver::{CompileOptions, Driver};
-use noirc_frontend::node_interner::FuncId;
+use clap::{Args, ValueEnum};
+use fm::FileManager;
+use iter_extended::{try_vecmap, vecmap};
+use nargo::NargoError;
+use nargo::{
+    ops::{
+        execute_circuit, fuzz, DefaultForeignCallResolver, ForeignCallResolver, FuzzFailure,
+        FuzzOptions, DEFAULT_FUZZ_RUNS,
+    },
+    workspace::Package,
+};
+use noirc_abi::{input_parser::Format, Abi, InputMap};
+use noirc_driver::{CompileOptions, CompiledProgram, Driver, RuntimeError, RuntimeErrorKind};
+use noirc_errors::Span;
+use noirc_frontend::{hir::def_map::TestFunction, token::TestCase};
+use serde::Serialize;
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
 use crate::{
-    cli::{check_cmd::check_crate_and_report_errors, compile_cmd::setup_driver},
+    cli::{
+        check_cmd::check_crate_and_report_errors,
+        compile_cmd::setup_workspace_driver,
+        execute_cmd::{execution_error_diagnostic, execution_error_message},
+        foreign_calls::ForeignCallOptions,
+    },
     errors::CliError,
+    workspace::resolve_workspace,
 };
 
-use super::NargoConfig;
+use super::{NargoConfig, PackageOptions};
 
 /// Run the tests for this program
 #[derive(Debug, Clone, Args)]
@@ -20,10 +48,42 @@ pub(crate) struct TestCommand {
     /// If given, only tests with names containing this string will be run
     test_name: Option<String>,
 
+    /// The format to report the test results in
+    #[arg(long, value_enum, default_value_t = TestFormat::Pretty)]
+    format: TestFormat,
+
+    /// The number of tests to run at once, defaulting to the number of available CPUs
+    #[arg(long)]
+    test_threads: Option<NonZeroUsize>,
+
+    /// The number of random inputs each `#[test(fuzz)]` test is run with
+    #[arg(long, default_value_t = DEFAULT_FUZZ_RUNS)]
+    fuzz_runs: usize,
+
+    /// The seed to generate random inputs from, to reproduce a fuzzing failure
+    #[arg(long)]
+    fuzz_seed: Option<u64>,
+
+    #[clap(flatten)]
+    foreign_call_options: ForeignCallOptions,
+
+    #[clap(flatten)]
+    packages: PackageOptions,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TestFormat {
+    /// Report each test to stderr as it finishes
+    Pretty,
+    /// Write a JSON document with the result of each test to stdout
+    Json,
+    /// Write a JUnit XML report to stdout
+    Junit,
+}
+
 pub(crate) fn run<B: Backend>(
     backend: &B,
     args: TestCommand,
@@ -31,44 +91,64 @@ pub(crate) fn run<B: Backend>(
 ) -> Result<(), CliError<B>> {
     let test_name: String = args.test_name.unwrap_or_else(|| "".to_owned());
 
-    run_tests(backend, &config.program_dir, &test_name, &args.compile_options)
-}
+    let mut compile_options = args.compile_options;
+    if args.format != TestFormat::Pretty {
+        // Reports are written to stdout, which must not be interleaved with the tests' output
+        compile_options.show_output = false;
+    }
 
-fn run_tests<B: Backend>(
-    backend: &B,
-    program_dir: &Path,
-    test_name: &str,
-    compile_options: &CompileOptions,
-) -> Result<(), CliError<B>> {
-    let mut driver = setup_driver(backend, program_dir)?;
-    check_crate_and_report_errors(&mut driver, compile_options.deny_warnings)?;
+    let test_threads = args
+        .test_threads
+        .or_else(|| std::thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get);
+
+    let fuzz_options = FuzzOptions { runs: args.fuzz_runs, seed: args.fuzz_seed };
 
-    let test_functions = driver.get_all_test_functions_in_crate_matching(test_name);
-    println!("Running {} test functions...", test_functions.len());
-    let mut failing = 0;
+    let workspace = resolve_workspace(&config.program_dir)?;
+    let packages = args.packages.select(&workspace, &config.program_dir)?;
+
+    // A single resolver is shared by the tests, so an external resolver is only started once
+    let foreign_calls =
+        Mutex::new(args.foreign_call_options.resolver::<B>(compile_options.show_output)?);
 
     let writer = StandardStream::stderr(ColorChoice::Always);
     let mut writer = writer.lock();
 
-    for test_function in test_functions {
-        let test_name = driver.function_name(test_function);
-        writeln!(writer, "Testing {test_name}...").expect("Failed to write to stdout");
-        writer.flush().ok();
+    let (mut driver, crate_ids) = setup_workspace_driver(backend, &packages)?;
+    let mut suites = Vec::new();
+    for (package, crate_id) in packages.iter().zip(crate_ids) {
+        driver.select_local_crate(crate_id);
+        let results = run_tests(
+            &mut driver,
+            package,
+            packages.len() > 1,
+            &test_name,
+            args.format,
+            test_threads,
+            &compile_options,
+            &fuzz_options,
+            &foreign_calls,
+            &mut writer,
+        )?;
+        suites.push((package.name.clone(), results));
+    }
 
-        match run_test(backend, test_name, test_function, &driver, compile_options) {
-            Ok(_) => {
-                writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).ok();
-                writeln!(writer, "ok").ok();
-            }
-            // Assume an error was already printed to stdout
-            Err(_) => failing += 1,
-        }
-        writer.reset().ok();
+    match args.format {
+        TestFormat::Pretty => (),
+        TestFormat::Json => println!("{}", json_report(&suites)),
+        TestFormat::Junit => println!("{}", junit_report(&suites)),
     }
 
+    let failing = suites
+        .iter()
+        .flat_map(|(_, results)| results)
+        .filter(|(_, result)| !result.passed())
+        .count();
     if failing == 0 {
-        writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).unwrap();
-        writeln!(writer, "All tests passed").ok();
+        if args.format == TestFormat::Pretty {
+            writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).unwrap();
+            writeln!(writer, "All tests passed").ok();
+        }
     } else {
         let plural = if failing == 1 { "" } else { "s" };
         return Err(CliError::Generic(format!("{failing} test{plural} failed")));
@@ -78,28 +158,766 @@ fn run_tests<B: Backend>(
     Ok(())
 }
 
-fn run_test<B: Backend>(
-    backend: &B,
+/// The results of the tests of a package, by the name of each test.
+type TestResults = Vec<(String, TestResult)>;
+
+/// Runs the tests of the driver's local crate, which is the crate of `package`.
+#[allow(clippy::too_many_arguments)]
+fn run_tests<B: Backend>(
+    driver: &mut Driver,
+    package: &Package,
+    show_package: bool,
     test_name: &str,
-    main: FuncId,
+    format: TestFormat,
+    test_threads: usize,
+    compile_options: &CompileOptions,
+    fuzz_options: &FuzzOptions,
+    foreign_calls: &Mutex<DefaultForeignCallResolver>,
+    writer: &mut impl WriteColor,
+) -> Result<TestResults, CliError<B>> {
+    let program_dir = package.root_dir.as_path();
+    check_crate_and_report_errors(driver, compile_options.deny_warnings)?;
+
+    // Tests are listed in the order they are declared, regardless of the order they finish in
+    let mut test_functions = driver.get_all_test_functions_in_crate_matching(test_name);
+    test_functions.sort_by_key(|test_function| declaration_order(driver, test_function));
+    let test_names = vecmap(&test_functions, |test| driver.function_name(test.id).to_owned());
+
+    if format == TestFormat::Pretty {
+        if show_package {
+            println!("Running {} test functions in {}...", test_names.len(), package.name);
+        } else {
+            println!("Running {} test functions...", test_names.len());
+        }
+    }
+
+    // Drivers can't be shared between threads, so the tests are compiled here and only their
+    // circuits are run on the worker threads
+    let mut results: Vec<Option<TestResult>> = vecmap(&test_names, |_| None);
+    let mut compiled_tests = Vec::new();
+    for (index, test_function) in test_functions.iter().enumerate() {
+        match compile_test(driver, test_function, compile_options) {
+            Ok(test) => compiled_tests.push((index, test)),
+            Err(result) => {
+                if format == TestFormat::Pretty {
+                    report_pretty(writer, &test_names[index], &result);
+                }
+                results[index] = Some(result);
+            }
+        }
+    }
+
+    let next_test = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    let file_manager = driver.file_manager();
+
+    std::thread::scope(|scope| {
+        for _ in 0..test_threads.min(compiled_tests.len()) {
+            let sender = sender.clone();
+            let (compiled_tests, next_test) = (&compiled_tests, &next_test);
+            scope.spawn(move || {
+                run_test_worker::<B>(
+                    compiled_tests,
+                    next_test,
+                    file_manager,
+                    program_dir,
+                    fuzz_options,
+                    foreign_calls,
+                    sender,
+                );
+            });
+        }
+        // The workers hold the remaining senders, so results stop once they have all finished
+        drop(sender);
+
+        for (index, result) in receiver {
+            if format == TestFormat::Pretty {
+                report_pretty(writer, &test_names[index], &result);
+            }
+            results[index] = Some(result);
+        }
+    });
+
+    let results = test_names
+        .into_iter()
+        .zip(results)
+        .map(|(name, result)| {
+            let result = result
+                .unwrap_or_else(|| TestResult::not_run(format!("Test '{name}' could not be run")));
+            (name, result)
+        })
+        .collect();
+    Ok(results)
+}
+
+/// Orders test functions by the file and span of their names.
+fn declaration_order(driver: &Driver, test_function: &TestFunction) -> (PathBuf, Span) {
+    let path = driver.file_manager().fetch_file(test_function.location.file).path();
+    (path.to_path_buf(), test_function.location.span)
+}
+
+/// Runs tests from `compiled_tests` until there are none left, sending the result of each along
+/// with the index it was compiled with. Tests are taken in turn from `next_test`, which is shared
+/// with the other workers, as are the foreign call resolver and the files of the crate.
+fn run_test_worker<B: Backend>(
+    compiled_tests: &[(usize, CompiledTest<'_>)],
+    next_test: &AtomicUsize,
+    file_manager: &FileManager,
+    program_dir: &Path,
+    fuzz_options: &FuzzOptions,
+    foreign_calls: &Mutex<DefaultForeignCallResolver>,
+    results: Sender<(usize, TestResult)>,
+) {
+    let backend = B::default();
+    let mut foreign_calls = foreign_calls;
+
+    while let Some((index, test)) = compiled_tests.get(next_test.fetch_add(1, Ordering::Relaxed)) {
+        let result =
+            test.run(&backend, file_manager, program_dir, fuzz_options, &mut foreign_calls);
+        // The receiver only hangs up if reporting has been abandoned
+        if results.send((*index, result)).is_err() {
+            return;
+        }
+    }
+}
+
+/// The outcome of running a single test function.
+pub(crate) enum TestStatus {
+    Pass,
+    Fail { message: String },
+}
+
+/// The outcome of running a single test function, along with how long it took to compile and
+/// execute.
+pub(crate) struct TestResult {
+    pub(crate) status: TestStatus,
+    pub(crate) duration: Duration,
+    /// The number of ACIR opcodes in the test's circuit, if it could be compiled.
+    pub(crate) opcode_count: Option<usize>,
+}
+
+impl TestResult {
+    fn not_run(message: String) -> TestResult {
+        TestResult {
+            status: TestStatus::Fail { message },
+            duration: Duration::ZERO,
+            opcode_count: None,
+        }
+    }
+
+    fn passed(&self) -> bool {
+        matches!(self.status, TestStatus::Pass)
+    }
+}
+
+/// A test function whose circuit has been compiled, which can be run without the driver.
+struct CompiledTest<'a> {
+    test_function: &'a TestFunction,
+    test_name: &'a str,
+    program: CompiledProgram,
+    /// How long the test took to compile
+    compile_duration: Duration,
+}
+
+pub(crate) fn run_test<B: Backend>(
+    backend: &B,
+    test_function: &TestFunction,
     driver: &Driver,
+    package_root: &Path,
     config: &CompileOptions,
-) -> Result<(), CliError<B>> {
-    let program = driver
-        .compile_no_check(config, main)
-        .map_err(|_| CliError::Generic(format!("Test '{test_name}' failed to compile")))?;
-
-    // Run the backend to ensure the PWG evaluates functions like std::hash::pedersen,
-    // otherwise constraints involving these expressions will not error.
-    match execute_circuit(backend, program.circuit, WitnessMap::new()) {
-        Ok(_) => Ok(()),
-        Err(error) => {
-            let writer = StandardStream::stderr(ColorChoice::Always);
-            let mut writer = writer.lock();
+    fuzz_options: &FuzzOptions,
+    foreign_calls: &mut impl ForeignCallResolver,
+) -> TestResult {
+    match compile_test(driver, test_function, config) {
+        Ok(test) => {
+            test.run(backend, driver.file_manager(), package_root, fuzz_options, foreign_calls)
+        }
+        Err(result) => result,
+    }
+}
+
+/// Compiles the test's circuit, or returns the result of the test if it can already be decided
+/// without running it.
+fn compile_test<'a>(
+    driver: &'a Driver,
+    test_function: &'a TestFunction,
+    config: &CompileOptions,
+) -> Result<CompiledTest<'a>, TestResult> {
+    let start = Instant::now();
+    let test_name = driver.function_name(test_function.id);
+    match driver.compile_test(config, test_function.id) {
+        Ok(program) => Ok(CompiledTest {
+            test_function,
+            test_name,
+            program,
+            compile_duration: start.elapsed(),
+        }),
+        // Constraints on constant values are checked at compile time, so a test which should fail
+        // may never make it to execution. Any other error means the test itself is broken.
+        Err(RuntimeError {
+            kind: RuntimeErrorKind::FailingConstraint { assert_message }, ..
+        }) if test_function.should_fail() => Err(TestResult {
+            status: check_expected_failure(test_function, assert_message.as_deref()),
+            duration: start.elapsed(),
+            opcode_count: None,
+        }),
+        Err(_) => Err(TestResult {
+            status: TestStatus::Fail { message: format!("Test '{test_name}' failed to compile") },
+            duration: start.elapsed(),
+            opcode_count: None,
+        }),
+    }
+}
+
+impl CompiledTest<'_> {
+    fn run<B: Backend>(
+        &self,
+        backend: &B,
+        file_manager: &FileManager,
+        package_root: &Path,
+        fuzz_options: &FuzzOptions,
+        foreign_calls: &mut impl ForeignCallResolver,
+    ) -> TestResult {
+        let start = Instant::now();
+        let status = if self.test_function.fuzz {
+            self.fuzz(backend, file_manager, fuzz_options, foreign_calls)
+        } else {
+            self.run_cases(backend, file_manager, package_root, foreign_calls)
+        };
+
+        TestResult {
+            status,
+            duration: self.compile_duration + start.elapsed(),
+            opcode_count: Some(self.program.circuit.opcodes.len()),
+        }
+    }
+
+    /// Runs the test once for each of its cases, or once without inputs if it has none, stopping
+    /// at the first case which fails.
+    fn run_cases<B: Backend>(
+        &self,
+        backend: &B,
+        file_manager: &FileManager,
+        package_root: &Path,
+        foreign_calls: &mut impl ForeignCallResolver,
+    ) -> TestStatus {
+        let abi = &self.program.abi;
+        let cases = match test_case_witnesses(&self.test_function.cases, abi, package_root) {
+            Ok(cases) => cases,
+            Err(message) => return TestStatus::Fail { message },
+        };
+
+        for (case, initial_witness) in cases {
+            let status = self.execute(backend, file_manager, initial_witness, foreign_calls);
+            if let TestStatus::Fail { message } = status {
+                let message = match case {
+                    Some(case) => format!("Failed with {case}: {message}"),
+                    None => message,
+                };
+                return TestStatus::Fail { message };
+            }
+        }
+        TestStatus::Pass
+    }
+
+    /// Runs the test with randomly generated inputs, reporting the smallest inputs it fails for.
+    fn fuzz<B: Backend>(
+        &self,
+        backend: &B,
+        file_manager: &FileManager,
+        fuzz_options: &FuzzOptions,
+        foreign_calls: &mut impl ForeignCallResolver,
+    ) -> TestStatus {
+        let abi = &self.program.abi;
+        let failure = fuzz(abi, fuzz_options, |inputs| {
+            let initial_witness = abi.encode(inputs, None).map_err(|error| error.to_string())?;
+            match self.execute(backend, file_manager, initial_witness, foreign_calls) {
+                TestStatus::Pass => Ok(()),
+                TestStatus::Fail { message } => Err(message),
+            }
+        });
+
+        match failure {
+            Ok(None) => TestStatus::Pass,
+            Ok(Some(FuzzFailure { inputs, message, seed })) => {
+                let inputs =
+                    Format::Toml.serialize(&inputs).unwrap_or_else(|error| error.to_string());
+                TestStatus::Fail {
+                    message: format!(
+                        "{message}\nSmallest failing inputs (--fuzz-seed {seed}):\n{}",
+                        inputs.trim_end()
+                    ),
+                }
+            }
+            Err(error) => TestStatus::Fail { message: error.to_string() },
+        }
+    }
+
+    /// Executes the test's circuit from the given initial witness, checking that it fails if it
+    /// should.
+    fn execute<B: Backend>(
+        &self,
+        backend: &B,
+        file_manager: &FileManager,
+        initial_witness: WitnessMap,
+        foreign_calls: &mut impl ForeignCallResolver,
+    ) -> TestStatus {
+        let (test_function, program) = (self.test_function, &self.program);
+        // Run the backend to ensure the PWG evaluates functions like std::hash::pedersen,
+        // otherwise constraints involving these expressions will not error.
+        match execute_circuit(backend, program.circuit.clone(), initial_witness, foreign_calls) {
+            Ok(_) if test_function.should_fail() => TestStatus::Fail {
+                message: format!("Test '{}' passed but should have failed", self.test_name),
+            },
+            Ok(_) => TestStatus::Pass,
+            // Only a constraint rejecting the witness counts as the failure the test expects
+            Err(NargoError::UnsatisfiedConstraint { opcode_index })
+                if test_function.should_fail() =>
+            {
+                let assert_message = program.debug.opcode_assert_message(opcode_index);
+                check_expected_failure(test_function, assert_message)
+            }
+            Err(NargoError::SolvingError(_)) if test_function.should_fail() => {
+                check_expected_failure(test_function, None)
+            }
+            Err(error) => {
+                let message = match execution_error_diagnostic(&error, &program.debug) {
+                    Some(diagnostic) => {
+                        let rendered = noirc_errors::reporter::render(file_manager, &diagnostic);
+                        rendered.trim_end().to_owned()
+                    }
+                    None => execution_error_message(&error, &program.debug),
+                };
+                TestStatus::Fail { message }
+            }
+        }
+    }
+}
+
+/// Returns the initial witness for each case the test is run with, along with the case itself.
+/// Tests without any cases are run once, without inputs.
+fn test_case_witnesses<'a>(
+    cases: &'a [TestCase],
+    abi: &Abi,
+    package_root: &Path,
+) -> Result<Vec<(Option<&'a TestCase>, WitnessMap)>, String> {
+    if cases.is_empty() {
+        return Ok(vec![(None, WitnessMap::new())]);
+    }
+
+    try_vecmap(cases, |case| {
+        let inputs = read_test_case(case, abi, package_root)
+            .map_err(|error| format!("Failed to read {case}: {error}"))?;
+        let initial_witness =
+            abi.encode(&inputs, None).map_err(|error| format!("Invalid {case}: {error}"))?;
+        Ok((Some(case), initial_witness))
+    })
+}
+
+fn read_test_case(case: &TestCase, abi: &Abi, package_root: &Path) -> Result<InputMap, String> {
+    match case {
+        TestCase::Inline(inputs) => {
+            // The case is the contents of an inline table, which is rewritten as a document so
+            // that it can be parsed in the same way as `Prover.toml`
+            let document: toml::Table = toml::from_str(&format!("case = {{ {inputs} }}"))
+                .map_err(|error| error.to_string())?;
+            let inputs = toml::to_string(&document["case"]).map_err(|error| error.to_string())?;
+            Format::Toml.parse(&inputs, abi).map_err(|error| error.to_string())
+        }
+        TestCase::File(path) => {
+            let path = package_root.join(path);
+            let format = match path.extension() {
+                Some(extension) if extension == Format::Json.ext() => Format::Json,
+                _ => Format::Toml,
+            };
+            let input_string = std::fs::read_to_string(&path)
+                .map_err(|error| format!("{}: {error}", path.display()))?;
+            format.parse(&input_string, abi).map_err(|error| error.to_string())
+        }
+    }
+}
+
+/// Checks that a test which should fail was rejected by an assertion with the message it was
+/// expected to, given the message of the assertion which failed.
+fn check_expected_failure(
+    test_function: &TestFunction,
+    assert_message: Option<&str>,
+) -> TestStatus {
+    match (test_function.failure_reason(), assert_message) {
+        (Some(reason), Some(message)) if !message.contains(reason) => TestStatus::Fail {
+            message: format!("Test failed with message '{message}', expected '{reason}'"),
+        },
+        (Some(reason), None) => TestStatus::Fail {
+            message: format!("Test failed without a message, expected '{reason}'"),
+        },
+        _ => TestStatus::Pass,
+    }
+}
+
+fn report_pretty(writer: &mut impl WriteColor, test_name: &str, result: &TestResult) {
+    write!(writer, "Testing {test_name}... ").expect("Failed to write to stderr");
+    match &result.status {
+        TestStatus::Pass => {
+            writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).ok();
+            writeln!(writer, "ok").ok();
+        }
+        TestStatus::Fail { message } => {
             writer.set_color(ColorSpec::new().set_fg(Some(Color::Red))).ok();
             writeln!(writer, "failed").ok();
             writer.reset().ok();
-            Err(error.into())
+            writeln!(writer, "{message}").ok();
+        }
+    }
+    writer.reset().ok();
+    writer.flush().ok();
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    passed: usize,
+    failed: usize,
+    tests: Vec<JsonTestResult<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonTestResult<'a> {
+    package: &'a str,
+    name: &'a str,
+    status: &'static str,
+    /// The time taken to compile and execute the test, in seconds
+    exec_time: f64,
+    acir_opcodes: Option<usize>,
+    message: Option<&'a str>,
+}
+
+/// Reports the results of the tests of each package as a single JSON document.
+fn json_report(suites: &[(String, TestResults)]) -> String {
+    let results = suites.iter().flat_map(|(package, results)| {
+        results.iter().map(move |(name, result)| (package.as_str(), name.as_str(), result))
+    });
+    let tests: Vec<_> = results
+        .map(|(package, name, result)| {
+            let (status, message) = match &result.status {
+                TestStatus::Pass => ("ok", None),
+                TestStatus::Fail { message } => ("failed", Some(message.as_str())),
+            };
+            JsonTestResult {
+                package,
+                name,
+                status,
+                exec_time: result.duration.as_secs_f64(),
+                acir_opcodes: result.opcode_count,
+                message,
+            }
+        })
+        .collect();
+
+    let failed = tests.iter().filter(|test| test.status != "ok").count();
+    let report = JsonReport { passed: tests.len() - failed, failed, tests };
+    serde_json::to_string_pretty(&report).expect("Test reports should serialize to JSON")
+}
+
+/// Reports the results of the tests of each package as a JUnit XML document, with a test suite
+/// for each package.
+fn junit_report(suites: &[(String, TestResults)]) -> String {
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+    for (suite_name, results) in suites {
+        report += &junit_suite(suite_name, results);
+    }
+    report += "</testsuites>";
+    report
+}
+
+fn junit_suite(suite_name: &str, results: &[(String, TestResult)]) -> String {
+    let failed = results.iter().filter(|(_, result)| !result.passed()).count();
+    let total_time: Duration = results.iter().map(|(_, result)| result.duration).sum();
+    let suite_name = escape_xml(suite_name);
+
+    let mut report = format!(
+        "  <testsuite name=\"{suite_name}\" tests=\"{}\" failures=\"{failed}\" time=\"{:.3}\">\n",
+        results.len(),
+        total_time.as_secs_f64()
+    );
+
+    for (name, result) in results {
+        report += &format!(
+            "    <testcase classname=\"{suite_name}\" name=\"{}\" time=\"{:.3}\">\n",
+            escape_xml(name),
+            result.duration.as_secs_f64()
+        );
+        if let Some(opcode_count) = result.opcode_count {
+            report += "      <properties>\n";
+            report +=
+                &format!("        <property name=\"acir_opcodes\" value=\"{opcode_count}\"/>\n");
+            report += "      </properties>\n";
+        }
+        if let TestStatus::Fail { message } = &result.status {
+            let message = escape_xml(message);
+            report += &format!("      <failure message=\"{message}\">{message}</failure>\n");
+        }
+        report += "    </testcase>\n";
+    }
+
+    report += "  </testsuite>\n";
+    report
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, time::Duration};
+
+    use acvm::{
+        acir::{circuit::Opcode, native_types::Witness},
+        FieldElement, Language,
+    };
+    use nargo::ops::{DefaultForeignCallResolver, FuzzOptions};
+    use noirc_abi::{input_parser::InputValue, Abi, AbiParameter, AbiType, AbiVisibility};
+    use noirc_driver::{CompileOptions, Driver};
+    use noirc_frontend::{graph::CrateType, token::TestCase};
+
+    use super::{
+        json_report, junit_report, read_test_case, run_test, test_case_witnesses, TestResult,
+        TestResults, TestStatus,
+    };
+    use crate::{backends::ConcreteBackend, resolver::add_std_lib};
+
+    const SHOULD_FAIL_TESTS: &str = r#"
+        #[test(should_fail)]
+        fn constant_assertion() {
+            assert(1 == 2);
+        }
+
+        #[test(should_fail_with = "too big")]
+        fn constant_assertion_with_message() {
+            assert(1 == 2, "value is too big");
+        }
+
+        #[test(should_fail_with = "too big")]
+        fn constant_assertion_with_other_message() {
+            assert(1 == 2, "value is too small");
+        }
+
+        #[test(should_fail, case = "x = 1")]
+        fn unsupported_opcode(x: Field) {
+            assert(std::hash::pedersen([x])[0] == 0);
+        }
+    "#;
+
+    /// Checks the tests in `source`, with a backend which doesn't support any black box functions.
+    fn check_tests(dir: &Path, source: &str) -> Driver {
+        let main = dir.join("main.nr");
+        std::fs::write(&main, source).unwrap();
+
+        let mut driver = Driver::new(
+            &Language::R1CS,
+            Box::new(|opcode| !matches!(opcode, Opcode::BlackBoxFuncCall(_))),
+        );
+        driver.create_local_crate(&main, CrateType::Binary);
+        add_std_lib(&mut driver);
+        driver.check_crate(false).unwrap();
+        driver
+    }
+
+    fn run_named_test(driver: &Driver, dir: &Path, test_name: &str) -> TestStatus {
+        let test_function = driver
+            .get_all_test_functions_in_crate_matching(test_name)
+            .into_iter()
+            .find(|test_function| driver.function_name(test_function.id) == test_name)
+            .unwrap();
+        let result = run_test(
+            &ConcreteBackend::default(),
+            &test_function,
+            driver,
+            dir,
+            &CompileOptions::default(),
+            &FuzzOptions::default(),
+            &mut DefaultForeignCallResolver::new(false),
+        );
+        result.status
+    }
+
+    #[test]
+    fn should_fail_only_accepts_failing_assertions() {
+        let dir = tempdir::TempDir::new("should_fail").unwrap();
+        let driver = check_tests(dir.path(), SHOULD_FAIL_TESTS);
+
+        assert!(matches!(
+            run_named_test(&driver, dir.path(), "constant_assertion"),
+            TestStatus::Pass
+        ));
+        assert!(matches!(
+            run_named_test(&driver, dir.path(), "constant_assertion_with_message"),
+            TestStatus::Pass
+        ));
+        match run_named_test(&driver, dir.path(), "constant_assertion_with_other_message") {
+            TestStatus::Fail { message } => assert!(message.contains("value is too small")),
+            TestStatus::Pass => panic!("the assertion's message doesn't match"),
+        }
+        // The circuit can't be compiled for the backend, which isn't the failure the test expects
+        match run_named_test(&driver, dir.path(), "unsupported_opcode") {
+            TestStatus::Fail { message } => assert!(message.contains("failed to compile")),
+            TestStatus::Pass => panic!("a compile error was mistaken for a failing assertion"),
         }
     }
+
+    fn results() -> Vec<(String, TestResults)> {
+        let results = vec![
+            (
+                "test_passes".to_owned(),
+                TestResult {
+                    status: TestStatus::Pass,
+                    duration: Duration::from_millis(1500),
+                    opcode_count: Some(3),
+                },
+            ),
+            (
+                "test_fails".to_owned(),
+                TestResult {
+                    status: TestStatus::Fail {
+                        message: "Cannot satisfy constraint <1>".to_owned(),
+                    },
+                    duration: Duration::ZERO,
+                    opcode_count: None,
+                },
+            ),
+        ];
+        vec![("my_package".to_owned(), results)]
+    }
+
+    #[test]
+    fn reports_json() {
+        let report: serde_json::Value = serde_json::from_str(&json_report(&results())).unwrap();
+
+        assert_eq!(report["passed"], 1);
+        assert_eq!(report["failed"], 1);
+        assert_eq!(report["tests"][0]["package"], "my_package");
+        assert_eq!(report["tests"][0]["name"], "test_passes");
+        assert_eq!(report["tests"][0]["status"], "ok");
+        assert_eq!(report["tests"][0]["exec_time"], 1.5);
+        assert_eq!(report["tests"][0]["acir_opcodes"], 3);
+        assert_eq!(report["tests"][1]["status"], "failed");
+        assert_eq!(report["tests"][1]["message"], "Cannot satisfy constraint <1>");
+    }
+
+    #[test]
+    fn reports_junit() {
+        let report = junit_report(&results());
+
+        assert!(report.contains(r#"<testsuite name="my_package" tests="2" failures="1""#));
+        assert!(
+            report.contains(r#"<testcase classname="my_package" name="test_passes" time="1.500">"#)
+        );
+        assert!(report.contains(r#"<property name="acir_opcodes" value="3"/>"#));
+        assert!(report.contains(r#"<failure message="Cannot satisfy constraint &lt;1&gt;">"#));
+    }
+
+    /// The ABI of a test taking `x: Field, y: [Field; 2]`.
+    fn test_abi() -> Abi {
+        let parameter = |name: &str, typ| AbiParameter {
+            name: name.to_owned(),
+            typ,
+            visibility: AbiVisibility::Private,
+        };
+        Abi {
+            parameters: vec![
+                parameter("x", AbiType::Field),
+                parameter("y", AbiType::Array { length: 2, typ: Box::new(AbiType::Field) }),
+            ],
+            param_witnesses: [
+                ("x".to_owned(), vec![Witness(1)]),
+                ("y".to_owned(), vec![Witness(2), Witness(3)]),
+            ]
+            .into_iter()
+            .collect(),
+            return_type: None,
+            return_witnesses: Vec::new(),
+        }
+    }
+
+    fn field(value: u128) -> FieldElement {
+        FieldElement::from(value)
+    }
+
+    #[test]
+    fn reads_inline_cases_as_toml() {
+        let case = TestCase::Inline("x = \"0x01\", y = [2, 3]".to_owned());
+        let inputs = read_test_case(&case, &test_abi(), Path::new("")).unwrap();
+
+        assert_eq!(inputs["x"], InputValue::Field(field(1)));
+        assert_eq!(inputs["y"], InputValue::Vec(vec![field(2), field(3)]));
+    }
+
+    #[test]
+    fn reads_case_files_by_extension() {
+        let dir = tempdir::TempDir::new("cases").unwrap();
+        std::fs::write(dir.path().join("case.toml"), "x = 4\ny = [5, 6]\n").unwrap();
+        std::fs::write(dir.path().join("case.json"), r#"{ "x": 7, "y": ["0x08", "0x09"] }"#)
+            .unwrap();
+
+        let toml = TestCase::File("case.toml".to_owned());
+        let inputs = read_test_case(&toml, &test_abi(), dir.path()).unwrap();
+        assert_eq!(inputs["y"], InputValue::Vec(vec![field(5), field(6)]));
+
+        let json = TestCase::File("case.json".to_owned());
+        let inputs = read_test_case(&json, &test_abi(), dir.path()).unwrap();
+        assert_eq!(inputs["x"], InputValue::Field(field(7)));
+        assert_eq!(inputs["y"], InputValue::Vec(vec![field(8), field(9)]));
+    }
+
+    #[test]
+    fn reports_malformed_and_missing_cases() {
+        let dir = tempdir::TempDir::new("cases").unwrap();
+        std::fs::write(dir.path().join("broken.toml"), "x = [1\n").unwrap();
+
+        let cases = [TestCase::Inline("x = 1, y =".to_owned())];
+        let error = test_case_witnesses(&cases, &test_abi(), dir.path()).unwrap_err();
+        assert!(error.starts_with("Failed to read case = \"x = 1, y =\""), "{error}");
+
+        let cases = [TestCase::File("broken.toml".to_owned())];
+        let error = test_case_witnesses(&cases, &test_abi(), dir.path()).unwrap_err();
+        assert!(error.starts_with("Failed to read inputs = \"broken.toml\""), "{error}");
+
+        let cases = [TestCase::File("missing.json".to_owned())];
+        let error = test_case_witnesses(&cases, &test_abi(), dir.path()).unwrap_err();
+        assert!(error.contains("missing.json"), "{error}");
+
+        // Every parameter must be given a value
+        let cases = [TestCase::Inline("x = 1".to_owned())];
+        assert!(test_case_witnesses(&cases, &test_abi(), dir.path()).is_err());
+    }
+
+    #[test]
+    fn encodes_each_case() {
+        let cases = [
+            TestCase::Inline("x = 1, y = [2, 3]".to_owned()),
+            TestCase::Inline("x = 4, y = [5, 6]".to_owned()),
+        ];
+        let witnesses = test_case_witnesses(&cases, &test_abi(), Path::new("")).unwrap();
+
+        assert_eq!(witnesses.len(), 2);
+        let (case, initial_witness) = &witnesses[1];
+        assert_eq!(*case, Some(&cases[1]));
+        assert_eq!(initial_witness[&Witness(1)], field(4));
+        assert_eq!(initial_witness[&Witness(3)], field(6));
+
+        // Tests without cases are run once, without inputs
+        let witnesses = test_case_witnesses(&[], &test_abi(), Path::new("")).unwrap();
+        assert_eq!(witnesses.len(), 1);
+        assert!(witnesses[0].0.is_none());
+    }
 }
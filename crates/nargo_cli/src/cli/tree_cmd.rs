@@ -0,0 +1,156 @@
+use std::collections::{HashMap, HashSet};
+
+use acvm::Backend;
+use clap::Args;
+use noirc_driver::Driver;
+use noirc_frontend::graph::{CrateGraph, CrateId};
+
+use super::{NargoConfig, PackageOptions};
+use crate::{errors::CliError, resolver::Resolver, workspace::resolve_workspace};
+
+/// Print the dependency graph of a package
+#[derive(Debug, Clone, Args)]
+pub(crate) struct TreeCommand {
+    #[clap(flatten)]
+    packages: PackageOptions,
+}
+
+pub(crate) fn run<B: Backend>(
+    backend: &B,
+    args: TreeCommand,
+    config: NargoConfig,
+) -> Result<(), CliError<B>> {
+    let workspace = resolve_workspace(&config.program_dir)?;
+    let packages = args.packages.select(&workspace, &config.program_dir)?;
+
+    // TODO(#1102): Remove need for driver to be aware of backend.
+    let mut driver =
+        Driver::new(&backend.np_language(), Box::new(|op| B::default().supports_opcode(op)));
+    let (crate_ids, sources) = Resolver::resolve_packages_with_sources(&mut driver, &packages)?;
+
+    let roots: Vec<_> =
+        packages.iter().map(|package| package.name.as_str()).zip(crate_ids).collect();
+    print!("{}", render_tree(&driver.context().crate_graph, &sources, &roots));
+    Ok(())
+}
+
+/// Renders the dependencies of each of the `roots`, along with where each dependency came from.
+///
+/// A dependency shared by several packages only has its own dependencies listed the first time
+/// it appears. It is marked with `(*)` each time after that.
+fn render_tree(
+    graph: &CrateGraph,
+    sources: &HashMap<CrateId, String>,
+    roots: &[(&str, CrateId)],
+) -> String {
+    let mut tree = String::new();
+    let mut shown = HashSet::new();
+    let mut has_duplicates = false;
+    for (name, crate_id) in roots {
+        tree.push_str(&format!("{name} ({})\n", sources[crate_id]));
+        shown.insert(*crate_id);
+        render_dependencies(
+            graph,
+            sources,
+            *crate_id,
+            "",
+            &mut shown,
+            &mut has_duplicates,
+            &mut tree,
+        );
+    }
+    if has_duplicates {
+        tree.push_str("\n(*) dependencies shown above\n");
+    }
+    tree
+}
+
+fn render_dependencies(
+    graph: &CrateGraph,
+    sources: &HashMap<CrateId, String>,
+    crate_id: CrateId,
+    prefix: &str,
+    shown: &mut HashSet<CrateId>,
+    has_duplicates: &mut bool,
+    tree: &mut String,
+) {
+    // The standard library is a dependency of every crate, so it isn't listed
+    let dependencies: Vec<_> = graph[crate_id]
+        .dependencies
+        .iter()
+        .filter(|dependency| sources.contains_key(&dependency.crate_id))
+        .collect();
+
+    for (index, dependency) in dependencies.iter().enumerate() {
+        let is_last = index + 1 == dependencies.len();
+        let (branch, indent) =
+            if is_last { ("└── ", "    ") } else { ("├── ", "│   ") };
+
+        let is_duplicate = !shown.insert(dependency.crate_id);
+        let marker = if is_duplicate { " (*)" } else { "" };
+        tree.push_str(&format!(
+            "{prefix}{branch}{} ({}){marker}\n",
+            dependency.as_name(),
+            sources[&dependency.crate_id]
+        ));
+
+        if is_duplicate {
+            *has_duplicates = true;
+        } else {
+            render_dependencies(
+                graph,
+                sources,
+                dependency.crate_id,
+                &format!("{prefix}{indent}"),
+                shown,
+                has_duplicates,
+                tree,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use acvm::Language;
+    use noirc_driver::Driver;
+
+    use super::render_tree;
+    use crate::{resolver::Resolver, workspace::resolve_workspace};
+
+    fn write_library(root: &Path, name: &str, dependencies: &str) {
+        let package_dir = root.join(name);
+        std::fs::create_dir_all(package_dir.join("src")).unwrap();
+        std::fs::write(package_dir.join("src/lib.nr"), "").unwrap();
+        let manifest = format!("[package]\nauthors = []\n\n[dependencies]\n{dependencies}\n");
+        std::fs::write(package_dir.join("Nargo.toml"), manifest).unwrap();
+    }
+
+    #[test]
+    fn marks_shared_dependencies() {
+        let dir = tempdir::TempDir::new("tree").unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        write_library(&root, "a", "b = { path = \"../b\" }\nc = { path = \"../c\" }");
+        write_library(&root, "b", "c = { path = \"../c\" }");
+        write_library(&root, "c", "");
+
+        let workspace = resolve_workspace(&root.join("a")).unwrap();
+        let packages: Vec<_> = workspace.members.iter().collect();
+        let mut driver = Driver::new(&Language::R1CS, Box::new(|_| true));
+        let (crate_ids, sources) =
+            Resolver::resolve_packages_with_sources(&mut driver, &packages).unwrap();
+
+        let tree = render_tree(&driver.context().crate_graph, &sources, &[("a", crate_ids[0])]);
+        let (a, b, c) = (root.join("a"), root.join("b"), root.join("c"));
+        let expected = format!(
+            "a ({})\n├── b ({})\n│   └── c ({})\n└── c ({}) (*)\n\n(*) dependencies shown above\n",
+            a.display(),
+            b.display(),
+            c.display(),
+            c.display()
+        );
+        assert_eq!(tree, expected);
+    }
+}
@@ -0,0 +1,38 @@
+use acvm::Backend;
+use clap::Args;
+
+use super::NargoConfig;
+use crate::{errors::CliError, lockfile::update_dependencies, workspace::resolve_workspace};
+
+/// Update git dependencies to the commit their tag, branch or rev currently points to
+#[derive(Debug, Clone, Args)]
+pub(crate) struct UpdateCommand {
+    /// The name of the dependency to update, updating every git dependency if not given
+    dependency: Option<String>,
+}
+
+pub(crate) fn run<B: Backend>(
+    _backend: &B,
+    args: UpdateCommand,
+    config: NargoConfig,
+) -> Result<(), CliError<B>> {
+    if config.offline {
+        return Err(CliError::Generic("dependencies cannot be updated with --offline".to_owned()));
+    }
+    if config.locked {
+        return Err(CliError::Generic("Nargo.lock cannot be updated with --locked".to_owned()));
+    }
+
+    let workspace = resolve_workspace(&config.program_dir)?;
+    let updated = update_dependencies(&workspace, args.dependency.as_deref())?;
+    for package in updated {
+        println!(
+            "Updated {} ({}, {}) to commit {}",
+            package.name,
+            package.source,
+            package.reference(),
+            package.commit
+        );
+    }
+    Ok(())
+}
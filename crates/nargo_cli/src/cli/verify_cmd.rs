@@ -10,8 +10,9 @@ use super::fs::{
 };
 use super::NargoConfig;
 use crate::{
-    constants::{PROOFS_DIR, PROOF_EXT, TARGET_DIR, VERIFIER_INPUT_FILE},
+    constants::{PROOFS_DIR, PROOF_EXT, VERIFIER_INPUT_FILE},
     errors::CliError,
+    workspace::target_dir,
 };
 
 use acvm::Backend;
@@ -47,9 +48,8 @@ pub(crate) fn run<B: Backend>(
     let proof_path =
         config.program_dir.join(PROOFS_DIR).join(&args.proof).with_extension(PROOF_EXT);
 
-    let circuit_build_path = args
-        .circuit_name
-        .map(|circuit_name| config.program_dir.join(TARGET_DIR).join(circuit_name));
+    let circuit_build_path =
+        args.circuit_name.map(|circuit_name| target_dir(&config.program_dir).join(circuit_name));
 
     verify_with_path(
         backend,
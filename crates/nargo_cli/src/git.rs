@@ -1,42 +1,131 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
-pub(crate) fn git_dep_location(base: &url::Url, tag: &str) -> PathBuf {
-    let folder_name = super::resolver::resolve_folder_name(base, tag);
+use nargo::manifest::GitReference;
 
-    super::nargo_crates().join(folder_name)
+/// Returns the directory which the git repository at `url` is checked out in at `commit`.
+pub(crate) fn git_dep_location(url: &str, commit: &str) -> Result<PathBuf, String> {
+    let base = url::Url::parse(url).map_err(|err| err.to_string())?;
+    let folder_name = super::resolver::resolve_folder_name(&base, commit);
+
+    Ok(super::nargo_crates().join(folder_name))
+}
+
+/// Returns the full hash of the commit starting with `rev` which the git repository at `url`
+/// has already been checked out at, if there is exactly one such checkout.
+pub(crate) fn cached_commit(url: &str, rev: &str) -> Option<String> {
+    let location = git_dep_location(url, rev).ok()?;
+    let checkouts = std::fs::read_dir(location.parent()?).ok()?;
+
+    let mut commits = checkouts
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name.starts_with(rev) && !name.ends_with(".partial"));
+    match (commits.next(), commits.next()) {
+        (Some(commit), None) => Some(commit),
+        _ => None,
+    }
+}
+
+/// Returns the hash of the commit which `reference` currently points to in the git repository
+/// at `url`.
+///
+/// Revs are returned as they are, as a commit can't be looked up without fetching it.
+pub(crate) fn resolve_git_reference(url: &str, reference: &GitReference) -> Result<String, String> {
+    let refs = match reference {
+        GitReference::Rev(rev) => return Ok(rev.clone()),
+        // Annotated tags are listed both as the tag object and, suffixed with `^{}`,
+        // as the commit they point to
+        GitReference::Tag(tag) => vec![format!("refs/tags/{tag}^{{}}"), format!("refs/tags/{tag}")],
+        GitReference::Branch(branch) => vec![format!("refs/heads/{branch}")],
+        GitReference::DefaultBranch => vec!["HEAD".to_owned()],
+    };
+
+    let mut args = vec!["ls-remote", url];
+    args.extend(refs.iter().map(String::as_str));
+    let output = run_git(None, &args)?;
+
+    let listed: Vec<(&str, &str)> =
+        output.lines().filter_map(|line| line.split_once('\t')).collect();
+    refs.iter()
+        .find_map(|wanted| listed.iter().find(|(_, name)| name == wanted))
+        .map(|(commit, _)| commit.to_string())
+        .ok_or_else(|| format!("could not find {reference} in {url}"))
 }
 
+/// Downloads the git repository at `url` and checks out `commit`, returning the directory
+/// it was checked out in.
+///
+/// Checkouts are cached by the full hash of their commit, so each commit is only downloaded
+/// once, even when it's first referred to by an abbreviated hash.
+///
 /// XXX: I'd prefer to use a GitHub library however, there
 /// does not seem to be an easy way to download a repo at a specific
 /// tag
 /// github-rs looks promising, however it seems to require an API token
 ///
 /// One advantage of using "git clone" is that there is effectively no rate limit
-pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
-    use std::process::Command;
-
-    let base = match url::Url::parse(url) {
-        Ok(base) => base,
-        Err(err) => return Err(err.to_string()),
-    };
-
-    let loc = git_dep_location(&base, tag);
+pub(crate) fn clone_git_repo(url: &str, commit: &str) -> Result<PathBuf, String> {
+    let loc = git_dep_location(url, commit)?;
     if loc.exists() {
         return Ok(loc);
     }
 
-    Command::new("git")
-        .arg("-c")
-        .arg("advice.detachedHead=false")
-        .arg("clone")
-        .arg("--depth")
-        .arg("1")
-        .arg("--branch")
-        .arg(tag)
-        .arg(base.as_str())
-        .arg(&loc)
-        .status()
-        .expect("git clone command failed to start");
+    // The repository is downloaded next to its final location and only moved there once it
+    // has been checked out, so that an interrupted download doesn't end up in the cache.
+    let partial = loc.with_extension("partial");
+    if partial.exists() {
+        std::fs::remove_dir_all(&partial).map_err(|err| err.to_string())?;
+    }
+    std::fs::create_dir_all(&partial).map_err(|err| err.to_string())?;
 
+    run_git(Some(&partial), &["init", "--quiet"])?;
+    // Most hosts allow a single commit to be fetched by its hash, which avoids downloading the
+    // whole history. Abbreviated hashes can only be found by fetching every branch and tag.
+    if run_git(Some(&partial), &["fetch", "--quiet", "--depth", "1", url, commit]).is_err() {
+        run_git(
+            Some(&partial),
+            &[
+                "fetch",
+                "--quiet",
+                url,
+                "+refs/heads/*:refs/remotes/origin/*",
+                "+refs/tags/*:refs/tags/*",
+            ],
+        )?;
+    }
+    run_git(Some(&partial), &["checkout", "--quiet", "--detach", commit])
+        .map_err(|_| format!("failed to check out commit {commit} of {url}"))?;
+
+    // Abbreviated hashes are cached under the full hash, which is the one pinned by Nargo.lock
+    let loc = git_dep_location(url, &git_head_commit(&partial)?)?;
+    if loc.exists() {
+        std::fs::remove_dir_all(&partial).map_err(|err| err.to_string())?;
+    } else {
+        std::fs::rename(&partial, &loc).map_err(|err| err.to_string())?;
+    }
     Ok(loc)
 }
+
+/// Returns the hash of the commit checked out in the git repository at `repo`.
+pub(crate) fn git_head_commit(repo: &Path) -> Result<String, String> {
+    run_git(Some(repo), &["rev-parse", "HEAD"])
+}
+
+/// Runs a git command, in `repo` if given, returning its trimmed standard output.
+fn run_git(repo: Option<&Path>, args: &[&str]) -> Result<String, String> {
+    let mut command = Command::new("git");
+    if let Some(repo) = repo {
+        command.arg("-C").arg(repo);
+    }
+    let output = command.args(args).output().expect("git command failed to start");
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    } else {
+        Err(format!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
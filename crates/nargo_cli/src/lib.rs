@@ -18,8 +18,10 @@ pub mod cli;
 mod constants;
 mod errors;
 mod git;
+mod lockfile;
 mod manifest;
 mod resolver;
+mod workspace;
 
 use nargo::manifest::InvalidPackageError;
 
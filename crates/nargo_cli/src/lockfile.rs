@@ -0,0 +1,311 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+};
+
+use nargo::{
+    lockfile::{LockedPackage, Lockfile},
+    manifest::{Dependency, GitReference},
+    workspace::Workspace,
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    constants::LOCK_FILE,
+    git::{clone_git_repo, git_dep_location, git_head_commit, resolve_git_reference},
+    resolver::DependencyResolutionError,
+};
+
+/// Restrictions on how git dependencies may be resolved.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct LockOptions {
+    /// Fail rather than change `Nargo.lock`.
+    pub(crate) locked: bool,
+    /// Fail rather than access the network.
+    pub(crate) offline: bool,
+}
+
+/// Reads the `Nargo.lock` of the workspace at `workspace_root`, if it has one.
+pub(crate) fn read_lockfile(
+    workspace_root: &Path,
+) -> Result<Option<Lockfile>, DependencyResolutionError> {
+    match std::fs::read_to_string(workspace_root.join(LOCK_FILE)) {
+        Ok(toml_as_string) => Ok(Some(Lockfile::from_toml_str(&toml_as_string)?)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Fetches the git dependencies of the packages in the workspace, along with their own
+/// dependencies, at the commit pinned for each of them in the workspace's `Nargo.lock`.
+///
+/// Dependencies which aren't pinned yet are pinned to the commit their tag, branch or rev
+/// currently resolves to, and `Nargo.lock` is rewritten whenever the set of pins changes.
+pub(crate) fn lock_dependencies(
+    workspace: &Workspace,
+    options: LockOptions,
+) -> Result<(), DependencyResolutionError> {
+    let existing = read_lockfile(&workspace.root_dir)?;
+    let lockfile = existing.clone().unwrap_or_default();
+    let updated = resolve_pins(workspace, &lockfile, options)?;
+
+    let up_to_date = match existing {
+        Some(mut existing) => {
+            existing.sort();
+            existing == updated
+        }
+        // Packages without git dependencies don't need a lockfile
+        None => updated.packages.is_empty(),
+    };
+    if up_to_date {
+        return Ok(());
+    }
+    if options.locked {
+        return Err(DependencyResolutionError::LockfileOutOfDate);
+    }
+
+    write_lockfile(&workspace.root_dir, &updated)
+}
+
+/// Re-pins the git dependency named `dep_name`, or every git dependency if no name is given,
+/// to the commit its tag, branch or rev currently resolves to, downloading it afresh.
+///
+/// Returns the packages which were pinned, after rewriting `Nargo.lock`.
+pub(crate) fn update_dependencies(
+    workspace: &Workspace,
+    dep_name: Option<&str>,
+) -> Result<Vec<LockedPackage>, DependencyResolutionError> {
+    let existing = read_lockfile(&workspace.root_dir)?.unwrap_or_default();
+    let (stale, kept): (Vec<_>, Vec<_>) = existing
+        .packages
+        .into_iter()
+        .partition(|package| dep_name.map_or(true, |name| package.name == name));
+    if let (Some(dep_name), true) = (dep_name, stale.is_empty()) {
+        return Err(DependencyResolutionError::NotPinned { dep_pkg_name: dep_name.to_owned() });
+    }
+
+    // The cached checkouts are removed so that they're downloaded again,
+    // even if their reference still resolves to the same commit.
+    for package in &stale {
+        let repo_dir = git_dep_location(&package.source, &package.commit)
+            .map_err(DependencyResolutionError::GitError)?;
+        if repo_dir.exists() {
+            std::fs::remove_dir_all(&repo_dir).map_err(|err| {
+                DependencyResolutionError::GitError(format!(
+                    "could not remove {}: {err}",
+                    repo_dir.display()
+                ))
+            })?;
+        }
+    }
+
+    let pins = Lockfile { packages: kept, ..Lockfile::default() };
+    let updated = resolve_pins(workspace, &pins, LockOptions::default())?;
+    write_lockfile(&workspace.root_dir, &updated)?;
+
+    Ok(updated.packages.into_iter().filter(|package| !pins.packages.contains(package)).collect())
+}
+
+/// Resolves the git dependencies of the workspace's packages, using the commits pinned by
+/// `lockfile` where there are any, and returns the pins of every dependency which was reached.
+fn resolve_pins(
+    workspace: &Workspace,
+    lockfile: &Lockfile,
+    options: LockOptions,
+) -> Result<Lockfile, DependencyResolutionError> {
+    let mut locker =
+        Locker { lockfile, options, resolved: HashMap::new(), visited: HashSet::new() };
+    for package in &workspace.members {
+        locker.lock_package(&package.root_dir)?;
+    }
+
+    let packages = locker.resolved.into_values().map(|(_, package)| package).collect();
+    let mut pins = Lockfile { packages, ..Lockfile::default() };
+    pins.sort();
+    Ok(pins)
+}
+
+fn write_lockfile(
+    workspace_root: &Path,
+    lockfile: &Lockfile,
+) -> Result<(), DependencyResolutionError> {
+    let lock_path = workspace_root.join(LOCK_FILE);
+    std::fs::write(&lock_path, lockfile.to_toml_string())
+        .map_err(|source| DependencyResolutionError::LockfileWriteError { lock_path, source })
+}
+
+struct Locker<'a> {
+    lockfile: &'a Lockfile,
+    options: LockOptions,
+    /// The git dependencies resolved so far, keyed by their source and reference,
+    /// along with the directory each one was checked out in
+    resolved: HashMap<(String, GitReference), (PathBuf, LockedPackage)>,
+    /// The directories of the packages whose dependencies have been visited
+    visited: HashSet<PathBuf>,
+}
+
+impl Locker<'_> {
+    fn lock_package(&mut self, pkg_root: &Path) -> Result<(), DependencyResolutionError> {
+        let canonical_root = pkg_root.canonicalize().unwrap_or_else(|_| pkg_root.to_path_buf());
+        if !self.visited.insert(canonical_root) {
+            return Ok(());
+        }
+
+        let manifest_path = super::find_package_manifest(pkg_root)?;
+        let manifest = super::manifest::parse(manifest_path)?;
+
+        for (dep_pkg_name, dep) in &manifest.dependencies {
+            let dir_path = match dep {
+                Dependency::Github { git, reference, directory } => {
+                    let repo_dir = self.lock_git_dep(dep_pkg_name, git, reference)?;
+                    match directory {
+                        Some(directory) => repo_dir.join(directory),
+                        None => repo_dir,
+                    }
+                }
+                Dependency::Path { path } => pkg_root.join(path),
+            };
+            self.lock_package(&dir_path)?;
+        }
+        Ok(())
+    }
+
+    /// Fetches the git dependency at the commit pinned for it, or pins it if it isn't yet,
+    /// returning the directory of the repository it was checked out in.
+    fn lock_git_dep(
+        &mut self,
+        dep_pkg_name: &str,
+        git: &str,
+        reference: &GitReference,
+    ) -> Result<PathBuf, DependencyResolutionError> {
+        let key = (git.to_owned(), reference.clone());
+        if let Some((repo_dir, _)) = self.resolved.get(&key) {
+            return Ok(repo_dir.clone());
+        }
+
+        let (repo_dir, package) = match self.lockfile.get(git, reference) {
+            Some(pinned) => (self.fetch_pinned(dep_pkg_name, pinned)?, pinned.clone()),
+            None if self.options.locked || self.options.offline => {
+                return Err(DependencyResolutionError::UnpinnedDependency {
+                    dep_pkg_name: dep_pkg_name.to_owned(),
+                    git: git.to_owned(),
+                    reference: reference.to_string(),
+                });
+            }
+            None => {
+                let commit = resolve_git_reference(git, reference)
+                    .map_err(DependencyResolutionError::GitError)?;
+                // Revs may be abbreviated, so the full hash is pinned. The checkout is cached
+                // under the full hash as well, which is where `fetch_pinned` looks for it.
+                let repo_dir =
+                    clone_git_repo(git, &commit).map_err(DependencyResolutionError::GitError)?;
+                let commit =
+                    git_head_commit(&repo_dir).map_err(DependencyResolutionError::GitError)?;
+                let package = LockedPackage::new(
+                    dep_pkg_name.to_owned(),
+                    git.to_owned(),
+                    reference,
+                    commit,
+                    checksum(&repo_dir)?,
+                );
+                (repo_dir, package)
+            }
+        };
+
+        self.resolved.insert(key, (repo_dir.clone(), package));
+        Ok(repo_dir)
+    }
+
+    fn fetch_pinned(
+        &self,
+        dep_pkg_name: &str,
+        pinned: &LockedPackage,
+    ) -> Result<PathBuf, DependencyResolutionError> {
+        let repo_dir = git_dep_location(&pinned.source, &pinned.commit)
+            .map_err(DependencyResolutionError::GitError)?;
+        if !repo_dir.exists() {
+            if self.options.offline {
+                return Err(DependencyResolutionError::NotAvailableOffline {
+                    dep_pkg_name: dep_pkg_name.to_owned(),
+                });
+            }
+            clone_git_repo(&pinned.source, &pinned.commit)
+                .map_err(DependencyResolutionError::GitError)?;
+        }
+
+        let checksum = checksum(&repo_dir)?;
+        if checksum != pinned.checksum {
+            return Err(DependencyResolutionError::ChecksumMismatch {
+                dep_pkg_name: dep_pkg_name.to_owned(),
+                expected: pinned.checksum.clone(),
+                found: checksum,
+            });
+        }
+        Ok(repo_dir)
+    }
+}
+
+fn checksum(dir_path: &Path) -> Result<String, DependencyResolutionError> {
+    checksum_dir(dir_path).map_err(|err| {
+        DependencyResolutionError::GitError(format!(
+            "could not read dependency at {}: {err}",
+            dir_path.display()
+        ))
+    })
+}
+
+/// Computes the SHA-256 checksum of the files within `dir`, ignoring git's metadata.
+fn checksum_dir(dir: &Path) -> io::Result<String> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for relative_path in files {
+        let contents = std::fs::read(dir.join(&relative_path))?;
+
+        // Paths are hashed with `/` separators so that checksums agree across platforms
+        let components: Vec<_> =
+            relative_path.iter().map(|component| component.to_string_lossy()).collect();
+        hasher.update(components.join("/").as_bytes());
+        hasher.update([0]);
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            if entry.file_name() != ".git" {
+                collect_files(root, &path, files)?;
+            }
+        } else {
+            files.push(path.strip_prefix(root).expect("files are within the root").to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::checksum_dir;
+
+    #[test]
+    fn checksum_covers_files_but_not_git_metadata() {
+        let dir = tempdir::TempDir::new("checksum").unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/lib.nr"), "fn foo() {}").unwrap();
+        let checksum = checksum_dir(dir.path()).unwrap();
+
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".git/HEAD"), "ref: refs/heads/main").unwrap();
+        assert_eq!(checksum_dir(dir.path()).unwrap(), checksum);
+
+        std::fs::write(dir.path().join("src/lib.nr"), "fn bar() {}").unwrap();
+        assert_ne!(checksum_dir(dir.path()).unwrap(), checksum);
+    }
+}
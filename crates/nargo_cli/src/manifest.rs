@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use nargo::manifest::{InvalidPackageError, PackageManifest};
+use nargo::manifest::{InvalidPackageError, Manifest, PackageManifest};
 
 /// Parses a Nargo.toml file from it's path
 /// The path to the toml file must be present.
@@ -8,8 +8,21 @@ use nargo::manifest::{InvalidPackageError, PackageManifest};
 pub(crate) fn parse<P: AsRef<Path>>(
     path_to_toml: P,
 ) -> Result<PackageManifest, InvalidPackageError> {
+    match parse_manifest(&path_to_toml)? {
+        Manifest::Package(manifest) => Ok(manifest),
+        Manifest::Workspace(_) => {
+            Err(InvalidPackageError::UnexpectedWorkspace(path_to_toml.as_ref().to_path_buf()))
+        }
+    }
+}
+
+/// Parses a Nargo.toml file which describes either a package or a workspace.
+/// The path to the toml file must be present.
+pub(crate) fn parse_manifest<P: AsRef<Path>>(
+    path_to_toml: P,
+) -> Result<Manifest, InvalidPackageError> {
     let toml_as_string =
         std::fs::read_to_string(&path_to_toml).expect("ice: path given for toml file is invalid");
 
-    PackageManifest::from_toml_str(&toml_as_string)
+    Manifest::from_toml_str(&toml_as_string)
 }
@@ -1,22 +1,34 @@
 use std::{
-    collections::HashMap,
-    path::{Path, PathBuf},
+    collections::{HashMap, HashSet},
+    fmt,
+    path::{Component, Path, PathBuf},
 };
 
 use acvm::{acir::circuit::Opcode, Language};
-use nargo::manifest::{Dependency, PackageManifest};
-use noirc_driver::Driver;
-use noirc_frontend::graph::{CrateId, CrateName, CrateType};
+use nargo::{
+    lockfile::Lockfile,
+    manifest::{Dependency, GitReference, PackageManifest},
+    workspace::Package,
+};
+use noirc_driver::{AddDependencyError, Driver};
+use noirc_frontend::graph::{CrateId, CrateName, CrateType, CHARACTER_BLACK_LIST};
 use thiserror::Error;
 
-use crate::{git::clone_git_repo, InvalidPackageError};
+use crate::{
+    constants::PKG_FILE,
+    git::{cached_commit, git_dep_location},
+    lockfile::read_lockfile,
+    workspace::{package_name, workspace_root},
+    InvalidPackageError,
+};
 
-/// Creates a unique folder name for a GitHub repo
-/// by using it's URL and tag
-pub(crate) fn resolve_folder_name(base: &url::Url, tag: &str) -> String {
+/// Creates a unique folder name for a git repository checked out at a commit
+/// by using its URL and the commit's hash
+pub(crate) fn resolve_folder_name(base: &url::Url, commit: &str) -> String {
     let mut folder_name = base.domain().unwrap().to_owned();
     folder_name.push_str(base.path());
-    folder_name.push_str(tag);
+    folder_name.push('/');
+    folder_name.push_str(commit);
     folder_name
 }
 
@@ -31,11 +43,62 @@ pub(crate) enum DependencyResolutionError {
     #[error("dependency {dep_pkg_name} is a binary package and so it cannot be depended upon.")]
     BinaryDependency { dep_pkg_name: String },
 
-    /// Attempted to depend on remote crate which has a local dependency.
+    /// Attempted to depend on a package under a name which isn't a valid crate name.
+    #[error("dependency {dep_pkg_name} in {} is not a valid crate name as it contains one of {:?}", manifest_path.display(), CHARACTER_BLACK_LIST)]
+    InvalidDependencyName { dep_pkg_name: String, manifest_path: PathBuf },
+
+    /// Packages depend on each other in a cycle.
+    #[error("dependency cycle detected: {}\n{}", cycle.join(" -> "), entries.join("\n"))]
+    DependencyCycle {
+        /// The names of the packages in the cycle, starting and ending with the same package
+        cycle: Vec<String>,
+        /// The `Nargo.toml` entries which form the cycle
+        entries: Vec<String>,
+    },
+
+    /// The driver rejected a dependency.
+    #[error(transparent)]
+    InvalidDependency(#[from] AddDependencyError),
+
+    /// Attempted to depend on remote crate which has a local dependency outside of its repository.
     /// We have no guarantees that this local dependency will be available so must error.
-    #[error("remote(git) dependency has a local dependency.\ndependency located at {}", dependency_path.display())]
+    #[error("remote(git) dependency has a local dependency outside of its repository.\ndependency located at {}", dependency_path.display())]
     RemoteDepWithLocalDep { dependency_path: PathBuf },
 
+    /// Attempted to resolve a git dependency which isn't pinned by Nargo.lock
+    /// when `--locked` or `--offline` forbid pinning it.
+    #[error("dependency {dep_pkg_name} ({git} at {reference}) is not pinned in Nargo.lock, so it cannot be resolved with --locked or --offline.\nRun nargo without these flags to pin it.")]
+    UnpinnedDependency { dep_pkg_name: String, git: String, reference: String },
+
+    /// Attempted to resolve a git dependency which hasn't been pinned and downloaded yet.
+    /// Resolution never accesses the network, as the LSP resolves packages on every request.
+    #[error("dependency {dep_pkg_name} ({git} at {reference}) has not been downloaded yet.\nRun a nargo command such as `nargo check` to pin and download it.")]
+    NotFetched { dep_pkg_name: String, git: String, reference: String },
+
+    /// Attempted to resolve a git dependency which hasn't been downloaded with `--offline`.
+    #[error("dependency {dep_pkg_name} has not been downloaded, so it cannot be resolved with --offline.")]
+    NotAvailableOffline { dep_pkg_name: String },
+
+    /// The contents of a git dependency differ from those pinned by Nargo.lock.
+    #[error("dependency {dep_pkg_name} does not match the checksum pinned in Nargo.lock.\nexpected: {expected}\nfound:    {found}")]
+    ChecksumMismatch { dep_pkg_name: String, expected: String, found: String },
+
+    /// Packages depend on the same git dependency at different commits.
+    #[error("dependency on {git} is required at conflicting versions:\n  {first}\n  {second}\nAll packages must depend on the same version of it.")]
+    ConflictingVersions { git: String, first: String, second: String },
+
+    /// Attempted to update a git dependency which isn't pinned by Nargo.lock.
+    #[error("no git dependency named {dep_pkg_name} is pinned in Nargo.lock")]
+    NotPinned { dep_pkg_name: String },
+
+    /// Nargo.lock has to change but `--locked` forbids it.
+    #[error("Nargo.lock needs to be updated but --locked was passed to prevent this.")]
+    LockfileOutOfDate,
+
+    /// Nargo.lock could not be written.
+    #[error("could not write to {}: {source}", lock_path.display())]
+    LockfileWriteError { lock_path: PathBuf, source: std::io::Error },
+
     /// Dependency is not a valid crate
     #[error(transparent)]
     MalformedDependency(#[from] InvalidPackageError),
@@ -46,9 +109,88 @@ struct CachedDep {
     entry_path: PathBuf,
     crate_type: CrateType,
     manifest: PackageManifest,
-    // Whether the dependency came from
-    // a remote dependency
-    remote: bool,
+}
+
+/// Identifies a package independently of the dependency it was reached through,
+/// so that each package is only added to the crate graph once.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum PackageSource {
+    /// A package on the local file system, by its canonical directory
+    Local(PathBuf),
+    /// A package in a git repository, by the repository's URL and the package's directory
+    /// within it. Each package may only be used at a single commit.
+    Git { url: String, directory: PathBuf },
+}
+
+impl PackageSource {
+    fn local(dir_path: &Path) -> Self {
+        PackageSource::Local(package_key(dir_path))
+    }
+
+    fn git(url: &str, directory: Option<&Path>) -> Self {
+        // The same repository may be written with or without a trailing `/` or `.git`
+        let url = url.trim_end_matches('/');
+        let url = url.strip_suffix(".git").unwrap_or(url).to_owned();
+        let directory = directory
+            .map(|directory| {
+                directory.components().filter(|component| component != &Component::CurDir).collect()
+            })
+            .unwrap_or_default();
+        PackageSource::Git { url, directory }
+    }
+}
+
+/// The commit a git package was resolved to, and the package which first required it.
+#[derive(Debug, Clone)]
+struct GitVersion {
+    commit: String,
+    reference: GitReference,
+    required_by: String,
+}
+
+impl fmt::Display for GitVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (commit {}) required by {}", self.reference, self.commit, self.required_by)
+    }
+}
+
+/// A package which has been added to the driver.
+#[derive(Debug, Clone)]
+struct ResolvedPackage {
+    /// The name the package was first reached by
+    name: String,
+    manifest_path: PathBuf,
+    crate_id: CrateId,
+    crate_type: CrateType,
+    /// The version of a git package, which every dependency on it must agree on
+    version: Option<GitVersion>,
+    dependencies: Vec<PackageDep>,
+}
+
+/// A dependency of a package, as written in its `Nargo.toml`.
+#[derive(Debug, Clone)]
+struct PackageDep {
+    name: String,
+    dependency: Dependency,
+    source: PackageSource,
+}
+
+/// Where a dependency was found, before it is added to the driver.
+struct LocatedDep {
+    dir_path: PathBuf,
+    source: PackageSource,
+    /// The git repository the dependency came from, if it is a remote dependency
+    repository: Option<GitRepository>,
+    version: Option<GitVersion>,
+}
+
+/// A checkout of the git repository which a remote package came from.
+#[derive(Debug, Clone)]
+struct GitRepository {
+    /// The directory the repository was checked out in
+    root: PathBuf,
+    url: String,
+    version: GitVersion,
 }
 
 /// Resolves a toml file by either downloading the necessary git repo
@@ -57,11 +199,16 @@ struct CachedDep {
 /// We need to download those too
 pub(crate) struct Resolver<'a> {
     driver: &'a mut Driver,
+    /// Every package which has been added to the driver, by its source.
+    /// Packages reached through several dependencies are only added once.
+    packages: HashMap<PackageSource, ResolvedPackage>,
+    /// The commits which git dependencies are pinned to
+    lockfile: Lockfile,
 }
 
 impl<'a> Resolver<'a> {
-    fn with_driver(driver: &mut Driver) -> Resolver {
-        Resolver { driver }
+    fn with_driver(driver: &mut Driver, lockfile: Lockfile) -> Resolver {
+        Resolver { driver, packages: HashMap::new(), lockfile }
     }
 
     /// Returns the Driver and the backend to use
@@ -74,6 +221,16 @@ impl<'a> Resolver<'a> {
         is_opcode_supported: Box<dyn Fn(&Opcode) -> bool>,
     ) -> Result<Driver, DependencyResolutionError> {
         let mut driver = Driver::new(&np_language, is_opcode_supported);
+        Resolver::resolve_package(&mut driver, dir_path)?;
+        Ok(driver)
+    }
+
+    /// Adds the package at `dir_path` to the driver as the local crate,
+    /// along with all of its dependencies and the standard library.
+    pub(crate) fn resolve_package(
+        driver: &mut Driver,
+        dir_path: &std::path::Path,
+    ) -> Result<(), DependencyResolutionError> {
         let (entry_path, crate_type) = super::lib_or_bin(dir_path)?;
 
         let manifest_path = super::find_package_manifest(dir_path)?;
@@ -81,93 +238,395 @@ impl<'a> Resolver<'a> {
 
         let crate_id = driver.create_local_crate(entry_path, crate_type);
 
-        let mut resolver = Resolver::with_driver(&mut driver);
         let pkg_root = manifest_path.parent().expect("Every manifest path has a parent.");
-        resolver.resolve_manifest(crate_id, manifest, pkg_root)?;
+        let mut resolver = Resolver::with_driver(driver, lockfile_for(pkg_root)?);
+        let pkg_name = package_name(pkg_root, &manifest);
+        let source = resolver.add_local_package(pkg_root, pkg_name, crate_id, crate_type);
+        resolver.resolve_manifest(&source, manifest, pkg_root, None)?;
 
-        add_std_lib(&mut driver);
-        Ok(driver)
+        add_std_lib(driver);
+        Ok(())
+    }
+
+    /// Adds each of the packages of a workspace to the driver as a local crate, resolving their
+    /// dependencies together so that the packages they share are only added once.
+    ///
+    /// Returns the crate of each package, in the same order as the packages.
+    pub(crate) fn resolve_packages(
+        driver: &mut Driver,
+        packages: &[&Package],
+    ) -> Result<Vec<CrateId>, DependencyResolutionError> {
+        let (crate_ids, _) = Resolver::resolve_packages_with_sources(driver, packages)?;
+        Ok(crate_ids)
+    }
+
+    /// Resolves the packages of a workspace as [`Resolver::resolve_packages`] does, also
+    /// returning a description of where each package's crate came from.
+    ///
+    /// The standard library is the only crate without a source.
+    pub(crate) fn resolve_packages_with_sources(
+        driver: &mut Driver,
+        packages: &[&Package],
+    ) -> Result<(Vec<CrateId>, HashMap<CrateId, String>), DependencyResolutionError> {
+        let lockfile = match packages.first() {
+            Some(package) => lockfile_for(&package.root_dir)?,
+            None => Lockfile::default(),
+        };
+        let mut resolver = Resolver::with_driver(driver, lockfile);
+
+        // Packages may depend on each other, so they must all be added before any dependencies
+        let mut crate_ids = Vec::with_capacity(packages.len());
+        let mut sources = Vec::with_capacity(packages.len());
+        for package in packages {
+            let crate_id =
+                resolver.driver.create_local_crate(&package.entry_path, package.crate_type);
+            sources.push(resolver.add_local_package(
+                &package.root_dir,
+                package.name.clone(),
+                crate_id,
+                package.crate_type,
+            ));
+            crate_ids.push(crate_id);
+        }
+
+        for (package, source) in packages.iter().zip(&sources) {
+            resolver.resolve_manifest(source, package.manifest.clone(), &package.root_dir, None)?;
+        }
+
+        let crate_sources = resolver
+            .packages
+            .iter()
+            .map(|(source, package)| (package.crate_id, describe_source(source, package)))
+            .collect();
+
+        add_std_lib(driver);
+        Ok((crate_ids, crate_sources))
     }
 
     // Resolves a config file by recursively resolving the dependencies in the config
-    // Need to solve the case of a project trying to use itself as a dep
     //
     // We do not need to add stdlib, as it's implicitly
     // imported. However, it may be helpful to have the stdlib imported by the
     // package manager.
+    //
+    // `repository` is the git repository the manifest's package came from,
+    // if it is a remote dependency.
     fn resolve_manifest(
         &mut self,
-        parent_crate: CrateId,
+        parent: &PackageSource,
         manifest: PackageManifest,
         pkg_root: &Path,
+        repository: Option<&GitRepository>,
     ) -> Result<(), DependencyResolutionError> {
-        let mut cached_packages: HashMap<PathBuf, (CrateId, CachedDep)> = HashMap::new();
+        let mut cached_packages: Vec<(LocatedDep, CachedDep)> = Vec::new();
+        let (parent_crate, pkg_name, manifest_path) = {
+            let parent = &self.packages[parent];
+            (parent.crate_id, parent.name.clone(), parent.manifest_path.clone())
+        };
 
         // First download and add these top level dependencies crates to the Driver
         for (dep_pkg_name, pkg_src) in manifest.dependencies.iter() {
-            let (dir_path, dep_meta) = Resolver::cache_dep(pkg_src, pkg_root)?;
-
-            let (entry_path, crate_type) = (&dep_meta.entry_path, &dep_meta.crate_type);
-
-            if crate_type == &CrateType::Binary {
-                return Err(DependencyResolutionError::BinaryDependency {
-                    dep_pkg_name: dep_pkg_name.to_string(),
+            if CrateName::new(dep_pkg_name).is_err() {
+                return Err(DependencyResolutionError::InvalidDependencyName {
+                    dep_pkg_name: dep_pkg_name.clone(),
+                    manifest_path,
                 });
             }
 
-            let crate_id = self.driver.create_non_local_crate(entry_path, *crate_type);
-            self.driver.add_dep(parent_crate, crate_id, dep_pkg_name);
+            let located =
+                self.locate_dep(dep_pkg_name, pkg_src, &pkg_name, pkg_root, repository)?;
 
-            cached_packages.insert(dir_path, (crate_id, dep_meta));
-        }
+            let (crate_id, new_meta) = match self.packages.get(&located.source) {
+                // The package has already been reached through another dependency
+                Some(resolved) => {
+                    check_same_version(&located, resolved)?;
+                    if resolved.crate_type == CrateType::Binary {
+                        return Err(DependencyResolutionError::BinaryDependency {
+                            dep_pkg_name: dep_pkg_name.to_string(),
+                        });
+                    }
+                    (resolved.crate_id, None)
+                }
+                None => {
+                    let dep_meta = retrieve_meta(&located.dir_path)?;
+                    let (entry_path, crate_type) = (&dep_meta.entry_path, dep_meta.crate_type);
+
+                    if crate_type == CrateType::Binary {
+                        return Err(DependencyResolutionError::BinaryDependency {
+                            dep_pkg_name: dep_pkg_name.to_string(),
+                        });
+                    }
 
-        // Resolve all transitive dependencies
-        for (dependency_path, (crate_id, dep_meta)) in cached_packages {
-            if dep_meta.remote && dep_meta.manifest.has_local_dependency() {
-                return Err(DependencyResolutionError::RemoteDepWithLocalDep { dependency_path });
+                    let crate_id = self.driver.create_non_local_crate(entry_path, crate_type);
+                    let resolved = ResolvedPackage {
+                        name: dep_pkg_name.clone(),
+                        manifest_path: located.dir_path.join(PKG_FILE),
+                        crate_id,
+                        crate_type,
+                        version: located.version.clone(),
+                        dependencies: Vec::new(),
+                    };
+                    self.packages.insert(located.source.clone(), resolved);
+                    (crate_id, Some(dep_meta))
+                }
+            };
+
+            // Cycles are detected between packages before the driver would reject them,
+            // so that they can be reported along with the entries which form them.
+            let dep = PackageDep {
+                name: dep_pkg_name.clone(),
+                dependency: pkg_src.clone(),
+                source: located.source.clone(),
+            };
+            self.check_acyclic(parent, &dep)?;
+            self.packages
+                .get_mut(parent)
+                .expect("parent package is resolved")
+                .dependencies
+                .push(dep);
+            self.driver.add_dep(parent_crate, crate_id, dep_pkg_name)?;
+
+            if let Some(dep_meta) = new_meta {
+                cached_packages.push((located, dep_meta));
             }
-            let mut new_res = Resolver::with_driver(self.driver);
-            new_res.resolve_manifest(crate_id, dep_meta.manifest, &dependency_path)?;
+        }
+
+        // Resolve all transitive dependencies of the packages which were just added
+        for (located, dep_meta) in cached_packages {
+            self.resolve_manifest(
+                &located.source,
+                dep_meta.manifest,
+                &located.dir_path,
+                located.repository.as_ref(),
+            )?;
         }
         Ok(())
     }
 
-    /// If the dependency is remote, download the dependency
-    /// and return the directory path along with the metadata
-    /// Needed to fill the CachedDep struct
-    ///
-    /// If it's a local path, the same applies, however it will not
-    /// be downloaded
-    fn cache_dep(
-        dep: &Dependency,
+    fn add_local_package(
+        &mut self,
         pkg_root: &Path,
-    ) -> Result<(PathBuf, CachedDep), DependencyResolutionError> {
-        fn retrieve_meta(
-            dir_path: &Path,
-            remote: bool,
-        ) -> Result<CachedDep, DependencyResolutionError> {
-            let (entry_path, crate_type) = super::lib_or_bin(dir_path)?;
-            let manifest_path = super::find_package_manifest(dir_path)?;
-            let manifest = super::manifest::parse(manifest_path)?;
-            Ok(CachedDep { entry_path, crate_type, manifest, remote })
+        name: String,
+        crate_id: CrateId,
+        crate_type: CrateType,
+    ) -> PackageSource {
+        let source = PackageSource::local(pkg_root);
+        let resolved = ResolvedPackage {
+            name,
+            manifest_path: pkg_root.join(PKG_FILE),
+            crate_id,
+            crate_type,
+            version: None,
+            dependencies: Vec::new(),
+        };
+        self.packages.insert(source.clone(), resolved);
+        source
+    }
+
+    /// Checks that depending on `dep` wouldn't make `parent` depend on itself.
+    fn check_acyclic(
+        &self,
+        parent: &PackageSource,
+        dep: &PackageDep,
+    ) -> Result<(), DependencyResolutionError> {
+        // A path of dependencies leading from the dependency back to the parent
+        let path = match self.find_path(&dep.source, parent, &mut HashSet::new()) {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let parent = &self.packages[parent];
+        let mut cycle = vec![parent.name.clone()];
+        let mut entries = vec![describe_entry(parent, dep)];
+        for (from, to) in path.iter().zip(path.iter().skip(1)) {
+            let package = &self.packages[from];
+            let dep = package
+                .dependencies
+                .iter()
+                .find(|dep| &dep.source == to)
+                .expect("path follows dependencies");
+            cycle.push(package.name.clone());
+            entries.push(describe_entry(package, dep));
         }
+        cycle.push(parent.name.clone());
+
+        Err(DependencyResolutionError::DependencyCycle { cycle, entries })
+    }
 
+    /// Returns the packages along a path of dependencies leading from `from` to `to`,
+    /// including both of them, if there is one.
+    fn find_path(
+        &self,
+        from: &PackageSource,
+        to: &PackageSource,
+        visited: &mut HashSet<PackageSource>,
+    ) -> Option<Vec<PackageSource>> {
+        if from == to {
+            return Some(vec![from.clone()]);
+        }
+        if !visited.insert(from.clone()) {
+            return None;
+        }
+
+        self.packages.get(from)?.dependencies.iter().find_map(|dep| {
+            let mut path = self.find_path(&dep.source, to, visited)?;
+            path.insert(0, from.clone());
+            Some(path)
+        })
+    }
+
+    /// Returns the directory of the dependency along with its source
+    ///
+    /// Remote dependencies must already have been downloaded, at the commit pinned by
+    /// `Nargo.lock` or, for revs which aren't pinned, at any commit already in the cache.
+    ///
+    /// Remote packages may only have local dependencies within their own repository. These are
+    /// identified as packages of that repository, the same as if they were git dependencies.
+    fn locate_dep(
+        &self,
+        dep_pkg_name: &str,
+        dep: &Dependency,
+        pkg_name: &str,
+        pkg_root: &Path,
+        repository: Option<&GitRepository>,
+    ) -> Result<LocatedDep, DependencyResolutionError> {
         match dep {
-            Dependency::Github { git, tag } => {
-                let dir_path =
-                    clone_git_repo(git, tag).map_err(DependencyResolutionError::GitError)?;
-                let meta = retrieve_meta(&dir_path, true)?;
-                Ok((dir_path, meta))
+            Dependency::Github { git, reference, directory } => {
+                // Nargo commands pin and download git dependencies before resolving them.
+                // The LSP resolves packages on every request without pinning them, so only
+                // what's already on disk is used here rather than accessing the network.
+                let not_fetched = || DependencyResolutionError::NotFetched {
+                    dep_pkg_name: dep_pkg_name.to_owned(),
+                    git: git.clone(),
+                    reference: reference.to_string(),
+                };
+                let commit = match (self.lockfile.get(git, reference), reference) {
+                    (Some(pinned), _) => pinned.commit.clone(),
+                    (None, GitReference::Rev(rev)) => {
+                        cached_commit(git, rev).ok_or_else(not_fetched)?
+                    }
+                    (None, _) => return Err(not_fetched()),
+                };
+                let repo_dir =
+                    git_dep_location(git, &commit).map_err(DependencyResolutionError::GitError)?;
+                if !repo_dir.exists() {
+                    return Err(not_fetched());
+                }
+
+                let dir_path = match directory {
+                    Some(directory) => repo_dir.join(directory),
+                    None => repo_dir.clone(),
+                };
+                let version = GitVersion {
+                    commit,
+                    reference: reference.clone(),
+                    required_by: pkg_name.to_owned(),
+                };
+                let repository =
+                    GitRepository { root: repo_dir, url: git.clone(), version: version.clone() };
+                Ok(LocatedDep {
+                    dir_path,
+                    source: PackageSource::git(git, directory.as_deref()),
+                    repository: Some(repository),
+                    version: Some(version),
+                })
             }
             Dependency::Path { path } => {
                 let dir_path = pkg_root.join(path);
-                let meta = retrieve_meta(&dir_path, false)?;
-                Ok((dir_path, meta))
+                let repository = match repository {
+                    Some(repository) => repository,
+                    None => {
+                        return Ok(LocatedDep {
+                            source: PackageSource::local(&dir_path),
+                            dir_path,
+                            repository: None,
+                            version: None,
+                        })
+                    }
+                };
+
+                let directory =
+                    match package_key(&dir_path).strip_prefix(package_key(&repository.root)) {
+                        Ok(directory) => directory.to_path_buf(),
+                        Err(_) => {
+                            return Err(DependencyResolutionError::RemoteDepWithLocalDep {
+                                dependency_path: pkg_root.to_path_buf(),
+                            })
+                        }
+                    };
+                let version =
+                    GitVersion { required_by: pkg_name.to_owned(), ..repository.version.clone() };
+                Ok(LocatedDep {
+                    source: PackageSource::git(&repository.url, Some(&directory)),
+                    dir_path,
+                    repository: Some(repository.clone()),
+                    version: Some(version),
+                })
+            }
+        }
+    }
+}
+
+fn retrieve_meta(dir_path: &Path) -> Result<CachedDep, DependencyResolutionError> {
+    let (entry_path, crate_type) = super::lib_or_bin(dir_path)?;
+    let manifest_path = super::find_package_manifest(dir_path)?;
+    let manifest = super::manifest::parse(manifest_path)?;
+    Ok(CachedDep { entry_path, crate_type, manifest })
+}
+
+/// Checks that a git package which has already been resolved is required at the same commit
+/// by another dependency on it.
+fn check_same_version(
+    located: &LocatedDep,
+    resolved: &ResolvedPackage,
+) -> Result<(), DependencyResolutionError> {
+    match (&located.source, &resolved.version, &located.version) {
+        (PackageSource::Git { url, .. }, Some(first), Some(second))
+            if first.commit != second.commit =>
+        {
+            Err(DependencyResolutionError::ConflictingVersions {
+                git: url.clone(),
+                first: first.to_string(),
+                second: second.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Describes where a package came from, such as the repository and tag of a git package.
+fn describe_source(source: &PackageSource, package: &ResolvedPackage) -> String {
+    match source {
+        PackageSource::Local(dir_path) => dir_path.display().to_string(),
+        PackageSource::Git { url, directory } => {
+            let mut description = url.clone();
+            if let Some(version) = &package.version {
+                let short_commit = version.commit.get(..7).unwrap_or(&version.commit);
+                description.push_str(&format!(", {}, commit {short_commit}", version.reference));
             }
+            if !directory.as_os_str().is_empty() {
+                description.push_str(&format!(", directory {}", directory.display()));
+            }
+            description
         }
     }
 }
 
+/// Writes a dependency as it appears in the `Nargo.toml` of the package depending on it.
+fn describe_entry(package: &ResolvedPackage, dep: &PackageDep) -> String {
+    format!("{}: {} = {}", package.manifest_path.display(), dep.name, dep.dependency)
+}
+
+/// Returns the lockfile of the workspace containing the package at `pkg_root`.
+fn lockfile_for(pkg_root: &Path) -> Result<Lockfile, DependencyResolutionError> {
+    Ok(read_lockfile(&workspace_root(pkg_root))?.unwrap_or_default())
+}
+
+/// Identifies a package by its directory, however the directory is reached.
+fn package_key(dir_path: &Path) -> PathBuf {
+    dir_path.canonicalize().unwrap_or_else(|_| dir_path.to_path_buf())
+}
+
 // This needs to be public to support the tests in `cli/mod.rs`.
 pub(crate) fn add_std_lib(driver: &mut Driver) {
     let std_crate_name = "std";
@@ -175,3 +634,133 @@ pub(crate) fn add_std_lib(driver: &mut Driver) {
     let std_crate = driver.create_non_local_crate(path_to_std_lib_file, CrateType::Library);
     driver.propagate_dep(std_crate, &CrateName::new(std_crate_name).unwrap());
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use acvm::Language;
+    use nargo::{
+        lockfile::Lockfile,
+        manifest::{Dependency, GitReference},
+    };
+    use noirc_driver::Driver;
+
+    use super::{DependencyResolutionError, GitRepository, GitVersion, PackageSource, Resolver};
+
+    fn write_library(root: &Path, name: &str, dependencies: &str) {
+        let package_dir = root.join(name);
+        std::fs::create_dir_all(package_dir.join("src")).unwrap();
+        std::fs::write(package_dir.join("src/lib.nr"), "").unwrap();
+        let manifest = format!("[package]\nauthors = []\n\n[dependencies]\n{dependencies}\n");
+        std::fs::write(package_dir.join("Nargo.toml"), manifest).unwrap();
+    }
+
+    #[test]
+    fn reports_cycles_across_path_dependencies() {
+        let dir = tempdir::TempDir::new("cycle").unwrap();
+        write_library(dir.path(), "a", r#"b = { path = "../b" }"#);
+        write_library(dir.path(), "b", r#"c = { path = "../c" }"#);
+        write_library(dir.path(), "c", r#"a = { path = "../a" }"#);
+
+        let mut driver = Driver::new(&Language::R1CS, Box::new(|_| true));
+        match Resolver::resolve_package(&mut driver, &dir.path().join("a")) {
+            Err(DependencyResolutionError::DependencyCycle { cycle, entries }) => {
+                // The cycle is found when `c` depends back on `a`
+                assert_eq!(cycle, vec!["c", "a", "b", "c"]);
+                assert_eq!(entries.len(), 3);
+                assert!(entries[0].ends_with(r#"a = { path = "../a" }"#));
+            }
+            other => panic!("expected a dependency cycle, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_dependency_names() {
+        let dir = tempdir::TempDir::new("names").unwrap();
+        write_library(dir.path(), "a", r#"my-lib = { path = "../b" }"#);
+        write_library(dir.path(), "b", "");
+
+        let mut driver = Driver::new(&Language::R1CS, Box::new(|_| true));
+        assert!(matches!(
+            Resolver::resolve_package(&mut driver, &dir.path().join("a")),
+            Err(DependencyResolutionError::InvalidDependencyName { .. })
+        ));
+    }
+
+    #[test]
+    fn unpinned_git_dependencies_are_not_fetched() {
+        let dir = tempdir::TempDir::new("unpinned").unwrap();
+        write_library(
+            dir.path(),
+            "a",
+            r#"b = { git = "https://github.com/noir-lang/does-not-exist", tag = "v1.0.0" }"#,
+        );
+
+        // Nothing may be downloaded while resolving, as the LSP resolves on every request
+        let mut driver = Driver::new(&Language::R1CS, Box::new(|_| true));
+        match Resolver::resolve_package(&mut driver, &dir.path().join("a")) {
+            Err(DependencyResolutionError::NotFetched { dep_pkg_name, reference, .. }) => {
+                assert_eq!(dep_pkg_name, "b");
+                assert_eq!(reference, "tag v1.0.0");
+            }
+            other => panic!("expected an unfetched dependency, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn git_sources_ignore_how_the_repository_is_written() {
+        let source = PackageSource::git("https://github.com/noir-lang/libs", None);
+        assert_eq!(PackageSource::git("https://github.com/noir-lang/libs.git", None), source);
+        assert_eq!(PackageSource::git("https://github.com/noir-lang/libs/", None), source);
+
+        let merkle =
+            PackageSource::git("https://github.com/noir-lang/libs", Some(Path::new("merkle")));
+        assert_ne!(merkle, source);
+        assert_eq!(
+            PackageSource::git("https://github.com/noir-lang/libs", Some(Path::new("./merkle"))),
+            merkle
+        );
+    }
+
+    #[test]
+    fn path_dependencies_within_a_repository_are_git_packages() {
+        let dir = tempdir::TempDir::new("repository").unwrap();
+        write_library(dir.path(), "merkle", "");
+        write_library(dir.path(), "hash", r#"merkle = { path = "../merkle" }"#);
+
+        let url = "https://github.com/noir-lang/libs";
+        let version = GitVersion {
+            commit: "0123456789abcdef".to_owned(),
+            reference: GitReference::Tag("v1.0.0".to_owned()),
+            required_by: "main".to_owned(),
+        };
+        let repository =
+            GitRepository { root: dir.path().to_path_buf(), url: url.to_owned(), version };
+
+        let mut driver = Driver::new(&Language::R1CS, Box::new(|_| true));
+        let resolver = Resolver::with_driver(&mut driver, Lockfile::default());
+        let dependency = Dependency::Path { path: "../merkle".to_owned() };
+        let located = resolver
+            .locate_dep("merkle", &dependency, "hash", &dir.path().join("hash"), Some(&repository))
+            .unwrap();
+
+        // The package is the same as the one a `git` dependency with `directory = "merkle"` has
+        assert_eq!(located.source, PackageSource::git(url, Some(Path::new("merkle"))));
+        let version = located.version.unwrap();
+        assert_eq!(version.commit, "0123456789abcdef");
+        assert_eq!(version.required_by, "hash");
+
+        let outside = Dependency::Path { path: "../..".to_owned() };
+        assert!(matches!(
+            resolver.locate_dep(
+                "libs",
+                &outside,
+                "hash",
+                &dir.path().join("hash"),
+                Some(&repository)
+            ),
+            Err(DependencyResolutionError::RemoteDepWithLocalDep { .. })
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+use std::path::{Path, PathBuf};
+
+use nargo::{
+    manifest::{InvalidPackageError, Manifest, PackageManifest, WorkspaceConfig},
+    workspace::{Package, Workspace},
+};
+
+use crate::{
+    constants::{PKG_FILE, TARGET_DIR},
+    find_file, find_package_manifest, lib_or_bin,
+    manifest::{parse, parse_manifest},
+    resolver::DependencyResolutionError,
+};
+
+/// Returns the workspace of the package or workspace whose `Nargo.toml` is nearest to
+/// `program_dir`.
+///
+/// A package belongs to the `[workspace]` of a directory above it if it is listed as one of its
+/// members. Otherwise, it is a workspace of its own.
+pub(crate) fn resolve_workspace(
+    program_dir: &Path,
+) -> Result<Workspace, DependencyResolutionError> {
+    let manifest_path = find_package_manifest(program_dir)?;
+    let root_dir =
+        manifest_path.parent().expect("infallible: manifest file path can't be root directory");
+
+    let manifest = match parse_manifest(&manifest_path)? {
+        Manifest::Workspace(manifest) => return read_workspace(root_dir, &manifest.workspace),
+        Manifest::Package(manifest) => manifest,
+    };
+
+    match find_enclosing_workspace(root_dir) {
+        Some((workspace_root, config)) => read_workspace(&workspace_root, &config),
+        None => {
+            let package = read_package(root_dir, manifest)?;
+            Ok(Workspace { root_dir: root_dir.to_path_buf(), members: vec![package] })
+        }
+    }
+}
+
+/// Returns the root of the workspace containing the package or workspace at `program_dir`.
+pub(crate) fn workspace_root(program_dir: &Path) -> PathBuf {
+    match find_enclosing_workspace(program_dir) {
+        Some((workspace_root, _)) => workspace_root,
+        None => program_dir.to_path_buf(),
+    }
+}
+
+/// Returns the directory which build artifacts are written to for the package or workspace at
+/// `program_dir`. This is shared by all of the packages in a workspace.
+pub(crate) fn target_dir(program_dir: &Path) -> PathBuf {
+    workspace_root(program_dir).join(TARGET_DIR)
+}
+
+/// Returns the root and configuration of the `[workspace]` above `package_root` which lists it
+/// as a member, if any.
+fn find_enclosing_workspace(package_root: &Path) -> Option<(PathBuf, WorkspaceConfig)> {
+    package_root.ancestors().skip(1).find_map(|dir| {
+        let config = match find_file(dir, "Nargo", "toml").map(parse_manifest) {
+            Some(Ok(Manifest::Workspace(manifest))) => manifest.workspace,
+            _ => return None,
+        };
+        let is_member =
+            config.members.iter().any(|member| same_dir(&dir.join(member), package_root));
+        is_member.then(|| (dir.to_path_buf(), config))
+    })
+}
+
+fn read_workspace(
+    root_dir: &Path,
+    config: &WorkspaceConfig,
+) -> Result<Workspace, DependencyResolutionError> {
+    let mut members: Vec<Package> = Vec::new();
+    for member in &config.members {
+        let package_root = root_dir.join(member);
+        let manifest_path = package_root.join(PKG_FILE);
+        if !manifest_path.is_file() {
+            return Err(InvalidPackageError::MissingManifestFile(package_root).into());
+        }
+
+        let package = read_package(&package_root, parse(manifest_path)?)?;
+        if members.iter().any(|other| other.name == package.name) {
+            return Err(InvalidPackageError::DuplicatePackageName(package.name).into());
+        }
+        members.push(package);
+    }
+    Ok(Workspace { root_dir: root_dir.to_path_buf(), members })
+}
+
+fn read_package(
+    root_dir: &Path,
+    manifest: PackageManifest,
+) -> Result<Package, DependencyResolutionError> {
+    let (entry_path, crate_type) = lib_or_bin(root_dir)?;
+    let name = package_name(root_dir, &manifest);
+
+    Ok(Package { name, root_dir: root_dir.to_path_buf(), entry_path, crate_type, manifest })
+}
+
+/// Returns the name of the package at `root_dir`.
+/// Packages without a name are named after their directory.
+pub(crate) fn package_name(root_dir: &Path, manifest: &PackageManifest) -> String {
+    manifest.package.name.clone().unwrap_or_else(|| {
+        root_dir
+            .file_name()
+            .map_or_else(|| "package".to_owned(), |name| name.to_string_lossy().into_owned())
+    })
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
@@ -10,8 +10,8 @@ use fm::{FileId, FileManager, FileType};
 use noirc_abi::FunctionSignature;
 use noirc_errors::{CustomDiagnostic, FileDiagnostic};
 use noirc_evaluator::{create_circuit, ssa_refactor::experimental_create_circuit};
-use noirc_frontend::graph::{CrateId, CrateName, CrateType, LOCAL_CRATE};
-use noirc_frontend::hir::def_map::{Contract, CrateDefMap};
+use noirc_frontend::graph::{CrateId, CrateName, CrateType, CHARACTER_BLACK_LIST, LOCAL_CRATE};
+use noirc_frontend::hir::def_map::{Contract, CrateDefMap, TestFunction};
 use noirc_frontend::hir::Context;
 use noirc_frontend::monomorphization::monomorphize;
 use noirc_frontend::node_interner::FuncId;
@@ -22,12 +22,15 @@ mod contract;
 mod program;
 
 pub use contract::{CompiledContract, ContractFunction, ContractFunctionType};
+pub use noirc_evaluator::{RuntimeError, RuntimeErrorKind};
 pub use program::CompiledProgram;
 
 pub struct Driver {
     context: Context,
     language: Language,
     is_opcode_supported: Box<dyn Fn(&Opcode) -> bool>,
+    /// The local crate which is checked and compiled
+    local_crate: CrateId,
 }
 
 #[derive(Args, Clone, Debug, Serialize, Deserialize)]
@@ -71,9 +74,44 @@ pub type Warnings = Vec<FileDiagnostic>;
 /// Helper type used to signify where errors or warnings are expected in file diagnostics
 pub type ErrorsAndWarnings = Vec<FileDiagnostic>;
 
+/// Errors which prevent a crate from depending on another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddDependencyError {
+    /// The dependency's name contains a character which isn't allowed in crate names.
+    InvalidCrateName(String),
+    /// The dependency is a binary crate, which can't be depended upon.
+    BinaryDependency(String),
+    /// The crate is already a dependency of the dependency, directly or transitively.
+    CyclicDependency(String),
+}
+
+impl std::fmt::Display for AddDependencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddDependencyError::InvalidCrateName(name) => write!(
+                f,
+                "`{name}` is not a valid crate name as it contains one of {CHARACTER_BLACK_LIST:?}"
+            ),
+            AddDependencyError::BinaryDependency(name) => {
+                write!(f, "crates cannot depend on binaries. {name} is a binary crate")
+            }
+            AddDependencyError::CyclicDependency(name) => {
+                write!(f, "depending on {name} would create a dependency cycle")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AddDependencyError {}
+
 impl Driver {
     pub fn new(language: &Language, is_opcode_supported: Box<dyn Fn(&Opcode) -> bool>) -> Self {
-        Driver { context: Context::default(), language: language.clone(), is_opcode_supported }
+        Driver {
+            context: Context::default(),
+            language: language.clone(),
+            is_opcode_supported,
+            local_crate: LOCAL_CRATE,
+        }
     }
 
     // TODO(#1599): Move control of the FileManager into nargo
@@ -81,6 +119,19 @@ impl Driver {
         &self.context.file_manager
     }
 
+    /// Returns the results of the compilation passes which have been run so far,
+    /// for tools which need to inspect the program such as the language server.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Uses `contents` as the source of the file at `path` instead of reading it from disk.
+    ///
+    /// This must be called before the file is added to the crate graph.
+    pub fn add_file_overlay(&mut self, path: PathBuf, contents: String) {
+        self.context.file_manager.add_overlay(path, contents);
+    }
+
     // This is here for backwards compatibility
     // with the restricted version which only uses one file
     pub fn compile_file(
@@ -95,10 +146,10 @@ impl Driver {
 
     /// Adds the File with the local crate root to the file system
     /// and adds the local crate to the graph
-    /// XXX: This may pose a problem with workspaces, where you can change the local crate and where
-    /// we have multiple binaries in one workspace
-    /// A Fix would be for the driver instance to store the local crate id.
-    // Granted that this is the only place which relies on the local crate being first
+    ///
+    /// A driver may have several local crates, such as the members of a workspace,
+    /// in which case the crate checked and compiled is chosen with [`Driver::select_local_crate`].
+    /// Until then it is the most recently created local crate.
     pub fn create_local_crate<P: AsRef<Path>>(
         &mut self,
         root_file: P,
@@ -107,11 +158,20 @@ impl Driver {
         let dir_path = root_file.as_ref().to_path_buf();
         let root_file_id = self.context.file_manager.add_file(&dir_path, FileType::Root).unwrap();
 
-        let crate_id = self.context.crate_graph.add_crate_root(crate_type, root_file_id);
+        let crate_id = self.context.crate_graph.add_local_crate_root(crate_type, root_file_id);
+        self.local_crate = crate_id;
+        crate_id
+    }
 
-        assert!(crate_id == LOCAL_CRATE);
+    /// Makes `crate_id` the local crate which is checked and compiled.
+    pub fn select_local_crate(&mut self, crate_id: CrateId) {
+        assert!(self.context.crate_graph[crate_id].is_local, "ice: crate is not a local crate");
+        self.local_crate = crate_id;
+    }
 
-        LOCAL_CRATE
+    /// Returns the local crate which is checked and compiled.
+    pub fn local_crate(&self) -> CrateId {
+        self.local_crate
     }
 
     /// Creates a Non Local Crate. A Non Local Crate is any crate which is the not the crate that
@@ -133,19 +193,24 @@ impl Driver {
     }
 
     /// Adds a edge in the crate graph for two crates
-    pub fn add_dep(&mut self, this_crate: CrateId, depends_on: CrateId, crate_name: &str) {
+    pub fn add_dep(
+        &mut self,
+        this_crate: CrateId,
+        depends_on: CrateId,
+        crate_name: &str,
+    ) -> Result<(), AddDependencyError> {
         let crate_name = CrateName::new(crate_name)
-            .expect("crate name contains blacklisted characters, please remove");
+            .map_err(|name| AddDependencyError::InvalidCrateName(name.to_owned()))?;
 
         // Cannot depend on a binary
         if self.context.crate_graph.crate_type(depends_on) == CrateType::Binary {
-            panic!("crates cannot depend on binaries. {crate_name:?} is a binary crate")
+            return Err(AddDependencyError::BinaryDependency(crate_name.as_string()));
         }
 
         self.context
             .crate_graph
-            .add_dep(this_crate, crate_name, depends_on)
-            .expect("cyclic dependency triggered");
+            .add_dep(this_crate, crate_name.clone(), depends_on)
+            .map_err(|_| AddDependencyError::CyclicDependency(crate_name.as_string()))
     }
 
     /// Propagates a given dependency to every other crate.
@@ -171,7 +236,7 @@ impl Driver {
     /// On error, this returns a non-empty vector of warnings and error messages, with at least one error.
     pub fn check_crate(&mut self, deny_warnings: bool) -> Result<Warnings, ErrorsAndWarnings> {
         let mut errors = vec![];
-        CrateDefMap::collect_defs(LOCAL_CRATE, &mut self.context, &mut errors);
+        CrateDefMap::collect_defs(self.local_crate, &mut self.context, &mut errors);
 
         if Self::has_errors(&errors, deny_warnings) {
             Err(errors)
@@ -181,7 +246,7 @@ impl Driver {
     }
 
     pub fn compute_function_signature(&self) -> Option<FunctionSignature> {
-        let local_crate = self.context.def_map(LOCAL_CRATE).unwrap();
+        let local_crate = self.context.def_map(self.local_crate).unwrap();
 
         let main_function = local_crate.main_function()?;
 
@@ -203,10 +268,10 @@ impl Driver {
         let main = match self.main_function() {
             Some(m) => m,
             None => {
-                let err = FileDiagnostic {
-                    file_id: FileId::default(),
-                    diagnostic: CustomDiagnostic::from_message("cannot compile crate into a program as the local crate is not a binary. For libraries, please use the check command")
-                };
+                let err = FileDiagnostic::new(
+                    FileId::default(),
+                    CustomDiagnostic::from_message("cannot compile crate into a program as the local crate is not a binary. For libraries, please use the check command"),
+                );
                 return Err(vec![err]);
             }
         };
@@ -296,6 +361,7 @@ impl Driver {
                 function_type,
                 abi: function.abi,
                 bytecode: function.circuit,
+                debug: function.debug,
             });
         }
 
@@ -311,11 +377,11 @@ impl Driver {
     /// - Panics if no main function is found
     pub fn main_function(&self) -> Option<FuncId> {
         // Find the local crate, one should always be present
-        let local_crate = self.context.def_map(LOCAL_CRATE).unwrap();
+        let local_crate = self.context.def_map(self.local_crate).unwrap();
 
         // Check the crate type
         // We don't panic here to allow users to `evaluate` libraries which will do nothing
-        if self.context.crate_graph[LOCAL_CRATE].crate_type != CrateType::Binary {
+        if self.context.crate_graph[self.local_crate].crate_type != CrateType::Binary {
             None
         } else {
             // All Binaries should have a main function
@@ -327,12 +393,33 @@ impl Driver {
     ///
     /// This function also assumes all errors in experimental_create_circuit and create_circuit
     /// are not warnings.
-    #[allow(deprecated)]
     pub fn compile_no_check(
         &self,
         options: &CompileOptions,
         main_function: FuncId,
     ) -> Result<CompiledProgram, FileDiagnostic> {
+        // The FileId here will be the file id of the file with the main file
+        // Errors will be shown at the call site without a stacktrace
+        self.compile_function(options, main_function).map_err(Into::into)
+    }
+
+    /// Compiles a test function as [`Driver::compile_no_check`] does, returning the evaluator's
+    /// error if it fails so that constraints which can never be satisfied can be told apart
+    /// from other errors.
+    pub fn compile_test(
+        &self,
+        options: &CompileOptions,
+        test_function: FuncId,
+    ) -> Result<CompiledProgram, RuntimeError> {
+        self.compile_function(options, test_function)
+    }
+
+    #[allow(deprecated)]
+    fn compile_function(
+        &self,
+        options: &CompileOptions,
+        main_function: FuncId,
+    ) -> Result<CompiledProgram, RuntimeError> {
         let program = monomorphize(main_function, &self.context.def_interner);
 
         let np_language = self.language.clone();
@@ -355,33 +442,27 @@ impl Driver {
             )
         };
 
-        match circuit_abi {
-            Ok((circuit, abi)) => Ok(CompiledProgram { circuit, abi }),
-            Err(err) => {
-                // The FileId here will be the file id of the file with the main file
-                // Errors will be shown at the call site without a stacktrace
-                Err(err.into())
-            }
-        }
+        let (circuit, debug, abi) = circuit_abi?;
+        Ok(CompiledProgram { circuit, abi, debug })
     }
 
     /// Returns a list of all functions in the current crate marked with #[test]
     /// whose names contain the given pattern string. An empty pattern string
     /// will return all functions marked with #[test].
-    pub fn get_all_test_functions_in_crate_matching(&self, pattern: &str) -> Vec<FuncId> {
+    pub fn get_all_test_functions_in_crate_matching(&self, pattern: &str) -> Vec<TestFunction> {
         let interner = &self.context.def_interner;
         self.context
-            .def_map(LOCAL_CRATE)
+            .def_map(self.local_crate)
             .expect("The local crate should be analyzed already")
             .get_all_test_functions(interner)
-            .filter_map(|id| interner.function_name(&id).contains(pattern).then_some(id))
+            .filter(|test_function| interner.function_name(&test_function.id).contains(pattern))
             .collect()
     }
 
     /// Return a Vec of all `contract` declarations in the source code and the functions they contain
     pub fn get_all_contracts(&self) -> Vec<Contract> {
         self.context
-            .def_map(LOCAL_CRATE)
+            .def_map(self.local_crate)
             .expect("The local crate should be analyzed already")
             .get_all_contracts()
     }
@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Location;
+
+/// Debugging information for a circuit, relating its opcodes back to the source code
+/// they were generated from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugInfo {
+    /// Maps the index of an opcode within the circuit to the call stack of the expression which
+    /// produced it: the locations of the calls which were inlined to reach the expression,
+    /// outermost first, followed by the location of the expression itself.
+    /// Opcodes which can't be attributed to an expression have no entry.
+    pub locations: BTreeMap<usize, Vec<Location>>,
+    /// Maps the index of an opcode within the circuit to the message of the assertion it checks,
+    /// for assertions which were given one.
+    pub assert_messages: BTreeMap<usize, String>,
+}
+
+impl DebugInfo {
+    pub fn new(
+        locations: BTreeMap<usize, Vec<Location>>,
+        assert_messages: BTreeMap<usize, String>,
+    ) -> Self {
+        DebugInfo { locations, assert_messages }
+    }
+
+    /// Updates the locations and messages after the opcodes of the circuit have been transformed.
+    ///
+    /// `opcode_indices` gives, for each opcode of the transformed circuit, the index of the
+    /// opcode it was generated from in the circuit these locations previously referred to.
+    pub fn update_acir(&mut self, opcode_indices: &[usize]) {
+        let mut locations = BTreeMap::new();
+        let mut assert_messages = BTreeMap::new();
+        for (new_index, old_index) in opcode_indices.iter().enumerate() {
+            if let Some(call_stack) = self.locations.get(old_index) {
+                locations.insert(new_index, call_stack.clone());
+            }
+            if let Some(message) = self.assert_messages.get(old_index) {
+                assert_messages.insert(new_index, message.clone());
+            }
+        }
+        self.locations = locations;
+        self.assert_messages = assert_messages;
+    }
+
+    /// Returns the call stack of the expression which produced the opcode at `opcode_index`.
+    pub fn opcode_call_stack(&self, opcode_index: usize) -> Option<&[Location]> {
+        self.locations.get(&opcode_index).map(Vec::as_slice)
+    }
+
+    /// Returns the message of the assertion checked by the opcode at `opcode_index`, if it has one.
+    pub fn opcode_assert_message(&self, opcode_index: usize) -> Option<&str> {
+        self.assert_messages.get(&opcode_index).map(String::as_str)
+    }
+}
@@ -3,15 +3,30 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
+pub mod debug_info;
 mod position;
 pub mod reporter;
 pub use position::{Location, Position, Span, Spanned};
-pub use reporter::{CustomDiagnostic, DiagnosticKind};
+pub use reporter::{CustomDiagnostic, CustomLabel, DiagnosticKind};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileDiagnostic {
     pub file_id: fm::FileId,
     pub diagnostic: CustomDiagnostic,
+
+    /// The locations of the calls through which the error was reached when executing a program,
+    /// outermost first. This is empty for errors found while compiling.
+    pub call_stack: Vec<Location>,
+}
+
+impl FileDiagnostic {
+    pub fn new(file_id: fm::FileId, diagnostic: CustomDiagnostic) -> FileDiagnostic {
+        FileDiagnostic { file_id, diagnostic, call_stack: Vec::new() }
+    }
+
+    pub fn with_call_stack(self, call_stack: Vec<Location>) -> Self {
+        FileDiagnostic { call_stack, ..self }
+    }
 }
 
 impl From<FileDiagnostic> for Vec<FileDiagnostic> {
@@ -1,5 +1,6 @@
 use codespan::Span as ByteSpan;
 use fm::FileId;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     hash::{Hash, Hasher},
     ops::Range,
@@ -88,6 +89,19 @@ impl Span {
     }
 }
 
+/// Spans are serialized as the range of byte offsets they cover.
+impl Serialize for Span {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.start()..self.end()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Span {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Span, D::Error> {
+        Range::<u32>::deserialize(deserializer).map(Span::new)
+    }
+}
+
 impl From<Span> for Range<usize> {
     fn from(span: Span) -> Self {
         span.0.into()
@@ -114,7 +128,7 @@ impl chumsky::Span for Span {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Location {
     pub span: Span,
     pub file: FileId,
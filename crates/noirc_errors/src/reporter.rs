@@ -1,14 +1,15 @@
-use crate::{FileDiagnostic, Span};
+use crate::{FileDiagnostic, Location, Span};
 use codespan_reporting::diagnostic::{Diagnostic, Label};
+use codespan_reporting::files::Files;
 use codespan_reporting::term;
-use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
+use codespan_reporting::term::termcolor::{Buffer, ColorChoice, StandardStream};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CustomDiagnostic {
-    message: String,
-    secondaries: Vec<CustomLabel>,
-    notes: Vec<String>,
-    kind: DiagnosticKind,
+    pub message: String,
+    pub secondaries: Vec<CustomLabel>,
+    pub notes: Vec<String>,
+    pub kind: DiagnosticKind,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -60,7 +61,7 @@ impl CustomDiagnostic {
     }
 
     pub fn in_file(self, file_id: fm::FileId) -> FileDiagnostic {
-        FileDiagnostic { file_id, diagnostic: self }
+        FileDiagnostic::new(file_id, self)
     }
 
     pub fn add_note(&mut self, message: String) {
@@ -93,9 +94,9 @@ impl std::fmt::Display for CustomDiagnostic {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct CustomLabel {
-    message: String,
-    span: Span,
+pub struct CustomLabel {
+    pub message: String,
+    pub span: Span,
 }
 
 impl CustomLabel {
@@ -113,7 +114,10 @@ pub fn report_all(
 ) -> ReportedErrors {
     let error_count = diagnostics
         .iter()
-        .map(|error| report(files, &error.diagnostic, Some(error.file_id), deny_warnings) as u32)
+        .map(|error| {
+            let diagnostic = &error.diagnostic;
+            report(files, diagnostic, Some(error.file_id), &error.call_stack, deny_warnings) as u32
+        })
         .sum();
 
     ReportedErrors { error_count }
@@ -124,20 +128,36 @@ pub fn report(
     files: &fm::FileManager,
     custom_diagnostic: &CustomDiagnostic,
     file: Option<fm::FileId>,
+    call_stack: &[Location],
     deny_warnings: bool,
 ) -> bool {
     let writer = StandardStream::stderr(ColorChoice::Always);
     let config = codespan_reporting::term::Config::default();
 
-    let diagnostic = convert_diagnostic(custom_diagnostic, file, deny_warnings);
+    let diagnostic = convert_diagnostic(files, custom_diagnostic, file, call_stack, deny_warnings);
     term::emit(&mut writer.lock(), &config, files.as_simple_files(), &diagnostic).unwrap();
 
     deny_warnings || custom_diagnostic.is_error()
 }
 
+/// Renders the given diagnostic as it would be reported to stderr, but without colors, so that
+/// it can be reported later or elsewhere.
+pub fn render(files: &fm::FileManager, file_diagnostic: &FileDiagnostic) -> String {
+    let mut buffer = Buffer::no_color();
+    let config = codespan_reporting::term::Config::default();
+
+    let FileDiagnostic { file_id, diagnostic, call_stack } = file_diagnostic;
+    let diagnostic = convert_diagnostic(files, diagnostic, Some(*file_id), call_stack, false);
+    term::emit(&mut buffer, &config, files.as_simple_files(), &diagnostic).unwrap();
+
+    String::from_utf8_lossy(buffer.as_slice()).into_owned()
+}
+
 fn convert_diagnostic(
+    files: &fm::FileManager,
     cd: &CustomDiagnostic,
     file: Option<fm::FileId>,
+    call_stack: &[Location],
     deny_warnings: bool,
 ) -> Diagnostic<usize> {
     let diagnostic = match (cd.kind, deny_warnings) {
@@ -158,5 +178,32 @@ fn convert_diagnostic(
         vec![]
     };
 
-    diagnostic.with_message(&cd.message).with_labels(secondary_labels).with_notes(cd.notes.clone())
+    let mut notes = cd.notes.clone();
+    if !call_stack.is_empty() {
+        notes.push(stack_trace(files, call_stack));
+    }
+
+    diagnostic.with_message(&cd.message).with_labels(secondary_labels).with_notes(notes)
+}
+
+/// Lists the position of each call within the call stack, outermost first.
+fn stack_trace(files: &fm::FileManager, call_stack: &[Location]) -> String {
+    let files = files.as_simple_files();
+
+    let mut result = "Call stack:".to_owned();
+    for (index, location) in call_stack.iter().enumerate() {
+        let file_id = location.file.as_usize();
+        let path = files.name(file_id).expect("call stacks should only refer to known files");
+        let position = files
+            .location(file_id, location.span.start() as usize)
+            .expect("call stacks should only refer to positions within their files");
+
+        result += &format!(
+            "\n  {}. {path}:{}:{}",
+            index + 1,
+            position.line_number,
+            position.column_number
+        );
+    }
+    result
 }
@@ -41,7 +41,7 @@ impl From<RuntimeErrorKind> for RuntimeError {
 impl From<RuntimeError> for FileDiagnostic {
     fn from(err: RuntimeError) -> Self {
         let file_id = err.location.map(|loc| loc.file).unwrap();
-        FileDiagnostic { file_id, diagnostic: err.into() }
+        FileDiagnostic::new(file_id, err.into())
     }
 }
 
@@ -61,6 +61,11 @@ pub enum RuntimeErrorKind {
     #[error("Unstructured Error")]
     UnstructuredError { message: String },
 
+    /// A constraint on constant values which can never be satisfied,
+    /// along with the message of its assertion if it was given one.
+    #[error("Constraint is always false")]
+    FailingConstraint { assert_message: Option<String> },
+
     #[error("Spanless")]
     // This is here due to the fact we don't have full coverage for span
     Spanless(String),
@@ -98,6 +103,17 @@ impl From<RuntimeError> for Diagnostic {
             RuntimeErrorKind::UnstructuredError { message } => {
                 Diagnostic::simple_error("".to_owned(), message.to_string(), span)
             }
+            RuntimeErrorKind::FailingConstraint { assert_message } => {
+                let message = match assert_message {
+                    Some(assert_message) => format!("{}: {assert_message}", error.kind),
+                    None => error.kind.to_string(),
+                };
+                if error.location.is_some() {
+                    Diagnostic::simple_error("".to_owned(), message, span)
+                } else {
+                    Diagnostic::from_message(&message)
+                }
+            }
             RuntimeErrorKind::UnsupportedOp { op, first_type, second_type } => {
                 Diagnostic::simple_error(
                     "unsupported operation".to_owned(),
@@ -13,14 +13,19 @@ pub mod ssa_refactor;
 pub mod brillig;
 
 use acvm::{
-    acir::circuit::{opcodes::Opcode as AcirOpcode, Circuit, PublicInputs},
+    acir::circuit::{
+        directives::{Directive, LogInfo},
+        opcodes::Opcode as AcirOpcode,
+        Circuit, PublicInputs,
+    },
     acir::native_types::{Expression, Witness},
     compiler::optimizers::simplify::CircuitSimplifier,
     Language,
 };
-use errors::{RuntimeError, RuntimeErrorKind};
+pub use errors::{RuntimeError, RuntimeErrorKind};
 use iter_extended::vecmap;
 use noirc_abi::{Abi, AbiType, AbiVisibility};
+use noirc_errors::{debug_info::DebugInfo, Location};
 use noirc_frontend::monomorphization::ast::*;
 use ssa::{node::ObjectType, ssa_gen::IrGenerator};
 use std::collections::{BTreeMap, BTreeSet};
@@ -58,6 +63,16 @@ pub struct Evaluator {
     return_is_distinct: bool,
 
     opcodes: Vec<AcirOpcode>,
+
+    // The call stack of the instruction each opcode was generated from,
+    // keyed by the index of the opcode.
+    opcode_locations: BTreeMap<usize, Vec<Location>>,
+    // The call stack of the instruction currently being evaluated, which is empty if unknown.
+    current_call_stack: Vec<Location>,
+    // The message of each failing assertion, keyed by the index of the opcode which constrains it.
+    assert_messages: BTreeMap<usize, String>,
+    // The message of the assertion currently being evaluated, if any.
+    current_assert_message: Option<String>,
 }
 
 /// Compiles the Program into ACIR and applies optimizations to the arithmetic gates
@@ -71,7 +86,7 @@ pub fn create_circuit(
     is_opcode_supported: &impl Fn(&AcirOpcode) -> bool,
     enable_logging: bool,
     show_output: bool,
-) -> Result<(Circuit, Abi), RuntimeError> {
+) -> Result<(Circuit, DebugInfo, Abi), RuntimeError> {
     let mut evaluator = Evaluator::default();
 
     // First evaluate the main function
@@ -83,26 +98,113 @@ pub fn create_circuit(
         public_parameters,
         return_values,
         opcodes,
+        opcode_locations,
+        assert_messages,
         ..
     } = evaluator;
     let simplifier = CircuitSimplifier::new(current_witness_index);
-    let optimized_circuit = acvm::compiler::compile(
+    let (optimized_circuit, debug_info) = optimize_circuit(
         Circuit {
             current_witness_index,
             opcodes,
             public_parameters: PublicInputs(public_parameters),
             return_values: PublicInputs(return_values.iter().copied().collect()),
         },
+        DebugInfo::new(opcode_locations, assert_messages),
         np_language,
         is_opcode_supported,
         &simplifier,
-    )
-    .map_err(|_| RuntimeErrorKind::Spanless(String::from("produced an acvm compile error")))?;
+    )?;
 
     let (parameters, return_type) = program.main_function_signature;
     let abi = Abi { parameters, param_witnesses, return_type, return_witnesses: return_values };
 
-    Ok((optimized_circuit, abi))
+    Ok((optimized_circuit, debug_info, abi))
+}
+
+/// Message of the log directives used to mark the boundaries between opcodes while optimizing.
+const OPCODE_BOUNDARY_MARKER: &str = "\0noirc_evaluator::opcode_boundary";
+
+/// Applies the backend specific optimizations of the ACVM to the circuit, updating the debug
+/// information so that it refers to the opcodes of the optimized circuit.
+///
+/// The ACVM may replace an opcode by several others or remove it entirely, but it never moves
+/// opcodes past each other. As it doesn't tell which opcode each optimized opcode stems from,
+/// a log directive, which is left untouched by the optimizations, is placed after each opcode
+/// to find where the opcodes generated from one opcode end. These markers are then removed.
+///
+/// This relies on the ACVM passing directives through unchanged and in order, which the tests
+/// below check along with the optimized circuit being the same as without markers. Each marker
+/// holds the index of the opcode it follows, so should one go missing or be moved, the opcodes
+/// can't be attributed and the debug information is dropped with a warning.
+pub(crate) fn optimize_circuit(
+    circuit: Circuit,
+    mut debug_info: DebugInfo,
+    np_language: Language,
+    is_opcode_supported: &impl Fn(&AcirOpcode) -> bool,
+    simplifier: &CircuitSimplifier,
+) -> Result<(Circuit, DebugInfo), RuntimeError> {
+    let marker = |index: usize| {
+        let message = format!("{OPCODE_BOUNDARY_MARKER}{index}");
+        AcirOpcode::Directive(Directive::Log(LogInfo::FinalizedOutput(message)))
+    };
+    let original_opcode_count = circuit.opcodes.len();
+    let opcodes = circuit
+        .opcodes
+        .into_iter()
+        .enumerate()
+        .flat_map(|(index, opcode)| [opcode, marker(index)])
+        .collect();
+
+    let optimized_circuit = acvm::compiler::compile(
+        Circuit { opcodes, ..circuit },
+        np_language,
+        is_opcode_supported,
+        simplifier,
+    )
+    .map_err(|_| RuntimeErrorKind::Spanless(String::from("produced an acvm compile error")))?;
+
+    let mut opcodes = Vec::new();
+    let mut opcode_indices = Vec::new();
+    let mut markers_in_order = true;
+    let mut original_index = 0;
+    for opcode in optimized_circuit.opcodes {
+        match marker_index(&opcode) {
+            Some(index) => {
+                markers_in_order &= index == original_index;
+                original_index += 1;
+            }
+            None => {
+                opcodes.push(opcode);
+                opcode_indices.push(original_index);
+            }
+        }
+    }
+
+    let markers_intact = markers_in_order && original_index == original_opcode_count;
+    debug_assert!(markers_intact, "the ACVM dropped or reordered the opcode boundary markers");
+    if markers_intact {
+        debug_info.update_acir(&opcode_indices);
+    } else {
+        eprintln!(
+            "warning: the debug information of the circuit was lost while optimizing it, so \
+             failing constraints will be reported without their location or call stack"
+        );
+        debug_info = DebugInfo::default();
+    }
+
+    Ok((Circuit { opcodes, ..optimized_circuit }, debug_info))
+}
+
+/// Returns the index of the opcode which `opcode` marks the end of, if it is a marker added by
+/// [`optimize_circuit`].
+fn marker_index(opcode: &AcirOpcode) -> Option<usize> {
+    match opcode {
+        AcirOpcode::Directive(Directive::Log(LogInfo::FinalizedOutput(message))) => {
+            message.strip_prefix(OPCODE_BOUNDARY_MARKER)?.parse().ok()
+        }
+        _ => None,
+    }
 }
 
 impl Evaluator {
@@ -144,6 +246,12 @@ impl Evaluator {
     }
 
     pub fn push_opcode(&mut self, gate: AcirOpcode) {
+        if !self.current_call_stack.is_empty() {
+            self.opcode_locations.insert(self.opcodes.len(), self.current_call_stack.clone());
+        }
+        if let Some(message) = &self.current_assert_message {
+            self.assert_messages.insert(self.opcodes.len(), message.clone());
+        }
         self.opcodes.push(gate);
     }
 
@@ -176,7 +284,7 @@ impl Evaluator {
 
         // Link that witness to the arithmetic gate
         let constraint = &arithmetic_gate - inter_var_witness;
-        self.opcodes.push(AcirOpcode::Arithmetic(constraint));
+        self.push_opcode(AcirOpcode::Arithmetic(constraint));
         inter_var_witness
     }
 
@@ -371,3 +479,139 @@ impl Evaluator {
         self.num_witnesses_abi_len = self.current_witness_index as usize;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use acvm::{
+        acir::{
+            circuit::{
+                directives::{Directive, LogInfo},
+                opcodes::{BlackBoxFuncCall, FunctionInput},
+                Circuit, Opcode, PublicInputs,
+            },
+            native_types::{Expression, Witness},
+        },
+        compiler::optimizers::simplify::CircuitSimplifier,
+        FieldElement, Language,
+    };
+    use noirc_errors::debug_info::DebugInfo;
+
+    use super::{marker_index, optimize_circuit, OPCODE_BOUNDARY_MARKER};
+
+    fn range(witness: u32, num_bits: u32) -> Opcode {
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+            input: FunctionInput { witness: Witness(witness), num_bits },
+        })
+    }
+
+    /// A circuit whose first opcode is split into several by the width of PLONK, and whose third
+    /// opcode is removed as it is implied by the second.
+    fn test_circuit() -> Circuit {
+        let sum = Expression {
+            mul_terms: Vec::new(),
+            linear_combinations: (1..=5)
+                .map(|index| (FieldElement::one(), Witness(index)))
+                .collect(),
+            q_c: FieldElement::zero(),
+        };
+        let equal = Expression {
+            mul_terms: Vec::new(),
+            linear_combinations: vec![
+                (FieldElement::one(), Witness(6)),
+                (-FieldElement::one(), Witness(7)),
+            ],
+            q_c: FieldElement::zero(),
+        };
+        Circuit {
+            current_witness_index: 7,
+            opcodes: vec![
+                Opcode::Arithmetic(sum),
+                range(1, 8),
+                range(1, 16),
+                Opcode::Arithmetic(equal),
+            ],
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        }
+    }
+
+    #[test]
+    fn markers_do_not_change_the_optimized_circuit() {
+        for np_language in [Language::R1CS, Language::PLONKCSat { width: 3 }] {
+            let circuit = test_circuit();
+            let simplifier = CircuitSimplifier::new(circuit.current_witness_index);
+            let expected = acvm::compiler::compile(
+                circuit.clone(),
+                np_language.clone(),
+                |_: &Opcode| true,
+                &simplifier,
+            )
+            .unwrap();
+
+            let simplifier = CircuitSimplifier::new(circuit.current_witness_index);
+            let (optimized, _) = optimize_circuit(
+                circuit,
+                DebugInfo::default(),
+                np_language,
+                &|_: &Opcode| true,
+                &simplifier,
+            )
+            .unwrap();
+            assert_eq!(optimized.opcodes, expected.opcodes);
+            assert_eq!(optimized.current_witness_index, expected.current_witness_index);
+        }
+    }
+
+    #[test]
+    fn locations_follow_the_optimized_opcodes() {
+        let circuit = test_circuit();
+        let original_opcodes = circuit.opcodes.clone();
+        // Each opcode is labelled with its index, which must still be its label once optimized
+        let labels: BTreeMap<_, _> =
+            (0..original_opcodes.len()).map(|index| (index, index.to_string())).collect();
+
+        let simplifier = CircuitSimplifier::new(circuit.current_witness_index);
+        let (optimized, debug_info) = optimize_circuit(
+            circuit,
+            DebugInfo::new(BTreeMap::new(), labels),
+            Language::PLONKCSat { width: 3 },
+            &|_: &Opcode| true,
+            &simplifier,
+        )
+        .unwrap();
+
+        let original_indices: Vec<usize> = (0..optimized.opcodes.len())
+            .map(|index| debug_info.opcode_assert_message(index).unwrap().parse().unwrap())
+            .collect();
+        // Opcodes stay in order, and every opcode comes from one of the original opcodes
+        assert!(original_indices.windows(2).all(|pair| pair[0] <= pair[1]));
+        // The sum is split into several opcodes, and the redundant range constraint is removed
+        assert!(original_indices.iter().filter(|index| **index == 0).count() > 1);
+        assert!(!original_indices.contains(&2));
+
+        for (opcode, original_index) in optimized.opcodes.iter().zip(&original_indices) {
+            match opcode {
+                Opcode::Arithmetic(expression) => {
+                    let refers_to = |witness| {
+                        expression.linear_combinations.iter().any(|(_, term)| *term == witness)
+                    };
+                    // Only the last opcode refers to witness 6
+                    assert_eq!(refers_to(Witness(6)), *original_index == 3);
+                }
+                _ => assert_eq!(opcode, &original_opcodes[*original_index]),
+            }
+        }
+    }
+
+    #[test]
+    fn markers_hold_the_index_of_their_opcode() {
+        let log =
+            |message: String| Opcode::Directive(Directive::Log(LogInfo::FinalizedOutput(message)));
+
+        assert_eq!(marker_index(&log(format!("{OPCODE_BOUNDARY_MARKER}12"))), Some(12));
+        assert_eq!(marker_index(&log("12".to_owned())), None);
+        assert_eq!(marker_index(&range(1, 8)), None);
+    }
+}
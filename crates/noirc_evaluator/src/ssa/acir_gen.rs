@@ -41,11 +41,22 @@ impl Acir {
         while let Some(block) = current_block {
             for iter in &block.instructions {
                 let ins = ctx.instruction(*iter);
+                let mut call_stack = ctx.get_call_stack(ins.id);
+                if call_stack.is_empty() {
+                    call_stack.extend(ins.get_location());
+                }
+                evaluator.current_call_stack = call_stack;
+                evaluator.current_assert_message = match &ins.operation {
+                    Operation::Constrain(_, _, message) => message.clone(),
+                    _ => None,
+                };
                 self.acir_gen_instruction(ins, evaluator, ctx, show_output)?;
             }
             //TODO we should rather follow the jumps
             current_block = block.left.map(|block_id| &ctx[block_id]);
         }
+        evaluator.current_call_stack = Vec::new();
+        evaluator.current_assert_message = None;
         self.memory.acir_gen(evaluator, ctx);
         Ok(())
     }
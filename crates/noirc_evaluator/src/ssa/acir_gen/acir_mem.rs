@@ -159,7 +159,7 @@ impl ArrayHeap {
     }
 
     fn add_block_opcode(&self, evaluator: &mut Evaluator, array_id: ArrayId, array_len: u32) {
-        evaluator.opcodes.push(AcirOpcode::Block(MemoryBlock {
+        evaluator.push_opcode(AcirOpcode::Block(MemoryBlock {
             id: AcirBlockId(array_id.as_u32()),
             len: array_len,
             trace: self.trace.clone(),
@@ -179,7 +179,7 @@ impl ArrayHeap {
             let value = Self::normalize_expression(&op.value, evaluator);
             trace.push(MemOp { operation: op.operation.clone(), index, value });
         }
-        evaluator.opcodes.push(AcirOpcode::ROM(MemoryBlock {
+        evaluator.push_opcode(AcirOpcode::ROM(MemoryBlock {
             id: AcirBlockId(array_id.as_u32()),
             len: array_len,
             trace,
@@ -199,7 +199,7 @@ impl ArrayHeap {
             let value = Self::normalize_expression(&op.value, evaluator);
             trace.push(MemOp { operation: op.operation.clone(), index, value });
         }
-        evaluator.opcodes.push(AcirOpcode::RAM(MemoryBlock {
+        evaluator.push_opcode(AcirOpcode::RAM(MemoryBlock {
             id: AcirBlockId(array_id.as_u32()),
             len: array_len,
             trace,
@@ -262,7 +262,7 @@ impl ArrayHeap {
             Vec::new()
         };
         // sort directive
-        evaluator.opcodes.push(AcirOpcode::Directive(Directive::PermutationSort {
+        evaluator.push_opcode(AcirOpcode::Directive(Directive::PermutationSort {
             inputs: tuple_expressions,
             tuple: 2,
             bits: bit_counter,
@@ -270,13 +270,13 @@ impl ArrayHeap {
         }));
         if read_write {
             let init = subtract(&out_op[0], FieldElement::one(), &Expression::one());
-            evaluator.opcodes.push(AcirOpcode::Arithmetic(init));
+            evaluator.push_opcode(AcirOpcode::Arithmetic(init));
         }
         for i in 0..len - 1 {
             // index sort
             let index_sub = subtract(&out_index[i + 1], FieldElement::one(), &out_index[i]);
             let primary_order = constraints::boolean_expr(&index_sub, evaluator);
-            evaluator.opcodes.push(AcirOpcode::Arithmetic(primary_order));
+            evaluator.push_opcode(AcirOpcode::Arithmetic(primary_order));
             // counter sort
             let cmp = constraints::evaluate_cmp(
                 &out_counter[i],
@@ -291,13 +291,13 @@ impl ArrayHeap {
                 FieldElement::one(),
                 &sub_cmp,
             );
-            evaluator.opcodes.push(AcirOpcode::Arithmetic(secondary_order));
+            evaluator.push_opcode(AcirOpcode::Arithmetic(secondary_order));
             // consistency checks
             let sub2 = subtract(&out_value[i + 1], FieldElement::one(), &out_value[i]);
             let load_on_same_adr = if read_write {
                 let sub1 = subtract(&Expression::one(), FieldElement::one(), &out_op[i + 1]);
                 let store_on_new_adr = mul_with_witness(evaluator, &index_sub, &sub1);
-                evaluator.opcodes.push(AcirOpcode::Arithmetic(store_on_new_adr));
+                evaluator.push_opcode(AcirOpcode::Arithmetic(store_on_new_adr));
                 mul_with_witness(evaluator, &sub1, &sub2)
             } else {
                 subtract(
@@ -306,7 +306,7 @@ impl ArrayHeap {
                     &sub2,
                 )
             };
-            evaluator.opcodes.push(AcirOpcode::Arithmetic(load_on_same_adr));
+            evaluator.push_opcode(AcirOpcode::Arithmetic(load_on_same_adr));
         }
     }
 }
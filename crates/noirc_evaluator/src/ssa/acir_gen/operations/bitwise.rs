@@ -153,7 +153,7 @@ pub(super) fn evaluate_bitwise(
         _ => unreachable!("ICE: expected a bitwise operation"),
     };
 
-    evaluator.opcodes.push(gate);
+    evaluator.push_opcode(gate);
 
     if opcode == BinaryOp::Or {
         constraints::subtract(
@@ -183,7 +183,7 @@ pub(crate) fn evaluate(
                 }
                 _ => panic!("Unsupported low level function {:?}", op),
             };
-            evaluator.opcodes.push(AcirOpcode::BlackBoxFuncCall(func_call));
+            evaluator.push_opcode(AcirOpcode::BlackBoxFuncCall(func_call));
         }
         Opcode::Sort => {
             let mut in_expr = Vec::new();
@@ -413,7 +413,7 @@ fn evaluate_println(
         Directive::Log(LogInfo::WitnessOutput(log_witnesses))
     };
 
-    evaluator.opcodes.push(AcirOpcode::Directive(log_directive));
+    evaluator.push_opcode(AcirOpcode::Directive(log_directive));
 }
 
 /// This trims any leading zeroes.
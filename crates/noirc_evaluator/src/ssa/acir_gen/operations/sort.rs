@@ -36,7 +36,7 @@ pub(crate) fn evaluate_permutation_with_witness(
     debug_assert_eq!(w, *bits);
     // we constrain the network output to out_expr
     for (b, o) in b.iter().zip(out_expr) {
-        evaluator.opcodes.push(AcirOpcode::Arithmetic(subtract(b, FieldElement::one(), o)));
+        evaluator.push_opcode(AcirOpcode::Arithmetic(subtract(b, FieldElement::one(), o)));
     }
 }
 
@@ -148,7 +148,7 @@ impl BasicBlock {
                         cond = Some(*ins_id);
                     }
 
-                    if let node::Operation::Constrain(a, _) = ins.operation {
+                    if let node::Operation::Constrain(a, ..) = ins.operation {
                         if a == ctx.zero() || Some(a) == cond {
                             return true;
                         }
@@ -423,7 +423,7 @@ pub(super) fn short_circuit_instructions(
     // short-circuit the return instruction (if it exists)
     zero_instructions(ctx, instructions, None);
     //nop and constrain false
-    let unreachable_op = node::Operation::Constrain(ctx.zero(), None);
+    let unreachable_op = node::Operation::Constrain(ctx.zero(), None, None);
     let unreachable_ins = ctx.add_instruction(Instruction::new(
         unreachable_op,
         node::ObjectType::NotAnObject,
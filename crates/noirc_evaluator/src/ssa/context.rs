@@ -44,8 +44,11 @@ pub(crate) struct SsaContext {
     dummy_load: HashMap<ArrayId, NodeId>,
 
     //debug information
-    #[allow(dead_code)]
-    locations: HashMap<NodeId, Location>,
+    // The call stack of each instruction: the locations of the calls inlined to reach it,
+    // followed by the location of the expression it was generated for
+    locations: HashMap<NodeId, Vec<Location>>,
+    // The source location of the expression instructions are currently generated for
+    pub(crate) current_location: Option<Location>,
 }
 
 impl Default for SsaContext {
@@ -64,6 +67,7 @@ impl Default for SsaContext {
             dummy_store: HashMap::new(),
             dummy_load: HashMap::new(),
             locations: HashMap::new(),
+            current_location: None,
             constants: HashMap::new(),
         };
         block::create_first_block(&mut pc);
@@ -569,7 +573,34 @@ impl SsaContext {
         if let Mark::ReplaceWith(replacement) = i.mark {
             return Ok(replacement);
         }
-        Ok(self.push_instruction(i))
+        let id = self.push_instruction(i);
+        if let Some(location) = self.current_location {
+            self.locations.insert(id, vec![location]);
+        }
+        Ok(id)
+    }
+
+    /// Returns the call stack of the instruction, which is empty if it is not known.
+    pub(crate) fn get_call_stack(&self, id: NodeId) -> Vec<Location> {
+        self.locations.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Records the instruction `to` as being generated for the same expression as `from`,
+    /// when copying instructions while unrolling.
+    pub(crate) fn copy_location(&mut self, from: NodeId, to: NodeId) {
+        if let Some(call_stack) = self.locations.get(&from) {
+            self.locations.insert(to, call_stack.clone());
+        }
+    }
+
+    /// Records the instruction `to`, copied from `from` while inlining the call `call`, as being
+    /// reached through that call.
+    pub(crate) fn inline_location(&mut self, call: NodeId, from: NodeId, to: NodeId) {
+        let mut call_stack = self.get_call_stack(call);
+        call_stack.extend(self.get_call_stack(from));
+        if !call_stack.is_empty() {
+            self.locations.insert(to, call_stack);
+        }
     }
 
     pub(crate) fn find_const_with_type(
@@ -1251,7 +1282,7 @@ impl SsaContext {
                 };
                 bin.predicate = Some(cond);
             }
-            Operation::Constrain(cond, _) => {
+            Operation::Constrain(cond, ..) => {
                 let operation =
                     Operation::Cond { condition: pred, val_true: *cond, val_false: self.one() };
                 let c_ins = self.add_instruction(Instruction::new(
@@ -130,6 +130,7 @@ pub(super) fn unroll_std_block(
                         match new_ins.mark {
                             Mark::None => {
                                 let id = ctx.push_instruction(new_ins);
+                                ctx.copy_location(*i_id, id);
                                 unroll_ctx.eval_map.insert(*i_id, NodeEval::VarOrInstruction(id));
                             }
                             Mark::Deleted => (),
@@ -306,7 +306,7 @@ fn inline_in_block(
                 Operation::Call { .. } => {
                     *nested_call = true;
                     let new_ins = new_cloned_instruction(clone, stack_frame.block);
-                    push_instruction(ctx, new_ins, stack_frame, inline_map);
+                    push_instruction(ctx, new_ins, call_id, stack_frame, inline_map);
                 }
                 Operation::Load { array_id, index, location } => {
                     //Compute the new address:
@@ -317,7 +317,7 @@ fn inline_in_block(
                         Some(stack_frame.block),
                     );
                     new_ins.id = clone.id;
-                    push_instruction(ctx, new_ins, stack_frame, inline_map);
+                    push_instruction(ctx, new_ins, call_id, stack_frame, inline_map);
                 }
                 Operation::Store { array_id, index, value, predicate, location } => {
                     let b = stack_frame.get_or_default(*array_id);
@@ -333,7 +333,7 @@ fn inline_in_block(
                         Some(stack_frame.block),
                     );
                     new_ins.id = clone.id;
-                    push_instruction(ctx, new_ins, stack_frame, inline_map);
+                    push_instruction(ctx, new_ins, call_id, stack_frame, inline_map);
                 }
                 Operation::Phi { .. } => {
                     unreachable!("Phi instructions should have been simplified");
@@ -373,7 +373,7 @@ fn inline_in_block(
                             assert!(stack_frame.stack.contains(&replacement));
                         }
                     } else {
-                        push_instruction(ctx, new_ins, stack_frame, inline_map);
+                        push_instruction(ctx, new_ins, call_id, stack_frame, inline_map);
                     }
                 }
             }
@@ -410,11 +410,13 @@ fn new_cloned_instruction(original: Instruction, block: BlockId) -> Instruction
 fn push_instruction(
     ctx: &mut SsaContext,
     instruction: Instruction,
+    call_id: NodeId,
     stack_frame: &mut StackFrame,
     inline_map: &mut HashMap<NodeId, NodeId>,
 ) {
     let old_id = instruction.id;
     let new_id = ctx.add_instruction(instruction);
+    ctx.inline_location(call_id, old_id, new_id);
     stack_frame.push(new_id);
     inline_map.insert(old_id, new_id);
 }
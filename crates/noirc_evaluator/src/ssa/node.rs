@@ -431,25 +431,16 @@ impl Instruction {
                     return Ok(NodeEval::Const(FieldElement::from((!l) & max), self.res_type));
                 }
             }
-            Operation::Constrain(value, location) => {
+            Operation::Constrain(value, location, message) => {
                 if let Some(obj) = eval_fn(ctx, *value)?.into_const_value() {
                     if obj.is_one() {
                         // Delete the constrain, it is always true
                         return Ok(NodeEval::VarOrInstruction(NodeId::dummy()));
                     } else if obj.is_zero() {
-                        if let Some(location) = *location {
-                            return Err(RuntimeError::new(
-                                RuntimeErrorKind::UnstructuredError {
-                                    message: "Constraint is always false".into(),
-                                },
-                                Some(location),
-                            ));
-                        } else {
-                            return Err(RuntimeErrorKind::Spanless(
-                                "Constraint is always false".into(),
-                            )
-                            .into());
-                        }
+                        return Err(RuntimeError::new(
+                            RuntimeErrorKind::FailingConstraint { assert_message: message.clone() },
+                            *location,
+                        ));
                     }
                 }
             }
@@ -521,7 +512,7 @@ impl Instruction {
             Operation::Call { location, .. } => Some(*location),
             Operation::Load { location, .. }
             | Operation::Store { location, .. }
-            | Operation::Constrain(_, location) => *location,
+            | Operation::Constrain(_, location, _) => *location,
             Operation::Cast(_)
             | Operation::Truncate { .. }
             | Operation::Not(_)
@@ -551,7 +542,8 @@ pub(crate) enum Operation {
     }, //truncate
 
     Not(NodeId), //(!) Bitwise Not
-    Constrain(NodeId, Option<Location>),
+    /// Constrains a value to be true, with the message to report if it isn't
+    Constrain(NodeId, Option<Location>, Option<String>),
 
     //control flow
     Jne(NodeId, BlockId), //jump on not equal
@@ -1202,7 +1194,7 @@ impl Operation {
                 Truncate { value: f(*value), bit_size: *bit_size, max_bit_size: *max_bit_size }
             }
             Not(id) => Not(f(*id)),
-            Constrain(id, loc) => Constrain(f(*id), *loc),
+            Constrain(id, loc, message) => Constrain(f(*id), *loc, message.clone()),
             Jne(id, block) => Jne(f(*id), *block),
             Jeq(id, block) => Jeq(f(*id), *block),
             Jmp(block) => Jmp(*block),
@@ -50,6 +50,7 @@ impl IrGenerator {
         let value = self.ssa_gen_expression(&main_body)?;
         let node_ids = value.to_node_ids();
 
+        self.context.current_location = None;
         if self.program.main().return_type != Type::Unit {
             self.context.new_instruction(Operation::Return(node_ids), ObjectType::NotAnObject)?;
         }
@@ -224,6 +225,7 @@ impl IrGenerator {
         // Get the opcode from the infix operator
         let opcode = Operation::Binary(Binary::from_ast(op, lhs_type, lhs, rhs, location));
         let op_type = self.context.get_result_type(&opcode, lhs_type);
+        self.context.current_location = Some(location);
         self.context.new_instruction(opcode, op_type)
     }
 
@@ -350,9 +352,11 @@ impl IrGenerator {
         &mut self,
         expr: &Expression,
         location: noirc_errors::Location,
+        message: &Option<String>,
     ) -> Result<Value, RuntimeError> {
         let cond = self.ssa_gen_expression(expr)?.unwrap_id();
-        let operation = Operation::Constrain(cond, Some(location));
+        self.context.current_location = Some(location);
+        let operation = Operation::Constrain(cond, Some(location), message.clone());
         self.context.new_instruction(operation, ObjectType::NotAnObject)?;
         Ok(Value::dummy())
     }
@@ -456,6 +460,7 @@ impl IrGenerator {
                 let (lhs_id, array_idx, loc) =
                     self.ssa_gen_indexed_value(array.as_ref(), index, *location)?;
                 let rhs_id = rhs.unwrap_id();
+                self.context.current_location = Some(loc);
                 self.context.handle_assign(lhs_id, Some(array_idx), rhs_id, Some(loc))?;
             }
             LValue::MemberAccess { object: _, field_index } => {
@@ -538,6 +543,7 @@ impl IrGenerator {
                 let e_type = array.element_type;
                 // Evaluate the index expression
                 let index_as_obj = self.ssa_gen_expression(&indexed_expr.index)?.unwrap_id();
+                self.context.current_location = Some(indexed_expr.location);
                 let load = Operation::Load {
                     array_id,
                     index: index_as_obj,
@@ -563,8 +569,8 @@ impl IrGenerator {
                 Ok(tuple.into_field_member(*field))
             }
             Expression::Let(let_expr) => self.ssa_gen_let(let_expr),
-            Expression::Constrain(expr, location) => {
-                self.ssa_gen_constrain(expr.as_ref(), *location)
+            Expression::Constrain(expr, location, message) => {
+                self.ssa_gen_constrain(expr.as_ref(), *location, message)
             }
             Expression::Assign(assign) => {
                 self.ssa_gen_assign(&assign.lvalue, assign.expression.as_ref())
@@ -7,12 +7,13 @@
 //! This module heavily borrows from Cranelift
 #![allow(dead_code)]
 
-use crate::errors::RuntimeError;
+use crate::{errors::RuntimeError, optimize_circuit};
 use acvm::{
     acir::circuit::{Circuit, Opcode as AcirOpcode, PublicInputs},
     Language,
 };
 use noirc_abi::Abi;
+use noirc_errors::debug_info::DebugInfo;
 
 use noirc_frontend::monomorphization::ast::Program;
 
@@ -61,10 +62,16 @@ pub fn experimental_create_circuit(
     is_opcode_supported: &impl Fn(&AcirOpcode) -> bool,
     enable_logging: bool,
     show_output: bool,
-) -> Result<(Circuit, Abi), RuntimeError> {
+) -> Result<(Circuit, DebugInfo, Abi), RuntimeError> {
     let func_sig = program.main_function_signature.clone();
-    let GeneratedAcir { current_witness_index, opcodes, return_witnesses } =
-        optimize_into_acir(program, show_output, enable_logging);
+    let GeneratedAcir {
+        current_witness_index,
+        opcodes,
+        return_witnesses,
+        locations,
+        assert_messages,
+        ..
+    } = optimize_into_acir(program, show_output, enable_logging);
 
     let abi = gen_abi(func_sig, return_witnesses.clone());
     let public_abi = abi.clone().public_abi();
@@ -76,23 +83,22 @@ pub fn experimental_create_circuit(
     // This region of code will optimize the ACIR bytecode for a particular backend
     // it will be removed in the near future and we will subsequently only return the
     // unoptimized backend-agnostic bytecode here
-    let optimized_circuit = {
-        use crate::errors::RuntimeErrorKind;
+    let (optimized_circuit, debug_info) = {
         use acvm::compiler::optimizers::simplify::CircuitSimplifier;
 
         let abi_len = abi.field_count();
 
         let simplifier = CircuitSimplifier::new(abi_len);
-        acvm::compiler::compile(
+        optimize_circuit(
             Circuit { current_witness_index, opcodes, public_parameters, return_values },
+            DebugInfo::new(locations, assert_messages),
             np_language,
             is_opcode_supported,
             &simplifier,
-        )
-        .map_err(|_| RuntimeErrorKind::Spanless(String::from("produced an acvm compile error")))?
+        )?
     };
 
-    Ok((optimized_circuit, abi))
+    Ok((optimized_circuit, debug_info, abi))
 }
 
 impl Ssa {
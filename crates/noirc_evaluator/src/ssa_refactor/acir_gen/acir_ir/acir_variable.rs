@@ -1,7 +1,9 @@
 use super::{errors::AcirGenError, generated_acir::GeneratedAcir};
 use crate::ssa_refactor::acir_gen::AcirValue;
 use crate::ssa_refactor::ir::types::Type as SsaType;
-use crate::ssa_refactor::ir::{instruction::Endian, map::TwoWayMap, types::NumericType};
+use crate::ssa_refactor::ir::{
+    dfg::CallStack, instruction::Endian, map::TwoWayMap, types::NumericType,
+};
 use acvm::acir::{
     brillig_vm::Opcode as BrilligOpcode,
     circuit::brillig::{BrilligInputs, BrilligOutputs},
@@ -652,6 +654,16 @@ impl AcirContext {
         }
     }
 
+    /// Sets the call stack which opcodes added from now on are generated from.
+    pub(crate) fn set_call_stack(&mut self, call_stack: CallStack) {
+        self.acir_ir.call_stack = call_stack;
+    }
+
+    /// Sets the message to report if the opcodes added from now on fail, until it is unset.
+    pub(crate) fn set_assert_message(&mut self, message: Option<String>) {
+        self.acir_ir.assert_message = message;
+    }
+
     /// Terminates the context and takes the resulting `GeneratedAcir`
     pub(crate) fn finish(self) -> GeneratedAcir {
         self.acir_ir
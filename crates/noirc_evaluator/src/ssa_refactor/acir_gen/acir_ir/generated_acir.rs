@@ -1,6 +1,9 @@
 //! `GeneratedAcir` is constructed as part of the `acir_gen` pass to accumulate all of the ACIR
 //! program as it is being converted from SSA form.
+use std::collections::BTreeMap;
+
 use super::errors::AcirGenError;
+use crate::ssa_refactor::ir::dfg::CallStack;
 use acvm::acir::{
     brillig_vm::Opcode as BrilligOpcode,
     circuit::{
@@ -34,6 +37,20 @@ pub(crate) struct GeneratedAcir {
     /// Note: This may contain repeated indices, which is necessary for later mapping into the
     /// abi's return type.
     pub(crate) return_witnesses: Vec<Witness>,
+
+    /// The call stack of the instruction each opcode was generated from,
+    /// keyed by the index of the opcode.
+    pub(crate) locations: BTreeMap<usize, CallStack>,
+
+    /// The call stack of the instruction currently being converted, which is empty if unknown.
+    pub(crate) call_stack: CallStack,
+
+    /// The message of the failed assertion to report for each opcode which checks one,
+    /// keyed by the index of the opcode.
+    pub(crate) assert_messages: BTreeMap<usize, String>,
+
+    /// The message of the assertion currently being converted, if any.
+    pub(crate) assert_message: Option<String>,
 }
 
 impl GeneratedAcir {
@@ -44,6 +61,12 @@ impl GeneratedAcir {
 
     /// Adds a new opcode into ACIR.
     fn push_opcode(&mut self, opcode: AcirOpcode) {
+        if !self.call_stack.is_empty() {
+            self.locations.insert(self.opcodes.len(), self.call_stack.clone());
+        }
+        if let Some(message) = &self.assert_message {
+            self.assert_messages.insert(self.opcodes.len(), message.clone());
+        }
         self.opcodes.push(opcode);
     }
 
@@ -255,7 +278,7 @@ impl GeneratedAcir {
             }
         };
 
-        self.opcodes.push(AcirOpcode::BlackBoxFuncCall(black_box_func_call));
+        self.push_opcode(AcirOpcode::BlackBoxFuncCall(black_box_func_call));
 
         outputs_clone
     }
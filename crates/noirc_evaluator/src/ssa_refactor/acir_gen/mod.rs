@@ -8,7 +8,7 @@ use self::acir_ir::{
 };
 use super::{
     ir::{
-        dfg::DataFlowGraph,
+        dfg::{CallStack, DataFlowGraph},
         function::RuntimeType,
         instruction::{
             Binary, BinaryOp, Instruction, InstructionId, Intrinsic, TerminatorInstruction,
@@ -89,6 +89,7 @@ impl Context {
             self.convert_ssa_instruction(*instruction_id, dfg, &ssa, &brillig, allow_log_ops);
         }
 
+        self.acir_context.set_call_stack(CallStack::new());
         self.convert_ssa_return(entry_block.terminator().unwrap(), dfg);
 
         self.acir_context.finish()
@@ -149,6 +150,7 @@ impl Context {
         allow_log_ops: bool,
     ) {
         let instruction = &dfg[instruction_id];
+        self.acir_context.set_call_stack(dfg.get_call_stack(instruction_id));
 
         match instruction {
             Instruction::Binary(binary) => {
@@ -157,11 +159,13 @@ impl Context {
                     .expect("add Result types to all methods so errors bubble up");
                 self.define_result_var(dfg, instruction_id, result_acir_var);
             }
-            Instruction::Constrain(value_id) => {
+            Instruction::Constrain(value_id, message) => {
                 let constrain_condition = self.convert_numeric_value(*value_id, dfg);
+                self.acir_context.set_assert_message(message.clone());
                 self.acir_context
                     .assert_eq_one(constrain_condition)
                     .expect("add Result types to all methods so errors bubble up");
+                self.acir_context.set_assert_message(None);
             }
             Instruction::Cast(value_id, typ) => {
                 let result_acir_var = self.convert_ssa_cast(value_id, typ, dfg);
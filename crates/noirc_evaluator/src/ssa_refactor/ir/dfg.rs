@@ -15,6 +15,11 @@ use super::{
 
 use acvm::FieldElement;
 use iter_extended::vecmap;
+use noirc_errors::Location;
+
+/// The locations of the calls which were inlined to reach an instruction, outermost first,
+/// followed by the location of the code the instruction was generated from.
+pub(crate) type CallStack = Vec<Location>;
 
 /// The DataFlowGraph contains most of the actual data in a function including
 /// its blocks, instructions, and values. This struct is largely responsible for
@@ -64,6 +69,10 @@ pub(crate) struct DataFlowGraph {
     /// for that of another. This information is purely used for printing the SSA, and has no
     /// material effect on the SSA itself.
     replaced_value_ids: HashMap<ValueId, ValueId>,
+
+    /// Call stack of each instruction, used to relate the opcodes generated
+    /// from an instruction back to the code it came from.
+    locations: HashMap<InstructionId, CallStack>,
 }
 
 impl DataFlowGraph {
@@ -127,11 +136,14 @@ impl DataFlowGraph {
     }
 
     /// Inserts a new instruction at the end of the given block and returns its results
+    ///
+    /// The instruction is recorded as originating from the code at the top of `call_stack`.
     pub(crate) fn insert_instruction_and_results(
         &mut self,
         instruction: Instruction,
         block: BasicBlockId,
         ctrl_typevars: Option<Vec<Type>>,
+        call_stack: CallStack,
     ) -> InsertInstructionResult {
         use InsertInstructionResult::*;
         match instruction.simplify(self) {
@@ -140,6 +152,9 @@ impl DataFlowGraph {
             SimplifyResult::None => {
                 let id = self.make_instruction(instruction, ctrl_typevars);
                 self.blocks[block].insert_instruction(id);
+                if !call_stack.is_empty() {
+                    self.locations.insert(id, call_stack);
+                }
                 InsertInstructionResult::Results(self.instruction_results(id))
             }
         }
@@ -287,6 +302,11 @@ impl DataFlowGraph {
         self.results.get(&instruction_id).expect("expected a list of Values").as_slice()
     }
 
+    /// Returns the call stack of the given instruction, which is empty if it is not known.
+    pub(crate) fn get_call_stack(&self, instruction_id: InstructionId) -> CallStack {
+        self.locations.get(&instruction_id).cloned().unwrap_or_default()
+    }
+
     /// Add a parameter to the given block
     pub(crate) fn add_block_parameter(&mut self, block_id: BasicBlockId, typ: Type) -> ValueId {
         let block = &mut self.blocks[block_id];
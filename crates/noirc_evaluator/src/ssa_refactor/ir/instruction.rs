@@ -86,8 +86,8 @@ pub(crate) enum Instruction {
     /// Truncates `value` to `bit_size`
     Truncate { value: ValueId, bit_size: u32, max_bit_size: u32 },
 
-    /// Constrains a value to be equal to true
-    Constrain(ValueId),
+    /// Constrains a value to be equal to true, with an optional message to report if it isn't
+    Constrain(ValueId, Option<String>),
 
     /// Performs a function call with a list of its arguments.
     Call { func: ValueId, arguments: Vec<ValueId> },
@@ -136,7 +136,7 @@ impl Instruction {
                 InstructionResultType::Operand(*value)
             }
             Instruction::ArraySet { array, .. } => InstructionResultType::Operand(*array),
-            Instruction::Constrain(_)
+            Instruction::Constrain(..)
             | Instruction::Store { .. }
             | Instruction::EnableSideEffects { .. } => InstructionResultType::None,
             Instruction::Load { .. } | Instruction::ArrayGet { .. } | Instruction::Call { .. } => {
@@ -168,7 +168,9 @@ impl Instruction {
                 bit_size: *bit_size,
                 max_bit_size: *max_bit_size,
             },
-            Instruction::Constrain(value) => Instruction::Constrain(f(*value)),
+            Instruction::Constrain(value, message) => {
+                Instruction::Constrain(f(*value), message.clone())
+            }
             Instruction::Call { func, arguments } => Instruction::Call {
                 func: f(*func),
                 arguments: vecmap(arguments.iter().copied(), f),
@@ -206,7 +208,7 @@ impl Instruction {
             Instruction::Cast(value, _)
             | Instruction::Not(value)
             | Instruction::Truncate { value, .. }
-            | Instruction::Constrain(value)
+            | Instruction::Constrain(value, _)
             | Instruction::Load { address: value } => {
                 f(*value);
             }
@@ -263,7 +265,7 @@ impl Instruction {
                     _ => None,
                 }
             }
-            Instruction::Constrain(value) => {
+            Instruction::Constrain(value, _) => {
                 if let Some(constant) = dfg.get_numeric_constant(*value) {
                     if constant.is_one() {
                         return Remove;
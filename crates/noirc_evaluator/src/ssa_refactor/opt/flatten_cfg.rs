@@ -140,7 +140,7 @@ use crate::ssa_refactor::{
     ir::{
         basic_block::BasicBlockId,
         cfg::ControlFlowGraph,
-        dfg::InsertInstructionResult,
+        dfg::{CallStack, InsertInstructionResult},
         dom::DominatorTree,
         function::Function,
         instruction::{BinaryOp, Instruction, InstructionId, TerminatorInstruction},
@@ -311,7 +311,8 @@ impl<'f> Context<'f> {
                 let then_branch =
                     self.inline_branch(block, then_block, old_condition, then_condition, one);
 
-                let else_condition = self.insert_instruction(Instruction::Not(then_condition));
+                let else_condition =
+                    self.insert_instruction(Instruction::Not(then_condition), CallStack::new());
                 let zero = FieldElement::zero();
 
                 let else_branch =
@@ -362,7 +363,7 @@ impl<'f> Context<'f> {
 
         if let Some((_, previous_condition)) = self.conditions.last() {
             let and = Instruction::binary(BinaryOp::And, *previous_condition, condition);
-            let new_condition = self.insert_instruction(and);
+            let new_condition = self.insert_instruction(and, CallStack::new());
             self.conditions.push((end_block, new_condition));
         } else {
             self.conditions.push((end_block, condition));
@@ -372,9 +373,12 @@ impl<'f> Context<'f> {
     /// Insert a new instruction into the function's entry block.
     /// Unlike push_instruction, this function will not map any ValueIds.
     /// within the given instruction, nor will it modify self.values in any way.
-    fn insert_instruction(&mut self, instruction: Instruction) -> ValueId {
+    fn insert_instruction(&mut self, instruction: Instruction, call_stack: CallStack) -> ValueId {
         let block = self.function.entry_block();
-        self.function.dfg.insert_instruction_and_results(instruction, block, None).first()
+        self.function
+            .dfg
+            .insert_instruction_and_results(instruction, block, None, call_stack)
+            .first()
     }
 
     /// Inserts a new instruction into the function's entry block, using the given
@@ -385,9 +389,15 @@ impl<'f> Context<'f> {
         &mut self,
         instruction: Instruction,
         ctrl_typevars: Option<Vec<Type>>,
+        call_stack: CallStack,
     ) -> InsertInstructionResult {
         let block = self.function.entry_block();
-        self.function.dfg.insert_instruction_and_results(instruction, block, ctrl_typevars)
+        self.function.dfg.insert_instruction_and_results(
+            instruction,
+            block,
+            ctrl_typevars,
+            call_stack,
+        )
     }
 
     /// Checks the branch condition on the top of the stack and uses it to build and insert an
@@ -401,7 +411,7 @@ impl<'f> Context<'f> {
             None => self.function.dfg.make_constant(FieldElement::one(), Type::unsigned(1)),
         };
         let enable_side_effects = Instruction::EnableSideEffects { condition };
-        self.insert_instruction_with_typevars(enable_side_effects, None);
+        self.insert_instruction_with_typevars(enable_side_effects, None, CallStack::new());
     }
 
     /// Merge two values a and b from separate basic blocks to a single value. This
@@ -415,13 +425,21 @@ impl<'f> Context<'f> {
     ) -> ValueId {
         let block = self.function.entry_block();
         let mul = Instruction::binary(BinaryOp::Mul, then_condition, then_value);
-        let then_value = self.function.dfg.insert_instruction_and_results(mul, block, None).first();
+        let then_value = self
+            .function
+            .dfg
+            .insert_instruction_and_results(mul, block, None, CallStack::new())
+            .first();
 
         let mul = Instruction::binary(BinaryOp::Mul, else_condition, else_value);
-        let else_value = self.function.dfg.insert_instruction_and_results(mul, block, None).first();
+        let else_value = self
+            .function
+            .dfg
+            .insert_instruction_and_results(mul, block, None, CallStack::new())
+            .first();
 
         let add = Instruction::binary(BinaryOp::Add, then_value, else_value);
-        self.function.dfg.insert_instruction_and_results(add, block, None).first()
+        self.function.dfg.insert_instruction_and_results(add, block, None, CallStack::new()).first()
     }
 
     /// Inline one branch of a jmpif instruction.
@@ -520,7 +538,11 @@ impl<'f> Context<'f> {
             let then_condition = then_branch.condition;
             let else_condition = else_branch.condition;
             let value = self.merge_values(then_condition, else_condition, then_case, else_case);
-            self.insert_instruction_with_typevars(Instruction::Store { address, value }, None);
+            self.insert_instruction_with_typevars(
+                Instruction::Store { address, value },
+                None,
+                CallStack::new(),
+            );
 
             if let Some(store) = self.store_values.get_mut(&address) {
                 store.new_value = value;
@@ -544,7 +566,8 @@ impl<'f> Context<'f> {
         } else {
             let load = Instruction::Load { address };
             let load_type = Some(vec![self.function.dfg.type_of_value(new_value)]);
-            let old_value = self.insert_instruction_with_typevars(load, load_type).first();
+            let old_value =
+                self.insert_instruction_with_typevars(load, load_type, CallStack::new()).first();
 
             self.store_values.insert(address, Store { old_value, new_value });
         }
@@ -584,7 +607,8 @@ impl<'f> Context<'f> {
     /// will also be mapped to the results of the new instruction.
     fn push_instruction(&mut self, id: InstructionId) {
         let instruction = self.function.dfg[id].map_values(|id| self.translate_value(id));
-        let instruction = self.handle_instruction_side_effects(instruction);
+        let call_stack = self.function.dfg.get_call_stack(id);
+        let instruction = self.handle_instruction_side_effects(instruction, call_stack.clone());
         let results = self.function.dfg.instruction_results(id);
         let results = vecmap(results, |id| self.function.dfg.resolve(*id));
 
@@ -593,25 +617,34 @@ impl<'f> Context<'f> {
             .then(|| vecmap(&results, |result| self.function.dfg.type_of_value(*result)));
 
         let block = self.function.entry_block();
-        let new_results =
-            self.function.dfg.insert_instruction_and_results(instruction, block, ctrl_typevars);
+        let new_results = self.function.dfg.insert_instruction_and_results(
+            instruction,
+            block,
+            ctrl_typevars,
+            call_stack,
+        );
         Self::insert_new_instruction_results(&mut self.values, &results, new_results);
     }
 
     /// If we are currently in a branch, we need to modify constrain instructions
     /// to multiply them by the branch's condition (see optimization #1 in the module comment).
-    fn handle_instruction_side_effects(&mut self, instruction: Instruction) -> Instruction {
+    fn handle_instruction_side_effects(
+        &mut self,
+        instruction: Instruction,
+        call_stack: CallStack,
+    ) -> Instruction {
         if let Some((_, condition)) = self.conditions.last().copied() {
             match instruction {
-                Instruction::Constrain(value) => {
-                    let mul = self.insert_instruction(Instruction::binary(
-                        BinaryOp::Mul,
-                        value,
-                        condition,
-                    ));
-                    let eq =
-                        self.insert_instruction(Instruction::binary(BinaryOp::Eq, mul, condition));
-                    Instruction::Constrain(eq)
+                Instruction::Constrain(value, message) => {
+                    let mul = self.insert_instruction(
+                        Instruction::binary(BinaryOp::Mul, value, condition),
+                        call_stack.clone(),
+                    );
+                    let eq = self.insert_instruction(
+                        Instruction::binary(BinaryOp::Eq, mul, condition),
+                        call_stack,
+                    );
+                    Instruction::Constrain(eq, message)
                 }
                 Instruction::Store { address, value } => {
                     self.remember_store(address, value);
@@ -740,7 +773,7 @@ mod test {
         builder.terminate_with_jmpif(v0, b1, b2);
 
         builder.switch_to_block(b1);
-        builder.insert_constrain(v1);
+        builder.insert_constrain(v1, None);
         builder.terminate_with_jmp(b2, vec![]);
 
         builder.switch_to_block(b2);
@@ -9,7 +9,7 @@ use iter_extended::vecmap;
 use crate::ssa_refactor::{
     ir::{
         basic_block::BasicBlockId,
-        dfg::InsertInstructionResult,
+        dfg::{CallStack, InsertInstructionResult},
         function::{Function, FunctionId, RuntimeType},
         instruction::{Instruction, InstructionId, TerminatorInstruction},
         value::{Value, ValueId},
@@ -49,6 +49,10 @@ struct InlineContext {
     recursion_level: u32,
     builder: FunctionBuilder,
 
+    /// The call stack of the call currently being inlined, which is prepended to the call stack
+    /// of each instruction inlined from the function it calls.
+    call_stack: CallStack,
+
     /// True if we failed to inline at least one call. If this is still false when finishing
     /// inlining we can remove all other functions from the resulting Ssa struct and keep only
     /// the function that was inlined into.
@@ -93,7 +97,12 @@ impl InlineContext {
     fn new(ssa: &Ssa) -> InlineContext {
         let main_name = ssa.main().name().to_owned();
         let builder = FunctionBuilder::new(main_name, ssa.next_id.next(), RuntimeType::Acir);
-        Self { builder, recursion_level: 0, failed_to_inline_a_call: false }
+        Self {
+            builder,
+            recursion_level: 0,
+            call_stack: CallStack::new(),
+            failed_to_inline_a_call: false,
+        }
     }
 
     /// Start inlining the main function and all functions reachable from it.
@@ -120,12 +129,13 @@ impl InlineContext {
     }
 
     /// Inlines a function into the current function and returns the translated return values
-    /// of the inlined function.
+    /// of the inlined function. `call_stack` is the call stack of the call being inlined.
     fn inline_function(
         &mut self,
         ssa: &Ssa,
         id: FunctionId,
         arguments: &[ValueId],
+        call_stack: CallStack,
     ) -> Vec<ValueId> {
         self.recursion_level += 1;
 
@@ -145,7 +155,11 @@ impl InlineContext {
         let current_block = context.context.builder.current_block();
         context.blocks.insert(source_function.entry_block(), current_block);
 
-        context.inline_blocks(ssa)
+        let caller_call_stack_len = context.context.call_stack.len();
+        context.context.call_stack.extend(call_stack);
+        let return_values = context.inline_blocks(ssa);
+        self.call_stack.truncate(caller_call_stack_len);
+        return_values
     }
 
     /// Finish inlining and return the new Ssa struct with the inlined version of main.
@@ -355,7 +369,8 @@ impl<'function> PerFunctionContext<'function> {
     ) {
         let old_results = self.source_function.dfg.instruction_results(call_id);
         let arguments = vecmap(arguments, |arg| self.translate_value(*arg));
-        let new_results = self.context.inline_function(ssa, function, &arguments);
+        let call_stack = self.source_function.dfg.get_call_stack(call_id);
+        let new_results = self.context.inline_function(ssa, function, &arguments, call_stack);
         let new_results = InsertInstructionResult::Results(&new_results);
         Self::insert_new_instruction_results(&mut self.values, old_results, new_results);
     }
@@ -371,6 +386,9 @@ impl<'function> PerFunctionContext<'function> {
             .requires_ctrl_typevars()
             .then(|| vecmap(&results, |result| self.source_function.dfg.type_of_value(*result)));
 
+        let mut call_stack = self.context.call_stack.clone();
+        call_stack.extend(self.source_function.dfg.get_call_stack(id));
+        self.context.builder.set_call_stack(call_stack);
         let new_results = self.context.builder.insert_instruction(instruction, ctrl_typevars);
         Self::insert_new_instruction_results(&mut self.values, &results, new_results);
     }
@@ -461,6 +479,8 @@ mod test {
         },
         ssa_builder::FunctionBuilder,
     };
+    use fm::FileId;
+    use noirc_errors::{Location, Span};
 
     #[test]
     fn basic_inlining() {
@@ -638,4 +658,45 @@ mod test {
             other => unreachable!("Unexpected terminator {other:?}"),
         }
     }
+
+    #[test]
+    fn inlining_prepends_call_locations() {
+        // fn foo {
+        //   b0(v0: Field):
+        //     v1 = call bar(v0)
+        //     return v1
+        // }
+        // fn bar {
+        //   b0(v0: Field):
+        //     v1 = mul v0, v0
+        //     return v1
+        // }
+        let foo_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("foo".into(), foo_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+
+        let bar_id = Id::test_new(1);
+        let bar = builder.import_function(bar_id);
+        let call_location = Location::new(Span::inclusive(30, 40), FileId::default());
+        builder.set_location(call_location);
+        let results = builder.insert_call(bar, vec![v0], vec![Type::field()]).to_vec();
+        builder.terminate_with_return(results);
+
+        builder.new_function("bar".into(), bar_id);
+        let v0 = builder.add_parameter(Type::field());
+        let location = Location::new(Span::inclusive(10, 20), FileId::default());
+        builder.set_location(location);
+        let v1 = builder.insert_binary(v0, BinaryOp::Mul, v0);
+        builder.terminate_with_return(vec![v1]);
+
+        let inlined = builder.finish().inline_functions();
+        let main = inlined.main();
+        let call_stacks: Vec<_> = main
+            .reachable_blocks()
+            .into_iter()
+            .flat_map(|block| main.dfg[block].instructions().to_vec())
+            .map(|instruction| main.dfg.get_call_stack(instruction))
+            .collect();
+        assert_eq!(call_stacks, vec![vec![call_location, location]]);
+    }
 }
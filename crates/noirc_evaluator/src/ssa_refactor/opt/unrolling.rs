@@ -448,10 +448,12 @@ impl<'f> LoopIteration<'f> {
             .requires_ctrl_typevars()
             .then(|| vecmap(&results, |result| self.function.dfg.type_of_value(*result)));
 
+        let call_stack = self.function.dfg.get_call_stack(id);
         let new_results = self.function.dfg.insert_instruction_and_results(
             instruction,
             self.insert_block,
             ctrl_typevars,
+            call_stack,
         );
 
         Self::insert_new_instruction_results(&mut self.values, &results, new_results);
@@ -565,7 +567,7 @@ mod tests {
         builder.switch_to_block(b5);
         let v4 = builder.insert_binary(v0, BinaryOp::Add, v2);
         let v5 = builder.insert_binary(ten, BinaryOp::Lt, v4);
-        builder.insert_constrain(v5);
+        builder.insert_constrain(v5, None);
         let v6 = builder.insert_binary(v2, BinaryOp::Add, one);
         builder.terminate_with_jmp(b4, vec![v6]);
 
@@ -1,6 +1,7 @@
 use std::rc::Rc;
 
 use acvm::FieldElement;
+use noirc_errors::Location;
 
 use crate::ssa_refactor::ir::{
     basic_block::BasicBlockId,
@@ -13,7 +14,7 @@ use crate::ssa_refactor::ir::{
 use super::{
     ir::{
         basic_block::BasicBlock,
-        dfg::InsertInstructionResult,
+        dfg::{CallStack, InsertInstructionResult},
         function::RuntimeType,
         instruction::{InstructionId, Intrinsic},
         types::CompositeType,
@@ -32,6 +33,9 @@ pub(crate) struct FunctionBuilder {
     pub(super) current_function: Function,
     current_block: BasicBlockId,
     finished_functions: Vec<Function>,
+
+    /// The call stack recorded for each instruction inserted from now on.
+    call_stack: CallStack,
 }
 
 impl FunctionBuilder {
@@ -48,7 +52,12 @@ impl FunctionBuilder {
         new_function.set_runtime(runtime);
         let current_block = new_function.entry_block();
 
-        Self { current_function: new_function, current_block, finished_functions: Vec::new() }
+        Self {
+            current_function: new_function,
+            current_block,
+            finished_functions: Vec::new(),
+            call_stack: CallStack::new(),
+        }
     }
 
     /// Finish the current function and create a new function.
@@ -65,6 +74,7 @@ impl FunctionBuilder {
         let mut new_function = Function::new(name, function_id);
         new_function.set_runtime(runtime_type);
         self.current_block = new_function.entry_block();
+        self.call_stack = CallStack::new();
 
         let old_function = std::mem::replace(&mut self.current_function, new_function);
         self.finished_functions.push(old_function);
@@ -148,9 +158,20 @@ impl FunctionBuilder {
             instruction,
             self.current_block,
             ctrl_typevars,
+            self.call_stack.clone(),
         )
     }
 
+    /// Sets the source location which instructions inserted from now on originate from.
+    pub(crate) fn set_location(&mut self, location: Location) {
+        self.call_stack = vec![location];
+    }
+
+    /// Sets the call stack which instructions inserted from now on originate from.
+    pub(crate) fn set_call_stack(&mut self, call_stack: CallStack) {
+        self.call_stack = call_stack;
+    }
+
     /// Switch to inserting instructions in the given block.
     /// Expects the given block to be within the same function. If you want to insert
     /// instructions into a new function, call new_function instead.
@@ -225,8 +246,8 @@ impl FunctionBuilder {
     }
 
     /// Insert a constrain instruction at the end of the current block.
-    pub(crate) fn insert_constrain(&mut self, boolean: ValueId) {
-        self.insert_instruction(Instruction::Constrain(boolean), None);
+    pub(crate) fn insert_constrain(&mut self, boolean: ValueId, message: Option<String>) {
+        self.insert_instruction(Instruction::Constrain(boolean, message), None);
     }
 
     /// Insert a call instruction a the end of the current block and return
@@ -414,8 +414,9 @@ impl<'a> FunctionContext<'a> {
                 let variable = self.ident_lvalue(ident);
                 (variable.clone(), LValue::Ident(variable))
             }
-            ast::LValue::Index { array, index, element_type, location: _ } => {
+            ast::LValue::Index { array, index, element_type, location } => {
                 let (old_array, index, index_lvalue) = self.index_lvalue(array, index);
+                self.builder.set_location(*location);
                 let element = self.codegen_array_index(old_array, index, element_type);
                 (element, index_lvalue)
             }
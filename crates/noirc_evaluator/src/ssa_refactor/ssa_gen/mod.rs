@@ -78,8 +78,8 @@ impl<'a> FunctionContext<'a> {
             }
             Expression::Call(call) => self.codegen_call(call),
             Expression::Let(let_expr) => self.codegen_let(let_expr),
-            Expression::Constrain(constrain, location) => {
-                self.codegen_constrain(constrain, *location)
+            Expression::Constrain(constrain, location, message) => {
+                self.codegen_constrain(constrain, *location, message.clone())
             }
             Expression::Assign(assign) => self.codegen_assign(assign),
             Expression::Semi(semi) => self.codegen_semi(semi),
@@ -173,12 +173,14 @@ impl<'a> FunctionContext<'a> {
     fn codegen_binary(&mut self, binary: &ast::Binary) -> Values {
         let lhs = self.codegen_non_tuple_expression(&binary.lhs);
         let rhs = self.codegen_non_tuple_expression(&binary.rhs);
+        self.builder.set_location(binary.location);
         self.insert_binary(lhs, binary.operator, rhs)
     }
 
     fn codegen_index(&mut self, index: &ast::Index) -> Values {
         let array = self.codegen_non_tuple_expression(&index.collection);
         let index_value = self.codegen_non_tuple_expression(&index.index);
+        self.builder.set_location(index.location);
         self.codegen_array_index(array, index_value, &index.element_type)
     }
 
@@ -345,6 +347,7 @@ impl<'a> FunctionContext<'a> {
             .flat_map(|argument| self.codegen_expression(argument).into_value_list(self))
             .collect();
 
+        self.builder.set_location(call.location);
         self.insert_call(function, arguments, &call.return_type)
     }
 
@@ -366,9 +369,15 @@ impl<'a> FunctionContext<'a> {
         Self::unit_value()
     }
 
-    fn codegen_constrain(&mut self, expr: &Expression, _location: Location) -> Values {
+    fn codegen_constrain(
+        &mut self,
+        expr: &Expression,
+        location: Location,
+        message: Option<String>,
+    ) -> Values {
         let boolean = self.codegen_non_tuple_expression(expr);
-        self.builder.insert_constrain(boolean);
+        self.builder.set_location(location);
+        self.builder.insert_constrain(boolean, message);
         Self::unit_value()
     }
 
@@ -332,6 +332,9 @@ pub struct FunctionDefinition {
     pub return_type: UnresolvedType,
     pub return_visibility: noirc_abi::AbiVisibility,
     pub return_distinctness: noirc_abi::AbiDistinctness,
+
+    /// The text of each line of the `///` doc comments before this function
+    pub doc_comments: Vec<String>,
 }
 
 /// Describes the types of smart contract functions that are allowed.
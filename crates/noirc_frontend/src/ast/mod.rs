@@ -216,3 +216,18 @@ impl UnresolvedTypeExpression {
         )
     }
 }
+
+/// An item of the Ast along with the `///` doc comments written before it, for items which
+/// don't have a node of their own to hold them, such as globals and struct fields.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Documented<T> {
+    pub item: T,
+    /// The text of each line of the doc comments, without the leading `///`.
+    pub doc_comments: Vec<String>,
+}
+
+impl<T> Documented<T> {
+    pub fn new(item: T, doc_comments: Vec<String>) -> Self {
+        Documented { item, doc_comments }
+    }
+}
@@ -405,8 +405,9 @@ pub enum LValue {
     Index { array: Box<LValue>, index: Expression },
 }
 
+/// An `assert` statement, along with the message to report if it fails.
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct ConstrainStatement(pub Expression);
+pub struct ConstrainStatement(pub Expression, pub Option<String>);
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Pattern {
@@ -471,7 +472,10 @@ impl Display for LetStatement {
 
 impl Display for ConstrainStatement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "constrain {}", self.0)
+        match &self.1 {
+            Some(message) => write!(f, "assert({}, {message:?})", self.0),
+            None => write!(f, "assert({})", self.0),
+        }
     }
 }
 
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use crate::{Ident, NoirFunction, UnresolvedGenerics, UnresolvedType};
+use crate::{Documented, Ident, NoirFunction, UnresolvedGenerics, UnresolvedType};
 use iter_extended::vecmap;
 use noirc_errors::Span;
 
@@ -9,18 +9,21 @@ use noirc_errors::Span;
 pub struct NoirStruct {
     pub name: Ident,
     pub generics: UnresolvedGenerics,
-    pub fields: Vec<(Ident, UnresolvedType)>,
+    pub fields: Vec<Documented<(Ident, UnresolvedType)>>,
     pub span: Span,
+
+    /// The text of each line of the `///` doc comments before this struct
+    pub doc_comments: Vec<String>,
 }
 
 impl NoirStruct {
     pub fn new(
         name: Ident,
         generics: Vec<Ident>,
-        fields: Vec<(Ident, UnresolvedType)>,
+        fields: Vec<Documented<(Ident, UnresolvedType)>>,
         span: Span,
     ) -> NoirStruct {
-        NoirStruct { name, generics, fields, span }
+        NoirStruct { name, generics, fields, span, doc_comments: Vec::new() }
     }
 }
 
@@ -40,7 +43,7 @@ impl Display for NoirStruct {
 
         writeln!(f, "struct {}{} {{", self.name, generics)?;
 
-        for (name, typ) in self.fields.iter() {
+        for Documented { item: (name, typ), .. } in self.fields.iter() {
             writeln!(f, "    {name}: {typ},")?;
         }
 
@@ -0,0 +1,202 @@
+//! The formatter lays out a Noir source file in a consistent style by printing it back out
+//! from its Ast.
+//!
+//! The Ast doesn't keep the comments in a file, so the file is lexed a second time with
+//! comments kept, and each comment is printed before the item or statement following it, or
+//! after the one ending on the same line as it.
+mod printer;
+mod source;
+
+use noirc_errors::CustomDiagnostic;
+use serde::Deserialize;
+
+use crate::parse_program;
+
+use self::{printer::Printer, source::Source};
+
+/// The options for formatting a file, which can be set in the `[fmt]` section of a package's
+/// Nargo.toml.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// The width lists of arguments, parameters and elements are kept within, if they fit on a
+    /// line on their own.
+    pub max_width: usize,
+
+    /// The number of spaces each level of indentation is made up of.
+    pub tab_spaces: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config { max_width: 100, tab_spaces: 4 }
+    }
+}
+
+/// Formats the given source file, returning the formatted source.
+///
+/// Files which don't parse can't be formatted, in which case the errors found while parsing
+/// are returned instead.
+pub fn format(source: &str, config: &Config) -> Result<String, Vec<CustomDiagnostic>> {
+    let (module, diagnostics) = parse_program(source);
+    if diagnostics.iter().any(CustomDiagnostic::is_error) {
+        return Err(diagnostics);
+    }
+
+    let source = Source::new(source);
+    let mut printer = Printer::new(&source, config);
+    printer.print_module(&module);
+    Ok(printer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format, Config};
+
+    fn assert_formats_to(source: &str, expected: &str) {
+        assert_formats_with_config(source, expected, &Config::default());
+    }
+
+    fn assert_formats_with_config(source: &str, expected: &str, config: &Config) {
+        let formatted = format(source, config).expect("source should parse");
+        assert_eq!(formatted, expected);
+
+        // Formatting is idempotent
+        assert_eq!(format(&formatted, config).unwrap(), formatted);
+    }
+
+    #[test]
+    fn formats_functions() {
+        assert_formats_to(
+            "use dep::std;
+fn main(x : Field,y:pub Field)->pub Field{
+let z=x+y*2;
+    assert(z!=0);
+  if z == 1 {x} else if z==2 {y} else {z}
+}
+#[test]
+fn test_main() { let mut a = [1,2,3]; a[0] += 1; assert_eq(main(a[0], 1), 3,\"not three\"); }
+",
+            "use dep::std;
+
+fn main(x: Field, y: pub Field) -> pub Field {
+    let z = x + y * 2;
+    assert(z != 0);
+    if z == 1 {
+        x
+    } else if z == 2 {
+        y
+    } else {
+        z
+    }
+}
+
+#[test]
+fn test_main() {
+    let mut a = [1, 2, 3];
+    a[0] += 1;
+    assert_eq(main(a[0], 1), 3, \"not three\");
+}
+",
+        );
+    }
+
+    #[test]
+    fn preserves_comments() {
+        assert_formats_to(
+            "// The entry point
+fn main(x: Field) {
+    // Check x
+    assert(x == 1);   // must be one
+
+    // Loop over some values
+    for i in [1, 2] {
+        let _y = i; // unused
+    }
+    // Nothing else to do
+}
+// The end
+",
+            "// The entry point
+fn main(x: Field) {
+    // Check x
+    assert(x == 1); // must be one
+
+    // Loop over some values
+    for i in [1, 2] {
+        let _y = i; // unused
+    }
+    // Nothing else to do
+}
+// The end
+",
+        );
+    }
+
+    #[test]
+    fn preserves_doc_comments() {
+        assert_formats_to(
+            "//! The module
+/// A point
+struct Point {
+    /// The x coordinate
+    x: Field,
+}
+/// The entry point
+fn main(p: Point) -> Field { p.x }
+",
+            "//! The module
+/// A point
+struct Point {
+    /// The x coordinate
+    x: Field,
+}
+
+/// The entry point
+fn main(p: Point) -> Field {
+    p.x
+}
+",
+        );
+    }
+
+    #[test]
+    fn keeps_parentheses_needed_for_precedence() {
+        assert_formats_to(
+            "fn main(x: u8) -> u8 { ((x + 1) * (2 - x)) - -(x as u8) + (x - (1 - 2)) }",
+            "fn main(x: u8) -> u8 {
+    (x + 1) * (2 - x) - -(x as u8) + (x - (1 - 2))
+}
+",
+        );
+    }
+
+    #[test]
+    fn breaks_long_lists() {
+        assert_formats_with_config(
+            "struct Foo { a: Field, b: [u8; 32] }
+fn main() { let foo = Foo { a: 1, b: [0; 32] }; call_something(foo.a, foo.b, another_argument, yet_another_argument); }",
+            "struct Foo {
+    a: Field,
+    b: [u8; 32],
+}
+
+fn main() {
+    let foo = Foo { a: 1, b: [0; 32] };
+    call_something(
+        foo.a,
+        foo.b,
+        another_argument,
+        yet_another_argument,
+    );
+}
+",
+            &Config { max_width: 40, ..Config::default() },
+        );
+    }
+
+    #[test]
+    fn rejects_files_which_dont_parse() {
+        assert!(format("fn main( {", &Config::default()).is_err());
+    }
+}
@@ -0,0 +1,1060 @@
+use acvm::FieldElement;
+use iter_extended::vecmap;
+use noirc_abi::{AbiDistinctness, AbiVisibility};
+use noirc_errors::{Position, Span};
+
+use crate::{
+    parser::SubModule, token::Keyword, ArrayLiteral, AssignStatement, BinaryOpKind,
+    BinaryTypeOperator, BlockExpression, ConstrainStatement, Expression, ExpressionKind,
+    FunctionDefinition, Ident, IfExpression, ImportStatement, LValue, Lambda, LetStatement,
+    Literal, NoirFunction, NoirImpl, NoirStruct, ParsedModule, Path, PathKind, Pattern, Signedness,
+    Statement, UnresolvedType, UnresolvedTypeExpression,
+};
+
+use super::{source::Source, Config};
+
+/// How tightly prefix, postfix and atomic expressions bind to their operands, see `binding_power`.
+const PREFIX: u8 = 9;
+const POSTFIX: u8 = 10;
+const ATOM: u8 = 11;
+
+/// An item in a module. The Ast keeps a separate list for each kind of item, so these are
+/// gathered together to be printed in the order they were written.
+enum Item<'m> {
+    Imports(Vec<&'m ImportStatement>),
+    ModuleDecl(&'m Ident),
+    Global(&'m LetStatement),
+    Struct(&'m NoirStruct),
+    Function(&'m NoirFunction),
+    Impl(&'m NoirImpl),
+    SubModule(&'m SubModule),
+}
+
+pub(super) struct Printer<'a> {
+    source: &'a Source,
+    config: &'a Config,
+    output: String,
+    indent: usize,
+    /// The index of the next comment in the source to be printed.
+    next_comment: usize,
+    /// Whether lists are always printed on a single line. This is used to measure the width
+    /// of a list, in which case comments aren't printed either.
+    single_line: bool,
+}
+
+impl<'a> Printer<'a> {
+    pub(super) fn new(source: &'a Source, config: &'a Config) -> Self {
+        Printer {
+            source,
+            config,
+            output: String::new(),
+            indent: 0,
+            next_comment: 0,
+            single_line: false,
+        }
+    }
+
+    pub(super) fn finish(mut self) -> String {
+        self.leading_comments(Some(Position::MAX));
+        if !self.at_line_start() {
+            self.newline();
+        }
+        self.output
+    }
+
+    pub(super) fn print_module(&mut self, module: &ParsedModule) {
+        let items = self.module_items(module);
+
+        let mut previous = None;
+        for (start, item) in &items {
+            // Imports, module declarations and globals may be grouped together, everything else
+            // is separated by a blank line.
+            let grouped = matches!(
+                (previous, item),
+                (Some(Item::Imports(_)), Item::Imports(_))
+                    | (Some(Item::ModuleDecl(_)), Item::ModuleDecl(_))
+                    | (Some(Item::Global(_)), Item::Global(_))
+            );
+            if previous.is_some() && !grouped {
+                self.blank_line();
+            }
+            self.leading_comments(Some(*start));
+            if self.source.follows_blank_line(*start) {
+                self.blank_line();
+            }
+
+            match item {
+                Item::Imports(imports) => self.print_imports(imports),
+                Item::ModuleDecl(name) => {
+                    self.write(&format!("mod {name};"));
+                    self.end_line(name.span().end());
+                }
+                Item::Global(global) => self.print_global(global),
+                Item::Struct(noir_struct) => self.print_struct(noir_struct),
+                Item::Function(function) => self.print_function(&function.def),
+                Item::Impl(noir_impl) => self.print_impl(noir_impl),
+                Item::SubModule(submodule) => self.print_submodule(submodule),
+            }
+            previous = Some(item);
+        }
+    }
+
+    /// Returns the items of `module` along with the position each starts at, in the order they
+    /// were written.
+    fn module_items<'m>(&self, module: &'m ParsedModule) -> Vec<(Position, Item<'m>)> {
+        let source = self.source;
+        let mut items = Vec::new();
+
+        for imports in group_imports(&module.imports) {
+            let start = imports[0].path.segments[0].span().start();
+            items.push((source.item_start(start, Keyword::Use), Item::Imports(imports)));
+        }
+        for decl in &module.module_decls {
+            let start = source.item_start(decl.item.span().start(), Keyword::Mod);
+            items.push((start, Item::ModuleDecl(&decl.item)));
+        }
+        for global in &module.globals {
+            let global = &global.item;
+            let start = source.item_start(pattern_span(&global.pattern).start(), Keyword::Global);
+            items.push((start, Item::Global(global)));
+        }
+        for noir_struct in &module.types {
+            items.push((noir_struct.span.start(), Item::Struct(noir_struct)));
+        }
+        for function in &module.functions {
+            let start = source.item_start(function.def.name.span().start(), Keyword::Fn);
+            items.push((start, Item::Function(function)));
+        }
+        for noir_impl in &module.impls {
+            let start = source.item_start(noir_impl.type_span.start(), Keyword::Impl);
+            items.push((start, Item::Impl(noir_impl)));
+        }
+        for submodule in &module.submodules {
+            let keyword = if submodule.is_contract { Keyword::Contract } else { Keyword::Mod };
+            let start = source.item_start(submodule.name.span().start(), keyword);
+            items.push((start, Item::SubModule(submodule)));
+        }
+
+        items.sort_by_key(|(start, _)| *start);
+        items
+    }
+
+    fn print_imports(&mut self, imports: &[&ImportStatement]) {
+        let last_name = |import: &ImportStatement| import.path.segments.last().unwrap().span();
+        let end = last_name(imports[imports.len() - 1]).end();
+
+        self.write("use ");
+        match imports {
+            [import] => self.write(&path_to_string(&import.path)),
+            _ => {
+                let mut prefix = imports[0].path.clone();
+                prefix.pop();
+                self.write(&format!("{}::", path_to_string(&prefix)));
+
+                self.list(
+                    ("{", "}"),
+                    false,
+                    imports,
+                    end,
+                    |import| last_name(*import),
+                    |printer, import| {
+                        printer.write(&import.path.segments.last().unwrap().to_string());
+                        if let Some(alias) = &import.alias {
+                            printer.write(&format!(" as {alias}"));
+                        }
+                    },
+                );
+            }
+        }
+        if let [import] = imports {
+            if let Some(alias) = &import.alias {
+                self.write(&format!(" as {alias}"));
+            }
+        }
+        self.write(";");
+        self.end_line(end);
+    }
+
+    fn print_global(&mut self, global: &LetStatement) {
+        self.write(&format!("global {}", pattern_to_string(&global.pattern)));
+
+        // The parser makes the types of globals `comptime`, which isn't written in the source
+        let typ = match &global.r#type {
+            UnresolvedType::FieldElement(_) => Some("Field".to_owned()),
+            UnresolvedType::Bool(_) => Some("bool".to_owned()),
+            UnresolvedType::Integer(_, sign, bits) => Some(integer_type(*sign, *bits)),
+            UnresolvedType::Unspecified => None,
+            other => Some(type_to_string(other)),
+        };
+        if let Some(typ) = typ {
+            self.write(&format!(": {typ}"));
+        }
+
+        self.write(" = ");
+        self.print_expression(&global.expression);
+        self.write(";");
+        self.end_line(self.source.statement_end(global.expression.span.end()));
+    }
+
+    fn print_struct(&mut self, noir_struct: &NoirStruct) {
+        let generics = generics_to_string(&noir_struct.generics);
+        self.write(&format!("struct {}{generics} ", noir_struct.name));
+
+        let end = noir_struct.span.end();
+        if noir_struct.fields.is_empty() && !self.has_comment_before(Some(end)) {
+            self.write("{}");
+        } else {
+            let fields = &noir_struct.fields;
+            self.vertical_list(
+                ("{", "}"),
+                fields,
+                end,
+                |field| field.item.0.span(),
+                |printer, field| {
+                    let (name, typ) = &field.item;
+                    printer.write(&format!("{name}: {}", type_to_string(typ)));
+                },
+            );
+        }
+        self.end_line(end);
+    }
+
+    fn print_function(&mut self, function: &FunctionDefinition) {
+        if let Some(attribute) = &function.attribute {
+            self.write(&attribute.to_string());
+            self.newline();
+        }
+        if function.is_unconstrained {
+            self.write("unconstrained ");
+        }
+        if function.is_open {
+            self.write("open ");
+        }
+        let generics = generics_to_string(&function.generics);
+        self.write(&format!("fn {}{generics}", function.name));
+
+        let name_end = function.name.span().end();
+        let body = self.source.block_after(name_end);
+        let parameters_end = body.map_or(name_end, |(open, _)| open);
+        self.list(
+            ("(", ")"),
+            false,
+            &function.parameters,
+            parameters_end,
+            |(pattern, _, _)| pattern_span(pattern),
+            |printer, (pattern, typ, visibility)| {
+                printer.write(&parameter_to_string(pattern, typ, *visibility));
+            },
+        );
+
+        let has_return_type = function.return_type != UnresolvedType::Unit
+            || function.return_visibility == AbiVisibility::Public
+            || function.return_distinctness == AbiDistinctness::Distinct;
+        if has_return_type {
+            self.write(" -> ");
+            if function.return_distinctness == AbiDistinctness::Distinct {
+                self.write("distinct ");
+            }
+            if function.return_visibility == AbiVisibility::Public {
+                self.write("pub ");
+            }
+            self.write(&type_to_string(&function.return_type));
+        }
+
+        self.write(" ");
+        let end = body.map(|(_, close)| close);
+        self.print_block(&function.body, end);
+        self.end_line(end.unwrap_or(name_end));
+    }
+
+    fn print_impl(&mut self, noir_impl: &NoirImpl) {
+        let generics = generics_to_string(&noir_impl.generics);
+        let object_type = type_to_string(&noir_impl.object_type);
+        self.write(&format!("impl{generics} {object_type} "));
+
+        let end = self.source.block_after(noir_impl.type_span.end()).map(|(_, close)| close);
+        if noir_impl.methods.is_empty() && !self.has_comment_before(end) {
+            self.write("{}");
+        } else {
+            self.write("{");
+            self.newline();
+            self.indent += 1;
+            for (index, method) in noir_impl.methods.iter().enumerate() {
+                if index > 0 {
+                    self.blank_line();
+                }
+                let start = self.source.item_start(method.def.name.span().start(), Keyword::Fn);
+                self.leading_comments(Some(start));
+                self.print_function(&method.def);
+            }
+            self.leading_comments(end);
+            self.indent -= 1;
+            self.write("}");
+        }
+        self.end_line(end.unwrap_or_else(|| noir_impl.type_span.end()));
+    }
+
+    fn print_submodule(&mut self, submodule: &SubModule) {
+        let keyword = if submodule.is_contract { "contract" } else { "mod" };
+        self.write(&format!("{keyword} {} ", submodule.name));
+
+        let name_end = submodule.name.span().end();
+        let end = self.source.block_after(name_end).map(|(_, close)| close);
+        let contents = &submodule.contents;
+        if self.module_items(contents).is_empty() && !self.has_comment_before(end) {
+            self.write("{}");
+        } else {
+            self.write("{");
+            self.newline();
+            self.indent += 1;
+            self.print_module(contents);
+            self.leading_comments(end);
+            self.indent -= 1;
+            self.write("}");
+        }
+        self.end_line(end.unwrap_or(name_end));
+    }
+
+    /// Prints a block, where `end` is the position of its closing brace in the source if known.
+    fn print_block(&mut self, block: &BlockExpression, end: Option<Position>) {
+        if block.is_empty() && !self.has_comment_before(end) {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.newline();
+        self.indent += 1;
+        for (index, statement) in block.0.iter().enumerate() {
+            let span = statement_span(statement);
+            if let Some(span) = span {
+                self.leading_comments(Some(span.start()));
+                if self.source.follows_blank_line(span.start()) {
+                    self.blank_line();
+                }
+            }
+
+            self.print_statement(statement);
+
+            // Comments after a statement belong to it, unless another statement follows on the
+            // same line
+            let next = block.0.get(index + 1).and_then(statement_span);
+            if let Some(span) = span {
+                let end = self.source.statement_end(span.end());
+                if next.map_or(true, |next| !self.source.same_line(end, next.start())) {
+                    self.trailing_comment(end);
+                }
+            }
+            self.newline();
+        }
+        self.leading_comments(end);
+        self.indent -= 1;
+        self.write("}");
+    }
+
+    fn print_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Let(let_statement) => {
+                self.write(&format!("let {}", pattern_to_string(&let_statement.pattern)));
+                if let_statement.r#type != UnresolvedType::Unspecified {
+                    self.write(&format!(": {}", type_to_string(&let_statement.r#type)));
+                }
+                self.write(" = ");
+                self.print_expression(&let_statement.expression);
+                self.write(";");
+            }
+            Statement::Constrain(constrain) => {
+                self.print_constrain(constrain);
+                self.write(";");
+            }
+            Statement::Expression(expression) => self.print_expression(expression),
+            Statement::Assign(assign) => {
+                self.print_assign(assign);
+                self.write(";");
+            }
+            Statement::Semi(expression) => {
+                self.print_expression(expression);
+                self.write(";");
+            }
+            Statement::Error => (),
+        }
+    }
+
+    fn print_constrain(&mut self, ConstrainStatement(condition, message): &ConstrainStatement) {
+        // `assert_eq(a, b)` is parsed as `assert(a == b)`, where the `==` spans the whole
+        // statement rather than sitting between its operands
+        let (name, mut arguments) = match &condition.kind {
+            ExpressionKind::Infix(infix)
+                if infix.operator.contents == BinaryOpKind::Equal
+                    && infix.operator.span().start() < infix.lhs.span.start() =>
+            {
+                ("assert_eq", vec![infix.lhs.clone(), infix.rhs.clone()])
+            }
+            _ => ("assert", vec![condition.clone()]),
+        };
+        if let Some(message) = message {
+            let message = ExpressionKind::string(message.clone());
+            arguments.push(Expression::new(message, condition.span));
+        }
+
+        self.write(name);
+        let end = self.source.statement_end(condition.span.end());
+        self.list(
+            ("(", ")"),
+            false,
+            &arguments,
+            end,
+            |argument| argument.span,
+            Self::print_expression,
+        );
+    }
+
+    fn print_assign(&mut self, AssignStatement { lvalue, expression }: &AssignStatement) {
+        self.print_lvalue(lvalue);
+
+        // `a += b` is parsed as `a = a + b`, where the `a + b` and the `a` within it both span the
+        // whole statement
+        match &expression.kind {
+            ExpressionKind::Infix(infix) if infix.lhs.span == expression.span => {
+                self.write(&format!(" {}= ", infix.operator.contents));
+                self.print_expression(&infix.rhs);
+            }
+            _ => {
+                self.write(" = ");
+                self.print_expression(expression);
+            }
+        }
+    }
+
+    fn print_lvalue(&mut self, lvalue: &LValue) {
+        match lvalue {
+            LValue::Ident(name) => self.write(&name.to_string()),
+            LValue::MemberAccess { object, field_name } => {
+                self.print_lvalue(object);
+                self.write(&format!(".{field_name}"));
+            }
+            LValue::Index { array, index } => {
+                self.print_lvalue(array);
+                self.write("[");
+                self.print_expression(index);
+                self.write("]");
+            }
+        }
+    }
+
+    fn print_expression(&mut self, expression: &Expression) {
+        match &expression.kind {
+            ExpressionKind::Literal(literal) => self.print_literal(literal, expression.span),
+            ExpressionKind::Block(block) => match desugared_for_loop(block) {
+                Some((identifier, array, body)) => {
+                    self.write(&format!("for {identifier} in "));
+                    self.print_expression(array);
+                    self.write(" ");
+                    self.print_expression(body);
+                }
+                None => self.print_block(block, Some(expression.span.end())),
+            },
+            ExpressionKind::Prefix(prefix) => {
+                self.write(&prefix.operator.to_string());
+                // `-a as u8` is parsed as `-(a as u8)`, which the parentheses make clear
+                match &prefix.rhs.kind {
+                    ExpressionKind::Cast(_) => self.print_operand(&prefix.rhs, ATOM),
+                    _ => self.print_operand(&prefix.rhs, PREFIX),
+                }
+            }
+            ExpressionKind::Index(index) => {
+                self.print_operand(&index.collection, POSTFIX);
+                self.write("[");
+                self.print_expression(&index.index);
+                self.write("]");
+            }
+            ExpressionKind::Call(call) => {
+                self.print_operand(&call.func, POSTFIX);
+                self.print_arguments(&call.arguments, expression.span.end());
+            }
+            ExpressionKind::MethodCall(call) => {
+                self.print_operand(&call.object, POSTFIX);
+                self.write(&format!(".{}", call.method_name));
+                self.print_arguments(&call.arguments, expression.span.end());
+            }
+            ExpressionKind::Constructor(constructor) => {
+                self.write(&format!("{} ", path_to_string(&constructor.type_name)));
+                self.list(
+                    ("{", "}"),
+                    true,
+                    &constructor.fields,
+                    expression.span.end(),
+                    |(name, _)| name.span(),
+                    |printer, (name, value)| match &value.kind {
+                        ExpressionKind::Variable(path) if path.as_ident() == Some(name) => {
+                            printer.write(&name.to_string());
+                        }
+                        _ => {
+                            printer.write(&format!("{name}: "));
+                            printer.print_expression(value);
+                        }
+                    },
+                );
+            }
+            ExpressionKind::MemberAccess(access) => {
+                self.print_operand(&access.lhs, POSTFIX);
+                self.write(&format!(".{}", access.rhs));
+            }
+            ExpressionKind::Cast(cast) => {
+                self.print_operand(&cast.lhs, POSTFIX);
+                self.write(&format!(" as {}", type_to_string(&cast.r#type)));
+            }
+            ExpressionKind::Infix(infix) => {
+                // Operators are left associative, so only a right operand of the same
+                // precedence needs parentheses
+                let precedence = operator_binding_power(infix.operator.contents);
+                self.print_operand(&infix.lhs, precedence);
+                self.write(&format!(" {} ", infix.operator.contents));
+                self.print_operand(&infix.rhs, precedence + 1);
+            }
+            ExpressionKind::For(for_loop) => {
+                self.write(&format!("for {} in ", for_loop.identifier));
+                self.print_expression(&for_loop.start_range);
+                self.write("..");
+                self.print_expression(&for_loop.end_range);
+                self.write(" ");
+                self.print_expression(&for_loop.block);
+            }
+            ExpressionKind::If(if_expression) => self.print_if(if_expression),
+            ExpressionKind::Variable(path) => self.write(&path_to_string(path)),
+            ExpressionKind::Tuple(elements) => {
+                let end = expression.span.end();
+                self.list(
+                    ("(", ")"),
+                    false,
+                    elements,
+                    end,
+                    |element| element.span,
+                    Self::print_expression,
+                );
+            }
+            ExpressionKind::Lambda(lambda) => self.print_lambda(lambda),
+            ExpressionKind::Error => (),
+        }
+    }
+
+    /// Prints `expression` as the operand of another expression, wrapping it in parentheses if
+    /// it doesn't bind at least as tightly as `binding_power`.
+    fn print_operand(&mut self, expression: &Expression, min_binding_power: u8) {
+        if binding_power(expression) < min_binding_power {
+            self.write("(");
+            self.print_expression(expression);
+            self.write(")");
+        } else {
+            self.print_expression(expression);
+        }
+    }
+
+    fn print_arguments(&mut self, arguments: &[Expression], end: Position) {
+        self.list(
+            ("(", ")"),
+            false,
+            arguments,
+            end,
+            |argument| argument.span,
+            Self::print_expression,
+        );
+    }
+
+    fn print_literal(&mut self, literal: &Literal, span: Span) {
+        match literal {
+            Literal::Array(ArrayLiteral::Standard(elements)) => {
+                self.list(
+                    ("[", "]"),
+                    false,
+                    elements,
+                    span.end(),
+                    |element| element.span,
+                    Self::print_expression,
+                );
+            }
+            Literal::Array(ArrayLiteral::Repeated { repeated_element, length }) => {
+                self.write("[");
+                self.print_expression(repeated_element);
+                self.write("; ");
+                self.print_expression(length);
+                self.write("]");
+            }
+            Literal::Bool(boolean) => self.write(&boolean.to_string()),
+            Literal::Integer(integer) => {
+                // Integers are written as they were in the source so that hex stays as hex
+                let text = self.source.text(span);
+                if FieldElement::try_from_str(&text) == Some(*integer) {
+                    self.write(&text);
+                } else if let Some(integer) = integer.try_into_u128() {
+                    self.write(&integer.to_string());
+                } else {
+                    self.write(&format!("0x{}", integer.to_hex().trim_start_matches('0')));
+                }
+            }
+            Literal::Str(string) => self.write(&format!("\"{string}\"")),
+        }
+    }
+
+    fn print_if(&mut self, if_expression: &IfExpression) {
+        self.write("if ");
+        self.print_expression(&if_expression.condition);
+        self.write(" ");
+        self.print_expression(&if_expression.consequence);
+
+        if let Some(alternative) = &if_expression.alternative {
+            self.write(" else ");
+            // `else if` is parsed as an `else` block holding just the inner `if`, which the
+            // block shares its span with
+            match &alternative.kind {
+                ExpressionKind::Block(BlockExpression(statements)) => match statements.as_slice() {
+                    [Statement::Expression(inner)]
+                        if inner.span == alternative.span
+                            && matches!(inner.kind, ExpressionKind::If(_)) =>
+                    {
+                        self.print_expression(inner);
+                    }
+                    _ => self.print_expression(alternative),
+                },
+                _ => self.print_expression(alternative),
+            }
+        }
+    }
+
+    fn print_lambda(&mut self, lambda: &Lambda) {
+        let parameters = vecmap(&lambda.parameters, |(pattern, typ)| match typ {
+            UnresolvedType::Unspecified => pattern_to_string(pattern),
+            _ => format!("{}: {}", pattern_to_string(pattern), type_to_string(typ)),
+        });
+        self.write(&format!("|{}| ", parameters.join(", ")));
+        if lambda.return_type != UnresolvedType::Unspecified {
+            self.write(&format!("-> {} ", type_to_string(&lambda.return_type)));
+        }
+        self.print_expression(&lambda.body);
+    }
+
+    /// Prints a comma separated list of items between `open` and `close`. The list is kept on
+    /// one line if it fits within the maximum width and has no comments inside it, otherwise
+    /// each item is put on its own line.
+    ///
+    /// `end` is the position the list ends at in the source, which comments before are printed
+    /// within the list.
+    fn list<T>(
+        &mut self,
+        (open, close): (&str, &str),
+        padded: bool,
+        items: &[T],
+        end: Position,
+        span_of: impl Fn(&T) -> Span,
+        print: impl Fn(&mut Printer<'a>, &T),
+    ) {
+        if items.is_empty() {
+            self.write(&format!("{open}{close}"));
+            return;
+        }
+
+        let texts = vecmap(items, |item| self.single_line_text(|printer| print(printer, item)));
+        let text = texts.join(", ");
+        let padding = if padded { " " } else { "" };
+        let first_line = text.lines().next().unwrap_or_default();
+        let width = self.column() + open.len() + first_line.chars().count() + close.len();
+        let fits = width + 2 * padding.len() <= self.config.max_width;
+
+        // Only the last item may span multiple lines while the list stays inline, such as a
+        // lambda passed as the last argument of a call
+        let (last, others) = texts.split_last().unwrap();
+        let inline = !others.iter().any(|text| text.contains('\n'));
+
+        if self.single_line || (fits && inline && !self.has_comment_before(Some(end))) {
+            if last.contains('\n') {
+                // The items are printed again so that the lines of the last one are indented
+                self.write(&format!("{open}{padding}"));
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        self.write(", ");
+                    }
+                    print(self, item);
+                }
+                self.write(&format!("{padding}{close}"));
+            } else {
+                self.write(&format!("{open}{padding}{text}{padding}{close}"));
+            }
+        } else {
+            self.vertical_list((open, close), items, end, span_of, print);
+        }
+    }
+
+    /// Prints a comma separated list of items between `open` and `close` with each item on its
+    /// own line. Comments before `end` are printed within the list.
+    fn vertical_list<T>(
+        &mut self,
+        (open, close): (&str, &str),
+        items: &[T],
+        end: Position,
+        span_of: impl Fn(&T) -> Span,
+        print: impl Fn(&mut Printer<'a>, &T),
+    ) {
+        self.write(open);
+        self.newline();
+        self.indent += 1;
+        for (index, item) in items.iter().enumerate() {
+            let span = span_of(item);
+            self.leading_comments(Some(span.start()));
+            print(self, item);
+            self.write(",");
+
+            let next = items.get(index + 1).map(&span_of);
+            if next.map_or(true, |next| !self.source.same_line(span.end(), next.start())) {
+                self.trailing_comment(span.end());
+            }
+            self.newline();
+        }
+        self.leading_comments(Some(end));
+        self.indent -= 1;
+        self.write(close);
+    }
+
+    /// Returns what `print` prints with every list on a single line, without any comments.
+    fn single_line_text(&self, print: impl FnOnce(&mut Printer<'a>)) -> String {
+        let mut printer = Printer {
+            source: self.source,
+            config: self.config,
+            output: String::new(),
+            indent: 0,
+            next_comment: self.source.comments().len(),
+            single_line: true,
+        };
+        print(&mut printer);
+        printer.output
+    }
+
+    /// Prints the comments before `position` which haven't been printed yet, each on its own
+    /// line. A comment which followed a blank line in the source is printed after one too.
+    fn leading_comments(&mut self, position: Option<Position>) {
+        let position = match position {
+            Some(position) => position,
+            None => return,
+        };
+        let source = self.source;
+        while let Some(comment) = source.comments().get(self.next_comment) {
+            if comment.span.start() >= position {
+                break;
+            }
+            self.next_comment += 1;
+
+            if !self.at_line_start() {
+                self.newline();
+            }
+            if source.follows_blank_line(comment.span.start()) {
+                self.blank_line();
+            }
+            self.write(&format!("//{}", comment.text.trim_end()));
+            self.newline();
+        }
+    }
+
+    /// Prints the next comment at the end of the current line if it was on the same line as
+    /// `end` in the source, after the code ending there.
+    fn trailing_comment(&mut self, end: Position) {
+        let source = self.source;
+        if let Some(comment) = source.comments().get(self.next_comment) {
+            if comment.span.start() > end && source.same_line(comment.span.start(), end) {
+                self.next_comment += 1;
+                self.write(&format!(" //{}", comment.text.trim_end()));
+            }
+        }
+    }
+
+    fn has_comment_before(&self, position: Option<Position>) -> bool {
+        match (self.source.comments().get(self.next_comment), position) {
+            (Some(comment), Some(position)) => comment.span.start() < position,
+            _ => false,
+        }
+    }
+
+    /// Finishes the line of an item or statement which ends at `end` in the source.
+    fn end_line(&mut self, end: Position) {
+        self.trailing_comment(end);
+        self.newline();
+    }
+
+    fn write(&mut self, text: &str) {
+        if self.at_line_start() && !text.is_empty() {
+            self.output.push_str(&" ".repeat(self.indent * self.config.tab_spaces));
+        }
+        self.output.push_str(text);
+    }
+
+    fn newline(&mut self) {
+        self.output.push('\n');
+    }
+
+    /// Separates what comes next from what came before with a blank line, unless there is one
+    /// already or nothing comes before it in the enclosing block, list or file.
+    fn blank_line(&mut self) {
+        if !self.at_line_start() {
+            self.newline();
+        }
+        let previous = self.output.trim_end_matches('\n');
+        let at_start = previous.is_empty() || previous.ends_with(&['{', '(', '['][..]);
+        if !at_start && !self.output.ends_with("\n\n") {
+            self.newline();
+        }
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.output.is_empty() || self.output.ends_with('\n')
+    }
+
+    /// Returns the width of the current line.
+    fn column(&self) -> usize {
+        if self.at_line_start() {
+            return self.indent * self.config.tab_spaces;
+        }
+        let line_start = self.output.rfind('\n').map_or(0, |newline| newline + 1);
+        self.output[line_start..].chars().count()
+    }
+}
+
+/// Returns how tightly `expression` binds when it is the operand of another expression, with
+/// higher values binding more tightly. Binary operators follow the parser's `Precedence`.
+fn binding_power(expression: &Expression) -> u8 {
+    match &expression.kind {
+        // The body of a lambda extends as far as it can
+        ExpressionKind::Lambda(_) => 0,
+        ExpressionKind::Infix(infix) => operator_binding_power(infix.operator.contents),
+        ExpressionKind::Prefix(_) => PREFIX,
+        ExpressionKind::Index(_)
+        | ExpressionKind::Call(_)
+        | ExpressionKind::MethodCall(_)
+        | ExpressionKind::MemberAccess(_)
+        | ExpressionKind::Cast(_) => POSTFIX,
+        _ => ATOM,
+    }
+}
+
+fn operator_binding_power(operator: BinaryOpKind) -> u8 {
+    match operator {
+        BinaryOpKind::Equal | BinaryOpKind::NotEqual => 1,
+        BinaryOpKind::Or => 2,
+        BinaryOpKind::Xor => 3,
+        BinaryOpKind::And => 4,
+        BinaryOpKind::Less
+        | BinaryOpKind::LessEqual
+        | BinaryOpKind::Greater
+        | BinaryOpKind::GreaterEqual => 5,
+        BinaryOpKind::ShiftLeft | BinaryOpKind::ShiftRight => 6,
+        BinaryOpKind::Add | BinaryOpKind::Subtract => 7,
+        BinaryOpKind::Multiply | BinaryOpKind::Divide | BinaryOpKind::Modulo => 8,
+    }
+}
+
+/// The parser desugars `for x in array { ... }` into a block binding the array to a fresh
+/// variable, and looping over its indices. This returns the loop variable, array and body of
+/// such a block.
+fn desugared_for_loop(block: &BlockExpression) -> Option<(&Ident, &Expression, &Expression)> {
+    let (array, for_loop) = match block.0.as_slice() {
+        [Statement::Let(array), Statement::Expression(for_loop)] => (array, for_loop),
+        _ => return None,
+    };
+    // The fresh variable's name can't be written in the source
+    match &array.pattern {
+        Pattern::Identifier(name) if name.0.contents.starts_with('$') => (),
+        _ => return None,
+    }
+
+    let for_loop = match &for_loop.kind {
+        ExpressionKind::For(for_loop) => for_loop,
+        _ => return None,
+    };
+    match &for_loop.block.kind {
+        ExpressionKind::Block(BlockExpression(statements)) => match statements.as_slice() {
+            [Statement::Let(element), Statement::Expression(body)] => match &element.pattern {
+                Pattern::Identifier(identifier) => Some((identifier, &array.expression, body)),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Imports are desugared by the parser into one per name. Names imported by the same `use`
+/// share the segments of their prefix, so are grouped back together by the spans of these.
+fn group_imports(imports: &[ImportStatement]) -> Vec<Vec<&ImportStatement>> {
+    let prefix = |import: &ImportStatement| {
+        let segments = &import.path.segments;
+        vecmap(&segments[..segments.len() - 1], Ident::span)
+    };
+
+    let mut groups: Vec<Vec<&ImportStatement>> = Vec::new();
+    for import in imports {
+        let grouped = groups.last().map_or(false, |group| {
+            group[0].path.kind == import.path.kind
+                && !prefix(import).is_empty()
+                && prefix(group[0]) == prefix(import)
+        });
+        if grouped {
+            groups.last_mut().unwrap().push(import);
+        } else {
+            groups.push(vec![import]);
+        }
+    }
+    groups
+}
+
+/// Returns the span of the source a statement was parsed from, excluding any keyword it
+/// starts with.
+fn statement_span(statement: &Statement) -> Option<Span> {
+    match statement {
+        Statement::Let(let_statement) => {
+            Some(pattern_span(&let_statement.pattern).merge(let_statement.expression.span))
+        }
+        Statement::Constrain(ConstrainStatement(condition, _)) => Some(condition.span),
+        Statement::Expression(expression) | Statement::Semi(expression) => Some(expression.span),
+        Statement::Assign(assign) => {
+            Some(lvalue_span(&assign.lvalue).merge(assign.expression.span))
+        }
+        Statement::Error => None,
+    }
+}
+
+fn lvalue_span(lvalue: &LValue) -> Span {
+    match lvalue {
+        LValue::Ident(name) => name.span(),
+        LValue::MemberAccess { object, .. } => lvalue_span(object),
+        LValue::Index { array, .. } => lvalue_span(array),
+    }
+}
+
+fn pattern_span(pattern: &Pattern) -> Span {
+    match pattern {
+        Pattern::Identifier(name) => name.span(),
+        Pattern::Mutable(_, span) | Pattern::Tuple(_, span) | Pattern::Struct(_, _, span) => *span,
+    }
+}
+
+fn pattern_to_string(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Identifier(name) => name.to_string(),
+        Pattern::Mutable(pattern, _) => format!("mut {}", pattern_to_string(pattern)),
+        Pattern::Tuple(patterns, _) => {
+            format!("({})", vecmap(patterns, pattern_to_string).join(", "))
+        }
+        Pattern::Struct(type_name, fields, _) => {
+            let fields = vecmap(fields, |(name, pattern)| match pattern {
+                Pattern::Identifier(binding) if binding == name => name.to_string(),
+                _ => format!("{name}: {}", pattern_to_string(pattern)),
+            });
+            format!("{} {{ {} }}", path_to_string(type_name), fields.join(", "))
+        }
+    }
+}
+
+fn parameter_to_string(
+    pattern: &Pattern,
+    typ: &UnresolvedType,
+    visibility: AbiVisibility,
+) -> String {
+    // The type of `self` is implied
+    if matches!(pattern, Pattern::Identifier(name) if name.0.contents == "self") {
+        return pattern_to_string(pattern);
+    }
+    let visibility = if visibility == AbiVisibility::Public { "pub " } else { "" };
+    format!("{}: {visibility}{}", pattern_to_string(pattern), type_to_string(typ))
+}
+
+fn path_to_string(path: &Path) -> String {
+    let segments = vecmap(&path.segments, ToString::to_string).join("::");
+    match path.kind {
+        PathKind::Crate => format!("crate::{segments}"),
+        PathKind::Dep => format!("dep::{segments}"),
+        PathKind::Plain => segments,
+    }
+}
+
+fn generics_to_string(generics: &[Ident]) -> String {
+    if generics.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", vecmap(generics, ToString::to_string).join(", "))
+    }
+}
+
+fn type_to_string(typ: &UnresolvedType) -> String {
+    let type_arguments = |arguments: &[UnresolvedType]| {
+        if arguments.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", vecmap(arguments, type_to_string).join(", "))
+        }
+    };
+
+    match typ {
+        UnresolvedType::FieldElement(comp_time) => format!("{comp_time}Field"),
+        UnresolvedType::Array(None, element) => format!("[{}]", type_to_string(element)),
+        UnresolvedType::Array(Some(length), element) => {
+            format!("[{}; {}]", type_to_string(element), type_expression_to_string(length))
+        }
+        UnresolvedType::Integer(comp_time, sign, bits) => {
+            format!("{comp_time}{}", integer_type(*sign, *bits))
+        }
+        UnresolvedType::Bool(comp_time) => format!("{comp_time}bool"),
+        UnresolvedType::Expression(expression) => type_expression_to_string(expression),
+        UnresolvedType::String(None) => "str".to_owned(),
+        UnresolvedType::String(Some(length)) => {
+            format!("str<{}>", type_expression_to_string(length))
+        }
+        UnresolvedType::Unit => "()".to_owned(),
+        UnresolvedType::Named(path, arguments) => {
+            format!("{}{}", path_to_string(path), type_arguments(arguments))
+        }
+        UnresolvedType::Vec(arguments, _) => format!("Vec{}", type_arguments(arguments)),
+        UnresolvedType::Tuple(elements) => {
+            format!("({})", vecmap(elements, type_to_string).join(", "))
+        }
+        UnresolvedType::Function(arguments, return_type) => {
+            let arguments = vecmap(arguments, type_to_string).join(", ");
+            format!("fn({arguments}) -> {}", type_to_string(return_type))
+        }
+        UnresolvedType::Unspecified | UnresolvedType::Error => String::new(),
+    }
+}
+
+fn integer_type(sign: Signedness, bits: u32) -> String {
+    match sign {
+        Signedness::Signed => format!("i{bits}"),
+        Signedness::Unsigned => format!("u{bits}"),
+    }
+}
+
+fn type_expression_to_string(expression: &UnresolvedTypeExpression) -> String {
+    match expression {
+        UnresolvedTypeExpression::Variable(path) => path_to_string(path),
+        UnresolvedTypeExpression::Constant(value, _) => value.to_string(),
+        UnresolvedTypeExpression::BinaryOperation(lhs, operator, rhs, _) => {
+            let precedence = type_operator_binding_power(*operator);
+            let operand = |operand: &UnresolvedTypeExpression, min_binding_power| {
+                let text = type_expression_to_string(operand);
+                match operand {
+                    UnresolvedTypeExpression::BinaryOperation(_, operator, _, _)
+                        if type_operator_binding_power(*operator) < min_binding_power =>
+                    {
+                        format!("({text})")
+                    }
+                    _ => text,
+                }
+            };
+            format!("{} {operator} {}", operand(lhs, precedence), operand(rhs, precedence + 1))
+        }
+    }
+}
+
+fn type_operator_binding_power(operator: BinaryTypeOperator) -> u8 {
+    match operator {
+        BinaryTypeOperator::Addition | BinaryTypeOperator::Subtraction => 1,
+        BinaryTypeOperator::Multiplication
+        | BinaryTypeOperator::Division
+        | BinaryTypeOperator::Modulo => 2,
+    }
+}
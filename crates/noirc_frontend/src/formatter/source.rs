@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use noirc_errors::{Position, Span};
+
+use crate::lexer::Lexer;
+use crate::token::{Keyword, Token};
+
+/// A comment in the source file, without its leading `//`.
+pub(super) struct Comment {
+    pub(super) span: Span,
+    pub(super) text: String,
+}
+
+/// The parts of a source file which the Ast doesn't record but the formatter needs: its
+/// comments, the lines it is made up of and the positions of tokens such as braces.
+pub(super) struct Source {
+    chars: Vec<char>,
+    line_starts: Vec<Position>,
+    tokens: Vec<(Token, Span)>,
+    comments: Vec<Comment>,
+    /// The position of the `}` closing each `{`, keyed by the position of the `{`.
+    closing_braces: HashMap<Position, Position>,
+}
+
+impl Source {
+    pub(super) fn new(source: &str) -> Self {
+        let chars: Vec<_> = source.chars().collect();
+
+        // Positions in spans count characters rather than bytes
+        let mut line_starts = vec![0];
+        for (position, ch) in (0..).zip(&chars) {
+            if *ch == '\n' {
+                line_starts.push(position + 1);
+            }
+        }
+
+        let mut tokens = Vec::new();
+        let mut comments = Vec::new();
+        let mut open_braces = Vec::new();
+        let mut closing_braces = HashMap::new();
+        for token in Lexer::lex_with_comments(source).0 .0 {
+            let span = token.to_span();
+            match token.into_token() {
+                Token::LineComment(text) => comments.push(Comment { span, text }),
+                // Doc comments are laid out like any other comment, so they are kept with the
+                // `/` or `!` which follows the leading `//`
+                Token::OuterDocComment(text) => {
+                    comments.push(Comment { span, text: format!("/{text}") });
+                }
+                Token::InnerDocComment(text) => {
+                    comments.push(Comment { span, text: format!("!{text}") });
+                }
+                token => {
+                    match token {
+                        Token::LeftBrace => open_braces.push(span.start()),
+                        Token::RightBrace => {
+                            if let Some(open) = open_braces.pop() {
+                                closing_braces.insert(open, span.start());
+                            }
+                        }
+                        _ => (),
+                    }
+                    tokens.push((token, span));
+                }
+            }
+        }
+
+        Source { chars, line_starts, tokens, comments, closing_braces }
+    }
+
+    pub(super) fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+
+    /// Returns the source text within `span`.
+    pub(super) fn text(&self, span: Span) -> String {
+        let end = (span.end() as usize + 1).min(self.chars.len());
+        self.chars[(span.start() as usize).min(end)..end].iter().collect()
+    }
+
+    /// Returns the line the given position is on, counting from zero.
+    pub(super) fn line(&self, position: Position) -> usize {
+        match self.line_starts.binary_search(&position) {
+            Ok(line) => line,
+            Err(next_line) => next_line - 1,
+        }
+    }
+
+    /// Returns true if the two positions are on the same line.
+    pub(super) fn same_line(&self, first: Position, second: Position) -> bool {
+        self.line(first) == self.line(second)
+    }
+
+    /// Returns true if the line before the one `position` is on contains only whitespace.
+    pub(super) fn follows_blank_line(&self, position: Position) -> bool {
+        let line = self.line(position);
+        if line == 0 {
+            return false;
+        }
+        let start = self.line_starts[line - 1] as usize;
+        let end = self.line_starts[line] as usize;
+        self.chars[start..end].iter().all(|ch| ch.is_whitespace())
+    }
+
+    /// Returns the positions of the braces around the first block following `position`, such as
+    /// the body of a function whose name ends at `position`.
+    pub(super) fn block_after(&self, position: Position) -> Option<(Position, Position)> {
+        let mut depth = 0_usize;
+        for (token, span) in self.tokens_after(position) {
+            match token {
+                Token::LeftParen | Token::LeftBracket => depth += 1,
+                Token::RightParen | Token::RightBracket => depth = depth.saturating_sub(1),
+                Token::LeftBrace if depth == 0 => {
+                    let open = span.start();
+                    return Some((open, *self.closing_braces.get(&open)?));
+                }
+                _ => (),
+            }
+        }
+        None
+    }
+
+    /// Returns the end of the statement whose last expression ends at `position`, which is its
+    /// semicolon if it has one. The closing parenthesis and message of an `assert` are skipped.
+    pub(super) fn statement_end(&self, position: Position) -> Position {
+        for (token, span) in self.tokens_after(position) {
+            match token {
+                Token::Semicolon => return span.end(),
+                Token::RightParen | Token::Comma | Token::Str(_) => (),
+                _ => break,
+            }
+        }
+        position
+    }
+
+    /// Returns the start of the item whose name starts at `position`, which is introduced by
+    /// `keyword`. Any modifiers or attribute before the keyword are included in the item.
+    pub(super) fn item_start(&self, position: Position, keyword: Keyword) -> Position {
+        let mut index = self.tokens.partition_point(|(_, span)| span.start() < position);
+        while index > 0 && self.tokens[index - 1].0 != Token::Keyword(keyword) {
+            index -= 1;
+        }
+        if index == 0 {
+            return position;
+        }
+        index -= 1;
+
+        while index > 0 {
+            match &self.tokens[index - 1].0 {
+                Token::Keyword(Keyword::Unconstrained | Keyword::Open) | Token::Attribute(_) => {
+                    index -= 1;
+                }
+                _ => break,
+            }
+        }
+        self.tokens[index].1.start()
+    }
+
+    fn tokens_after(&self, position: Position) -> impl Iterator<Item = &(Token, Span)> {
+        let index = self.tokens.partition_point(|(_, span)| span.start() <= position);
+        self.tokens[index..].iter()
+    }
+}
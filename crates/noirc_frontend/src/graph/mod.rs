@@ -9,7 +9,8 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use smol_str::SmolStr;
 
 /// The local crate is the crate being compiled.
-/// The caller should ensure that this crate has a CrateId(0).
+/// When several crates are being compiled, such as the members of a workspace,
+/// this is the first of them to have been added to the graph.
 pub const LOCAL_CRATE: CrateId = CrateId(0);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct CrateId(usize);
@@ -63,6 +64,8 @@ pub struct CrateData {
     pub root_file_id: FileId,
     pub crate_type: CrateType,
     pub dependencies: Vec<Dependency>,
+    /// Whether this is one of the crates being compiled, rather than a dependency of them
+    pub is_local: bool,
 }
 
 /// A dependency is a crate name and a crate_id
@@ -81,11 +84,21 @@ impl Dependency {
 
 impl CrateGraph {
     pub fn add_crate_root(&mut self, crate_type: CrateType, file_id: FileId) -> CrateId {
+        self.add_root(crate_type, file_id, false)
+    }
+
+    /// Adds the root of a crate which is being compiled, rather than being a dependency.
+    pub fn add_local_crate_root(&mut self, crate_type: CrateType, file_id: FileId) -> CrateId {
+        self.add_root(crate_type, file_id, true)
+    }
+
+    fn add_root(&mut self, crate_type: CrateType, file_id: FileId, is_local: bool) -> CrateId {
         let mut roots_with_file_id =
             self.arena.iter().filter(|(_, crate_data)| crate_data.root_file_id == file_id);
         assert!(roots_with_file_id.next().is_none(), "you cannot add the same file id twice");
 
-        let data = CrateData { root_file_id: file_id, crate_type, dependencies: Vec::new() };
+        let data =
+            CrateData { root_file_id: file_id, crate_type, dependencies: Vec::new(), is_local };
         let crate_id = CrateId(self.arena.len());
         let prev = self.arena.insert(crate_id, data);
         assert!(prev.is_none());
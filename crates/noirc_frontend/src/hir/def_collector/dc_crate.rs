@@ -1,6 +1,6 @@
 use super::dc_mod::collect_defs;
 use super::errors::DefCollectorErrorKind;
-use crate::graph::{CrateId, LOCAL_CRATE};
+use crate::graph::CrateId;
 use crate::hir::def_map::{CrateDefMap, LocalModuleId, ModuleId};
 use crate::hir::resolution::errors::ResolverError;
 use crate::hir::resolution::resolver::Resolver;
@@ -10,15 +10,16 @@ use crate::hir::resolution::{
 };
 use crate::hir::type_check::{type_check_func, TypeChecker};
 use crate::hir::Context;
-use crate::node_interner::{FuncId, NodeInterner, StmtId, StructId};
+use crate::hir_def::stmt::HirPattern;
+use crate::node_interner::{FuncId, NodeInterner, ReferenceId, StmtId, StructId};
 use crate::{
     ExpressionKind, Generics, Ident, LetStatement, NoirFunction, NoirStruct, ParsedModule, Shared,
     Type, TypeBinding, UnresolvedGenerics, UnresolvedType,
 };
 use fm::FileId;
 use iter_extended::vecmap;
-use noirc_errors::Span;
 use noirc_errors::{CustomDiagnostic, FileDiagnostic};
+use noirc_errors::{Location, Span};
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -46,6 +47,7 @@ pub struct UnresolvedGlobal {
     pub module_id: LocalModuleId,
     pub stmt_id: StmtId,
     pub stmt_def: LetStatement,
+    pub doc_comments: Vec<String>,
 }
 
 /// Given a Crate root, collect all definitions in that crate
@@ -134,9 +136,15 @@ impl DefCollector {
 
         // Populate module namespaces according to the imports used
         let current_def_map = context.def_maps.get_mut(&crate_id).unwrap();
+        let mut import_references = Vec::new();
         for resolved_import in resolved {
             let name = resolved_import.name;
+            let file_id = current_def_map.modules[resolved_import.module_scope.0].origin.file_id();
+            let location = Location::new(resolved_import.path_segment.span(), file_id);
+
             for ns in resolved_import.resolved_namespace.iter_defs() {
+                import_references.push((location, ns));
+
                 let result = current_def_map.modules[resolved_import.module_scope.0]
                     .import(name.clone(), ns);
 
@@ -166,6 +174,12 @@ impl DefCollector {
 
         file_global_ids.append(&mut more_global_ids);
 
+        // Imported globals can only be traced back to their definitions once they are resolved
+        for (location, module_def_id) in import_references {
+            let reference = context.def_interner.module_def_reference(module_def_id);
+            context.def_interner.add_reference(location, reference);
+        }
+
         // Before we resolve any function symbols we must go through our impls and
         // re-collect the methods within into their proper module. This cannot be
         // done before resolution since we need to be able to resolve the type of the
@@ -237,10 +251,10 @@ fn collect_impls(
                         errors.push(err.into_file_diagnostic(unresolved.file_id));
                     }
                 }
-            // Prohibit defining impls for primitive types if we're in the local crate.
+            // Prohibit defining impls for primitive types if we're in a local crate.
             // We should really prevent it for all crates that aren't the noir stdlib but
             // there is no way of checking if the current crate is the stdlib currently.
-            } else if typ != Type::Error && crate_id == LOCAL_CRATE {
+            } else if typ != Type::Error && context.crate_graph[crate_id].is_local {
                 let span = *span;
                 let error = DefCollectorErrorKind::NonStructTypeInImpl { span };
                 errors.push(error.into_file_diagnostic(unresolved.file_id));
@@ -299,6 +313,12 @@ fn resolve_globals(
 
         context.def_interner.update_global(global.stmt_id, hir_stmt);
 
+        let let_stmt = context.def_interner.let_statement(&global.stmt_id);
+        if let HirPattern::Identifier(ident) = let_stmt.pattern {
+            let id = ReferenceId::Definition(ident.id);
+            context.def_interner.add_doc_comments(id, global.doc_comments);
+        }
+
         context.def_interner.push_global(global.stmt_id, name, global.module_id, storage_slot);
 
         (global.file_id, global.stmt_id)
@@ -332,8 +352,27 @@ fn resolve_structs(
         context.def_interner.push_empty_struct(*type_id, typ);
     }
 
-    for (type_id, typ) in structs {
+    for (type_id, mut typ) in structs {
+        let location = Location::new(typ.struct_def.name.span(), typ.file_id);
+        context.def_interner.add_declaration(ReferenceId::Struct(type_id), location);
+
+        let doc_comments = std::mem::take(&mut typ.struct_def.doc_comments);
+        context.def_interner.add_doc_comments(ReferenceId::Struct(type_id), doc_comments);
+        for (index, field) in typ.struct_def.fields.iter_mut().enumerate() {
+            let doc_comments = std::mem::take(&mut field.doc_comments);
+            let id = ReferenceId::StructMember(type_id, index);
+            context.def_interner.add_doc_comments(id, doc_comments);
+        }
+
+        let file_id = typ.file_id;
         let (generics, fields) = resolve_struct_fields(context, crate_id, typ, errors);
+        for (index, (field_name, _)) in fields.iter().enumerate() {
+            let location = Location::new(field_name.span(), file_id);
+            context
+                .def_interner
+                .add_declaration(ReferenceId::StructMember(type_id, index), location);
+        }
+
         context.def_interner.update_struct(type_id, |struct_def| {
             struct_def.set_fields(fields);
             struct_def.generics = generics;
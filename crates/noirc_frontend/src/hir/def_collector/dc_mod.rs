@@ -1,9 +1,12 @@
 use fm::FileId;
-use noirc_errors::FileDiagnostic;
+use noirc_errors::{FileDiagnostic, Location};
 
 use crate::{
-    graph::CrateId, hir::def_collector::dc_crate::UnresolvedStruct, node_interner::StructId,
-    parser::SubModule, Ident, LetStatement, NoirFunction, NoirImpl, NoirStruct, ParsedModule,
+    graph::CrateId,
+    hir::def_collector::dc_crate::UnresolvedStruct,
+    node_interner::{ReferenceId, StructId},
+    parser::SubModule,
+    Documented, Ident, LetStatement, NoirFunction, NoirImpl, NoirStruct, ParsedModule,
 };
 
 use super::{
@@ -35,9 +38,18 @@ pub fn collect_defs(
 ) {
     let mut collector = ModCollector { def_collector, file_id, module_id };
 
+    let id = ReferenceId::Module(ModuleId { krate: crate_id, local_id: module_id });
+    context.def_interner.add_doc_comments(id, ast.doc_comments);
+
     // First resolve the module declarations
     for decl in ast.module_decls {
-        collector.parse_module_declaration(context, &decl, crate_id, errors);
+        collector.parse_module_declaration(
+            context,
+            &decl.item,
+            decl.doc_comments,
+            crate_id,
+            errors,
+        );
     }
 
     collector.collect_submodules(context, crate_id, ast.submodules, file_id, errors);
@@ -64,10 +76,10 @@ impl<'a> ModCollector<'a> {
     fn collect_globals(
         &mut self,
         context: &mut Context,
-        globals: Vec<LetStatement>,
+        globals: Vec<Documented<LetStatement>>,
         errors: &mut Vec<FileDiagnostic>,
     ) {
-        for global in globals {
+        for Documented { item: global, doc_comments } in globals {
             let name = global.pattern.name_ident().clone();
 
             // First create dummy function in the DefInterner
@@ -88,6 +100,7 @@ impl<'a> ModCollector<'a> {
                 module_id: self.module_id,
                 stmt_id,
                 stmt_def: global,
+                doc_comments,
             });
         }
     }
@@ -97,9 +110,15 @@ impl<'a> ModCollector<'a> {
             let mut unresolved_functions =
                 UnresolvedFunctions { file_id: self.file_id, functions: Vec::new() };
 
-            for method in r#impl.methods {
+            for mut method in r#impl.methods {
                 let func_id = context.def_interner.push_empty_fn();
-                context.def_interner.push_function_definition(method.name().to_owned(), func_id);
+                let definition_id = context
+                    .def_interner
+                    .push_function_definition(method.name().to_owned(), func_id);
+                let doc_comments = std::mem::take(&mut method.def_mut().doc_comments);
+                context
+                    .def_interner
+                    .add_doc_comments(ReferenceId::Definition(definition_id), doc_comments);
                 unresolved_functions.push_fn(self.module_id, func_id, method);
             }
 
@@ -118,13 +137,18 @@ impl<'a> ModCollector<'a> {
         let mut unresolved_functions =
             UnresolvedFunctions { file_id: self.file_id, functions: Vec::new() };
 
-        for function in functions {
+        for mut function in functions {
             let name = function.name_ident().clone();
 
             // First create dummy function in the DefInterner
             // So that we can get a FuncId
             let func_id = context.def_interner.push_empty_fn();
-            context.def_interner.push_function_definition(name.0.contents.clone(), func_id);
+            let definition_id =
+                context.def_interner.push_function_definition(name.0.contents.clone(), func_id);
+            let doc_comments = std::mem::take(&mut function.def_mut().doc_comments);
+            context
+                .def_interner
+                .add_doc_comments(ReferenceId::Definition(definition_id), doc_comments);
 
             // Now link this func_id to a crate level map with the noir function and the module id
             // Encountering a NoirFunction, we retrieve it's module_data to get the namespace
@@ -199,6 +223,13 @@ impl<'a> ModCollector<'a> {
                 submodule.is_contract,
                 errors,
             ) {
+                let module_id = ModuleId { krate: crate_id, local_id: child };
+                let location = Location::new(submodule.name.span(), file_id);
+                context.def_interner.add_declaration(ReferenceId::Module(module_id), location);
+                context
+                    .def_interner
+                    .add_doc_comments(ReferenceId::Module(module_id), submodule.doc_comments);
+
                 collect_defs(
                     self.def_collector,
                     submodule.contents,
@@ -219,6 +250,7 @@ impl<'a> ModCollector<'a> {
         &mut self,
         context: &mut Context,
         mod_name: &Ident,
+        doc_comments: Vec<String>,
         crate_id: CrateId,
         errors: &mut Vec<FileDiagnostic>,
     ) {
@@ -240,6 +272,11 @@ impl<'a> ModCollector<'a> {
         if let Some(child_mod_id) =
             self.push_child_module(mod_name, child_file_id, true, false, errors)
         {
+            let module_id = ModuleId { krate: crate_id, local_id: child_mod_id };
+            let location = Location::new(mod_name.span(), self.file_id);
+            context.def_interner.add_reference(location, ReferenceId::Module(module_id));
+            context.def_interner.add_doc_comments(ReferenceId::Module(module_id), doc_comments);
+
             collect_defs(
                 self.def_collector,
                 ast,
@@ -3,10 +3,10 @@ use crate::hir::def_collector::dc_crate::DefCollector;
 use crate::hir::Context;
 use crate::node_interner::{FuncId, NodeInterner};
 use crate::parser::{parse_program, ParsedModule};
-use crate::token::Attribute;
+use crate::token::{Attribute, TestAttribute, TestCase, TestScope};
 use arena::{Arena, Index};
 use fm::{FileId, FileManager};
-use noirc_errors::FileDiagnostic;
+use noirc_errors::{FileDiagnostic, Location};
 use std::collections::HashMap;
 
 mod module_def;
@@ -105,6 +105,11 @@ impl CrateDefMap {
         self.krate
     }
 
+    /// Returns the root module of each dependency of this crate, by the name used to import it.
+    pub fn extern_prelude(&self) -> &HashMap<String, ModuleId> {
+        &self.extern_prelude
+    }
+
     /// Find the main function for this crate
     pub fn main_function(&self) -> Option<FuncId> {
         let root_module = &self.modules()[self.root.0];
@@ -128,12 +133,17 @@ impl CrateDefMap {
     pub fn get_all_test_functions<'a>(
         &'a self,
         interner: &'a NodeInterner,
-    ) -> impl Iterator<Item = FuncId> + 'a {
+    ) -> impl Iterator<Item = TestFunction> + 'a {
         self.modules.iter().flat_map(|(_, module)| {
-            module
-                .value_definitions()
-                .filter_map(|id| id.as_function())
-                .filter(|id| interner.function_meta(id).attributes == Some(Attribute::Test))
+            module.value_definitions().filter_map(|id| id.as_function()).filter_map(|id| {
+                let meta = interner.function_meta(&id);
+                match meta.attributes {
+                    Some(Attribute::Test(TestAttribute { scope, cases, fuzz })) => {
+                        Some(TestFunction { id, scope, cases, fuzz, location: meta.name.location })
+                    }
+                    _ => None,
+                }
+            })
         })
     }
 
@@ -187,6 +197,34 @@ pub struct Contract {
     pub functions: Vec<FuncId>,
 }
 
+/// A function marked with `#[test]`, along with the outcome it is expected to have.
+/// This is not an AST node, it is just a convenient form to return for CrateDefMap::get_all_test_functions.
+pub struct TestFunction {
+    pub id: FuncId,
+    pub scope: TestScope,
+    /// The inputs to run the test with, once for each case
+    pub cases: Vec<TestCase>,
+    /// Whether the test is run with randomly generated inputs
+    pub fuzz: bool,
+    /// The location of the function's name
+    pub location: Location,
+}
+
+impl TestFunction {
+    /// Returns true if the test only passes if it fails.
+    pub fn should_fail(&self) -> bool {
+        matches!(self.scope, TestScope::ShouldFail { .. })
+    }
+
+    /// Returns the message the test's failure must contain for it to pass, if any.
+    pub fn failure_reason(&self) -> Option<&str> {
+        match &self.scope {
+            TestScope::ShouldFail { reason } => reason.as_deref(),
+            TestScope::None => None,
+        }
+    }
+}
+
 /// Given a FileId, fetch the File, from the FileManager and parse it's content
 pub fn parse_file(
     fm: &mut FileManager,
@@ -85,6 +85,16 @@ impl ModuleData {
         self.scope.find_name(name)
     }
 
+    /// Returns every item visible within this module, including any imported items.
+    pub fn scope(&self) -> &ItemScope {
+        &self.scope
+    }
+
+    /// Returns only the items defined within this module, excluding any imported items.
+    pub fn definitions(&self) -> &ItemScope {
+        &self.definitions
+    }
+
     /// Return an iterator over all definitions defined within this module,
     /// excluding any type definitions.
     pub fn value_definitions(&self) -> impl Iterator<Item = ModuleDefId> + '_ {
@@ -46,7 +46,7 @@ pub enum ResolverError {
     NoSuchNumericTypeVariable { path: crate::Path },
     #[error("Closures cannot capture mutable variables")]
     CapturedMutableVariable { span: Span },
-    #[error("Test functions are not allowed to have any parameters")]
+    #[error("Test functions are not allowed to have any parameters without inputs")]
     TestFunctionHasParameters { span: Span },
     #[error("Only struct types can be used in constructor expressions")]
     NonStructUsedInConstructor { typ: Type, span: Span },
@@ -223,8 +223,8 @@ impl From<ResolverError> for Diagnostic {
                 span,
             ),
             ResolverError::TestFunctionHasParameters { span } => Diagnostic::simple_error(
-                "Test functions cannot have any parameters".into(),
-                "Try removing the parameters or moving the test into a wrapper function".into(),
+                "Test functions can only have parameters if they are given inputs".into(),
+                "Try adding inputs with `#[test(case = \"...\")]` or `#[test(inputs = \"Prover.toml\")]`, or generating them with `#[test(fuzz)]`".into(),
                 span,
             ),
             ResolverError::NonStructUsedInConstructor { typ, span } => Diagnostic::simple_error(
@@ -30,6 +30,8 @@ pub struct ResolvedImport {
     pub resolved_namespace: PerNs,
     // The module which we must add the resolved namespace to
     pub module_scope: LocalModuleId,
+    // The last segment of the import path, which names the symbol we have resolved to
+    pub path_segment: Ident,
 }
 
 impl From<PathResolutionError> for CustomDiagnostic {
@@ -66,7 +68,8 @@ pub fn resolve_imports(
                 .map_err(|error| (error, module_scope))?;
 
         let name = resolve_path_name(&import_directive);
-        Ok(ResolvedImport { name, resolved_namespace, module_scope })
+        let path_segment = import_directive.path.last_segment();
+        Ok(ResolvedImport { name, resolved_namespace, module_scope, path_segment })
     })
 }
 
@@ -25,7 +25,7 @@ use crate::graph::CrateId;
 use crate::hir::def_map::{ModuleDefId, TryFromModuleDefId, MAIN_FUNCTION};
 use crate::hir_def::stmt::{HirAssignStatement, HirLValue, HirPattern};
 use crate::node_interner::{
-    DefinitionId, DefinitionKind, ExprId, FuncId, NodeInterner, StmtId, StructId,
+    DefinitionId, DefinitionKind, ExprId, FuncId, NodeInterner, ReferenceId, StmtId, StructId,
 };
 use crate::{
     hir::{def_map::CrateDefMap, resolution::path_resolver::PathResolver},
@@ -210,6 +210,7 @@ impl<'a> Resolver<'a> {
 
         let id = self.interner.push_definition(name.0.contents.clone(), mutable, definition);
         let location = Location::new(name.span(), self.file);
+        self.interner.add_declaration(ReferenceId::Definition(id), location);
         let ident = HirIdent { location, id };
         let resolver_meta = ResolverMeta { num_times_used: 0, ident, warn_if_unused };
 
@@ -254,6 +255,7 @@ impl<'a> Resolver<'a> {
         } else {
             let id = self.interner.push_definition(name.0.contents.clone(), false, definition);
             let location = Location::new(name.span(), self.file);
+            self.interner.add_declaration(ReferenceId::Definition(id), location);
             ident = HirIdent { location, id };
             resolver_meta = ResolverMeta { num_times_used: 0, ident, warn_if_unused: true };
         }
@@ -294,6 +296,7 @@ impl<'a> Resolver<'a> {
         if let Some((variable_found, _)) = variable {
             variable_found.num_times_used += 1;
             let id = variable_found.ident.id;
+            self.interner.add_reference(location, ReferenceId::Definition(id));
             Ok(HirIdent { location, id })
         } else {
             Err(ResolverError::VariableNotDeclared {
@@ -575,7 +578,10 @@ impl<'a> Resolver<'a> {
         // Check whether the struct definition has globals in the local module and add them to the scope
         self.resolve_local_globals();
 
-        let fields = vecmap(unresolved.fields, |(ident, typ)| (ident, self.resolve_type(typ)));
+        let fields = vecmap(unresolved.fields, |field| {
+            let (ident, typ) = field.item;
+            (ident, self.resolve_type(typ))
+        });
 
         (generics, fields, self.errors)
     }
@@ -598,6 +604,7 @@ impl<'a> Resolver<'a> {
         let location = Location::new(func.name_ident().span(), self.file);
         let id = self.interner.function_definition_id(func_id);
         let name_ident = HirIdent { id, location };
+        self.interner.add_declaration(ReferenceId::Definition(id), location);
 
         let attributes = func.attribute().cloned();
 
@@ -641,7 +648,12 @@ impl<'a> Resolver<'a> {
             self.push_err(ResolverError::DistinctNotAllowed { ident: func.name_ident().clone() });
         }
 
-        if attributes == Some(Attribute::Test) && !parameters.is_empty() {
+        // Parameterised tests are given their inputs by their attribute, or generated by fuzzing
+        let is_test_without_inputs = matches!(
+            &attributes,
+            Some(Attribute::Test(test)) if test.cases.is_empty() && !test.fuzz
+        );
+        if is_test_without_inputs && !parameters.is_empty() {
             self.push_err(ResolverError::TestFunctionHasParameters {
                 span: func.name_ident().span(),
             });
@@ -661,6 +673,7 @@ impl<'a> Resolver<'a> {
             attributes,
             contract_function_type: self.handle_function_type(func),
             is_unconstrained: func.def.is_unconstrained,
+            generic_names: vecmap(&func.def.generics, |generic| generic.0.contents.clone()),
             location,
             typ,
             parameters: parameters.into(),
@@ -809,7 +822,8 @@ impl<'a> Resolver<'a> {
             }
             Statement::Constrain(constrain_stmt) => {
                 let expr_id = self.resolve_expression(constrain_stmt.0);
-                HirStatement::Constrain(HirConstrainStatement(expr_id, self.file))
+                let message = constrain_stmt.1;
+                HirStatement::Constrain(HirConstrainStatement(expr_id, self.file, message))
             }
             Statement::Expression(expr) => HirStatement::Expression(self.resolve_expression(expr)),
             Statement::Semi(expr) => HirStatement::Semi(self.resolve_expression(expr)),
@@ -1097,6 +1111,12 @@ impl<'a> Resolver<'a> {
         for (field, expr) in fields {
             let resolved = resolve_function(self, expr);
 
+            if let Some(index) = struct_type.borrow().field_index(&field.0.contents) {
+                let location = Location::new(field.span(), self.file);
+                let struct_id = struct_type.borrow().id;
+                self.interner.add_reference(location, ReferenceId::StructMember(struct_id, index));
+            }
+
             if unseen_fields.contains(&field) {
                 unseen_fields.remove(&field);
                 seen_fields.insert(field.clone());
@@ -1141,15 +1161,20 @@ impl<'a> Resolver<'a> {
 
     fn lookup_global(&mut self, path: Path) -> Result<DefinitionId, ResolverError> {
         let span = path.span();
+        let location = Location::new(path.last_segment().span(), self.file);
         let id = self.resolve_path(path)?;
 
         if let Some(function) = TryFromModuleDefId::try_from(id) {
-            return Ok(self.interner.function_definition_id(function));
+            let definition_id = self.interner.function_definition_id(function);
+            self.interner.add_reference(location, ReferenceId::Definition(definition_id));
+            return Ok(definition_id);
         }
 
         if let Some(global) = TryFromModuleDefId::try_from(id) {
             let let_stmt = self.interner.let_statement(&global);
-            return Ok(let_stmt.ident().id);
+            let definition_id = let_stmt.ident().id;
+            self.interner.add_reference(location, ReferenceId::Definition(definition_id));
+            return Ok(definition_id);
         }
 
         let expected = "global variable".into();
@@ -1159,7 +1184,7 @@ impl<'a> Resolver<'a> {
 
     /// Lookup a given struct type by name.
     fn lookup_struct_or_error(&mut self, path: Path) -> Option<Shared<StructType>> {
-        match self.lookup(path) {
+        match self.lookup_struct(path) {
             Ok(struct_id) => Some(self.get_struct(struct_id)),
             Err(error) => {
                 self.push_err(error);
@@ -1178,7 +1203,7 @@ impl<'a> Resolver<'a> {
             }
         }
 
-        match self.lookup(path) {
+        match self.lookup_struct(path) {
             Ok(struct_id) => {
                 let struct_type = self.get_struct(struct_id);
                 let generics = struct_type.borrow().instantiate(self.interner);
@@ -1191,6 +1216,14 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    /// Looks up a given struct by name, recording the path as a reference to it.
+    fn lookup_struct(&mut self, path: Path) -> Result<StructId, ResolverError> {
+        let location = Location::new(path.last_segment().span(), self.file);
+        let struct_id = self.lookup(path)?;
+        self.interner.add_reference(location, ReferenceId::Struct(struct_id));
+        Ok(struct_id)
+    }
+
     fn resolve_path(&mut self, path: Path) -> Result<ModuleDefId, ResolverError> {
         self.path_resolver.resolve(self.def_maps, path).map_err(ResolverError::PathResolutionError)
     }
@@ -1275,6 +1308,13 @@ mod test {
     // func_namespace is used to emulate the fact that functions can be imported
     // and functions can be forward declared
     fn resolve_src_code(src: &str, func_namespace: Vec<&str>) -> Vec<ResolverError> {
+        resolve_src_code_with_interner(src, func_namespace).1
+    }
+
+    fn resolve_src_code_with_interner(
+        src: &str,
+        func_namespace: Vec<&str>,
+    ) -> (NodeInterner, Vec<ResolverError>) {
         let (program, errors) = parse_program(src);
         assert!(errors.is_empty());
 
@@ -1316,7 +1356,7 @@ mod test {
             errors.extend(err);
         }
 
-        errors
+        (interner, errors)
     }
 
     #[test]
@@ -1484,6 +1524,28 @@ mod test {
         assert!(errors.is_empty());
     }
 
+    #[test]
+    fn records_references_to_locals() {
+        let src = r#"
+            fn main(x : Field) {
+                let y = x;
+                assert(y == x);
+            }
+        "#;
+
+        let (interner, errors) = resolve_src_code_with_interner(src, vec!["main"]);
+        assert!(errors.is_empty());
+
+        let file = FileId::default();
+        let declaration = src.find("x :").unwrap() as u32;
+        let reference = src.find("= x").unwrap() as u32 + 2;
+
+        let id = interner.reference_at(file, reference).expect("expected a reference to x");
+        assert_eq!(interner.reference_at(file, declaration), Some(id));
+        assert_eq!(interner.declaration_location(id).unwrap().span.start(), declaration);
+        assert_eq!(interner.find_references(id).len(), 2);
+    }
+
     fn path_unresolved_error(err: ResolverError, expected_unresolved_path: &str) {
         match err {
             ResolverError::PathResolutionError(PathResolutionError::Unresolved(name)) => {
@@ -1,12 +1,12 @@
 use iter_extended::vecmap;
-use noirc_errors::Span;
+use noirc_errors::{Location, Span};
 
 use crate::{
     hir_def::{
         expr::{self, HirArrayLiteral, HirBinaryOp, HirExpression, HirLiteral},
         types::Type,
     },
-    node_interner::{ExprId, FuncId},
+    node_interner::{ExprId, FuncId, ReferenceId},
     CompTime, Shared, TypeBinding,
 };
 
@@ -117,6 +117,12 @@ impl<'interner> TypeChecker<'interner> {
                 let method_name = method_call.method.0.contents.as_str();
                 match self.lookup_method(object_type.clone(), method_name, expr_id) {
                     Some(method_id) => {
+                        let file = self.interner.expr_location(expr_id).file;
+                        let location = Location::new(method_call.method.span(), file);
+                        let definition_id = self.interner.function_definition_id(method_id);
+                        self.interner
+                            .add_reference(location, ReferenceId::Definition(definition_id));
+
                         let mut args =
                             vec![(object_type, self.interner.expr_span(&method_call.object))];
                         let mut arg_types = vecmap(&method_call.arguments, |arg| {
@@ -469,6 +475,14 @@ impl<'interner> TypeChecker<'interner> {
         match self.check_field_access(&lhs_type, &access.rhs.0.contents, span) {
             Some((element_type, index)) => {
                 self.interner.set_field_index(expr_id, index);
+
+                if let Type::Struct(struct_type, _) = &lhs_type {
+                    let file = self.interner.expr_location(&expr_id).file;
+                    let location = Location::new(access.rhs.span(), file);
+                    let reference = ReferenceId::StructMember(struct_type.borrow().id, index);
+                    self.interner.add_reference(location, reference);
+                }
+
                 element_type
             }
             None => Type::Error,
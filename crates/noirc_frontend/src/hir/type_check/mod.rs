@@ -212,6 +212,7 @@ mod test {
             location,
             contract_function_type: None,
             is_unconstrained: false,
+            generic_names: Vec::new(),
             typ: Type::Function(vec![Type::field(None), Type::field(None)], Box::new(Type::Unit)),
             parameters: vec![
                 Param(Identifier(x), Type::field(None), noirc_abi::AbiVisibility::Private),
@@ -127,6 +127,10 @@ pub struct FuncMeta {
 
     pub is_unconstrained: bool,
 
+    /// The names of the generics declared on this function, in the order they were declared.
+    /// This does not include the generics of the impl the function may be declared in.
+    pub generic_names: Vec<String>,
+
     pub parameters: Parameters,
 
     pub return_visibility: AbiVisibility,
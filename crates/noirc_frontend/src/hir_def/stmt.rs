@@ -45,8 +45,9 @@ pub struct HirAssignStatement {
 /// This node also contains the FileId of the file the constrain
 /// originates from. This is used later in the SSA pass to issue
 /// an error if a constrain is found to be always false.
+/// The optional message is reported if the constraint fails.
 #[derive(Debug, Clone)]
-pub struct HirConstrainStatement(pub ExprId, pub FileId);
+pub struct HirConstrainStatement(pub ExprId, pub FileId, pub Option<String>);
 
 #[derive(Debug, Clone)]
 pub enum HirPattern {
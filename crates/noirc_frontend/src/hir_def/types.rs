@@ -115,6 +115,11 @@ pub struct StructType {
     fields: Vec<(Ident, Type)>,
 
     pub generics: Generics,
+
+    /// The names of the generics as written in the struct's definition, in the same order
+    /// as `generics`.
+    pub generic_names: Vec<String>,
+
     pub span: Span,
 }
 
@@ -143,8 +148,9 @@ impl StructType {
         span: Span,
         fields: Vec<(Ident, Type)>,
         generics: Generics,
+        generic_names: Vec<String>,
     ) -> StructType {
-        StructType { id, fields, name, span, generics }
+        StructType { id, fields, name, span, generics, generic_names }
     }
 
     /// To account for cyclic references between structs, a struct's
@@ -199,6 +205,11 @@ impl StructType {
         self.fields.iter().map(|(name, _)| name.clone()).collect()
     }
 
+    /// Returns the index of the field with the given name, if there is one.
+    pub fn field_index(&self, field_name: &str) -> Option<usize> {
+        self.fields.iter().position(|(name, _)| name.0.contents == field_name)
+    }
+
     /// True if the given index is the same index as a generic type of this struct
     /// which is expected to be a numeric generic.
     /// This is needed because we infer type kinds in Noir and don't have extensive kind checking.
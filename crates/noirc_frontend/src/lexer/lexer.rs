@@ -17,6 +17,7 @@ pub struct Lexer<'a> {
     char_iter: Peekable<Zip<Chars<'a>, RangeFrom<u32>>>,
     position: Position,
     done: bool,
+    skip_comments: bool,
 }
 
 pub type SpannedTokenResult = Result<SpannedToken, LexerErrorKind>;
@@ -25,10 +26,22 @@ impl<'a> Lexer<'a> {
     /// Given a source file of noir code, return all the tokens in the file
     /// in order, along with any lexing errors that occurred.
     pub fn lex(source: &'a str) -> (Tokens, Vec<LexerErrorKind>) {
-        let lexer = Lexer::new(source);
+        Lexer::new(source).collect_tokens()
+    }
+
+    /// Like `lex`, except that comments are returned as `Token::LineComment`s instead of
+    /// being skipped. The parser doesn't accept comments, so these tokens are only useful
+    /// to tools which need to reproduce the source, such as the formatter.
+    pub fn lex_with_comments(source: &'a str) -> (Tokens, Vec<LexerErrorKind>) {
+        let mut lexer = Lexer::new(source);
+        lexer.skip_comments = false;
+        lexer.collect_tokens()
+    }
+
+    fn collect_tokens(self) -> (Tokens, Vec<LexerErrorKind>) {
         let mut tokens = vec![];
         let mut errors = vec![];
-        for result in lexer {
+        for result in self {
             match result {
                 Ok(token) => tokens.push(token),
                 Err(error) => errors.push(error),
@@ -43,6 +56,7 @@ impl<'a> Lexer<'a> {
             char_iter: source.chars().zip(0..).peekable(),
             position: 0,
             done: false,
+            skip_comments: true,
         }
     }
 
@@ -173,9 +187,10 @@ impl<'a> Lexer<'a> {
             Token::Minus => self.single_double_peek_token('>', prev_token, Token::Arrow),
             Token::Colon => self.single_double_peek_token(':', prev_token, Token::DoubleColon),
             Token::Slash => {
+                let start = self.position;
                 if self.peek_char_is('/') {
                     self.next_char();
-                    return self.parse_comment();
+                    return self.parse_comment(start);
                 }
                 Ok(spanned_prev_token)
             }
@@ -257,16 +272,26 @@ impl<'a> Lexer<'a> {
         }
         self.next_char();
 
-        let (word, start, end) = self.eat_while(None, |ch| {
-            (ch.is_ascii_alphabetic() || ch.is_numeric() || ch == '_' || ch == '(' || ch == ')')
-                && (ch != ']')
-        });
+        // Arguments such as `should_fail_with = "..."` may contain strings, which can hold a `]`
+        let start = self.position;
+        let mut word = String::new();
+        let mut in_string = false;
+        while let Some(ch) = self.peek_char() {
+            if ch == ']' && !in_string {
+                break;
+            }
+            if ch == '"' {
+                in_string = !in_string;
+            }
+            word.push(ch);
+            self.next_char();
+        }
+        let end = self.position;
 
         if !self.peek_char_is(']') {
-            return Err(LexerErrorKind::UnexpectedCharacter {
-                span: Span::single_char(self.position),
-                expected: "]".to_owned(),
-                found: self.next_char().unwrap(),
+            return Err(LexerErrorKind::MalformedFuncAttribute {
+                span: Span::exclusive(start, end),
+                found: word,
             });
         }
         self.next_char();
@@ -330,9 +355,25 @@ impl<'a> Lexer<'a> {
         str_literal_token.into_span(start_span, end_span)
     }
 
-    fn parse_comment(&mut self) -> SpannedTokenResult {
-        let _ = self.eat_while(None, |ch| ch != '\n');
-        self.next_token()
+    /// Lexes a comment, whose leading `//` has already been consumed. Doc comments are always
+    /// returned, since the parser attaches them to the items they document, while other
+    /// comments are skipped unless the lexer was asked to keep them. Doc comments which don't
+    /// document anything are removed before parsing, see `parser::doc_comments`.
+    fn parse_comment(&mut self, start: Position) -> SpannedTokenResult {
+        let (comment, _, end) = self.eat_while(None, |ch| ch != '\n');
+        if let Some(doc) = comment.strip_prefix('!') {
+            return Ok(Token::InnerDocComment(doc.to_owned()).into_span(start, end));
+        }
+        // `////` starts an ordinary comment, as it does in Rust
+        if let Some(doc) = comment.strip_prefix('/').filter(|doc| !doc.starts_with('/')) {
+            return Ok(Token::OuterDocComment(doc.to_owned()).into_span(start, end));
+        }
+
+        if self.skip_comments {
+            self.next_token()
+        } else {
+            Ok(Token::LineComment(comment).into_span(start, end))
+        }
     }
 
     /// Skips white space. They are not significant in the source language
@@ -416,6 +457,63 @@ fn test_custom_gate_syntax() {
     }
 }
 
+#[test]
+fn test_test_attributes() {
+    use super::token::{TestAttribute, TestCase, TestScope};
+
+    let input = r#"#[test] #[test(should_fail)] #[test(should_fail_with = "not [enough] funds")]
+        #[test(case = "x = 1, y = [2, 3]", inputs = "Prover.toml", should_fail)] #[test(fuzz)]"#;
+
+    let expected = vec![
+        Token::Attribute(Attribute::Test(TestAttribute::default())),
+        Token::Attribute(Attribute::Test(TestAttribute {
+            scope: TestScope::ShouldFail { reason: None },
+            cases: Vec::new(),
+            fuzz: false,
+        })),
+        Token::Attribute(Attribute::Test(TestAttribute {
+            scope: TestScope::ShouldFail { reason: Some("not [enough] funds".to_owned()) },
+            cases: Vec::new(),
+            fuzz: false,
+        })),
+        Token::Attribute(Attribute::Test(TestAttribute {
+            scope: TestScope::ShouldFail { reason: None },
+            cases: vec![
+                TestCase::Inline("x = 1, y = [2, 3]".to_owned()),
+                TestCase::File("Prover.toml".to_owned()),
+            ],
+            fuzz: false,
+        })),
+        Token::Attribute(Attribute::Test(TestAttribute {
+            scope: TestScope::None,
+            cases: Vec::new(),
+            fuzz: true,
+        })),
+    ];
+
+    let mut lexer = Lexer::new(input);
+    for token in expected.into_iter() {
+        let got = lexer.next_token().unwrap();
+        assert_eq!(got, token);
+    }
+}
+
+#[test]
+fn test_malformed_test_attributes() {
+    for input in [
+        "#[test(should_pass)]",
+        "#[test(should_fail_with)]",
+        r#"#[test(should_fail_with = "reason)]"#,
+        r#"#[test(should_fail, should_fail_with = "reason")]"#,
+        r#"#[test(case = "x = 1",)]"#,
+        "#[test(inputs = Prover.toml)]",
+        r#"#[test(fuzz, case = "x = 1")]"#,
+    ] {
+        let mut lexer = Lexer::new(input);
+        assert!(lexer.next_token().is_err(), "{input} should not lex");
+    }
+}
+
 #[test]
 fn test_int_type() {
     let input = "u16 i16 i108 u104.5";
@@ -480,6 +578,37 @@ fn test_comment() {
     }
 }
 
+#[test]
+fn test_comments_are_kept_when_requested() {
+    let input = "let x = 5; // five
+        // the end";
+
+    let (tokens, errors) = Lexer::lex_with_comments(input);
+    assert!(errors.is_empty());
+
+    let tokens: Vec<_> =
+        tokens.0.into_iter().map(|token| (token.to_span(), token.into_token())).collect();
+    assert_eq!(tokens[5], (Span::inclusive(11, 17), Token::LineComment(" five".to_string())));
+    assert_eq!(tokens[6].1, Token::LineComment(" the end".to_string()));
+    assert_eq!(tokens[7].1, Token::EOF);
+}
+
+#[test]
+fn test_doc_comments() {
+    let input = "//! A module
+        /// A function
+        //// Not a doc comment
+        fn main() {}";
+
+    let (tokens, errors) = Lexer::lex(input);
+    assert!(errors.is_empty());
+
+    let tokens: Vec<_> = tokens.0.into_iter().map(SpannedToken::into_token).collect();
+    assert_eq!(tokens[0], Token::InnerDocComment(" A module".to_string()));
+    assert_eq!(tokens[1], Token::OuterDocComment(" A function".to_string()));
+    assert_eq!(tokens[2], Token::Keyword(Keyword::Fn));
+}
+
 #[test]
 fn test_eat_string_literal() {
     let input = "let _word = \"hello\"";
@@ -18,6 +18,13 @@ pub enum Token {
     Keyword(Keyword),
     IntType(IntType),
     Attribute(Attribute),
+    /// A `//` comment, holding the text after the slashes. These are only produced when the
+    /// lexer is asked to keep comments, see `Lexer::lex_with_comments`.
+    LineComment(String),
+    /// A `///` doc comment, holding the text after the slashes. It documents the item after it.
+    OuterDocComment(String),
+    /// A `//!` doc comment, holding the text after the `!`. It documents the module it is in.
+    InnerDocComment(String),
     /// <
     Less,
     /// <=
@@ -149,6 +156,9 @@ impl fmt::Display for Token {
             Token::Str(ref b) => write!(f, "{b}"),
             Token::Keyword(k) => write!(f, "{k}"),
             Token::Attribute(ref a) => write!(f, "{a}"),
+            Token::LineComment(ref comment) => write!(f, "//{comment}"),
+            Token::OuterDocComment(ref comment) => write!(f, "///{comment}"),
+            Token::InnerDocComment(ref comment) => write!(f, "//!{comment}"),
             Token::IntType(ref i) => write!(f, "{i}"),
             Token::Less => write!(f, "<"),
             Token::LessEqual => write!(f, "<="),
@@ -197,6 +207,8 @@ pub enum TokenKind {
     Literal,
     Keyword,
     Attribute,
+    OuterDocComment,
+    InnerDocComment,
 }
 
 impl fmt::Display for TokenKind {
@@ -207,6 +219,8 @@ impl fmt::Display for TokenKind {
             TokenKind::Literal => write!(f, "literal"),
             TokenKind::Keyword => write!(f, "keyword"),
             TokenKind::Attribute => write!(f, "attribute"),
+            TokenKind::OuterDocComment => write!(f, "doc comment"),
+            TokenKind::InnerDocComment => write!(f, "inner doc comment"),
         }
     }
 }
@@ -218,6 +232,8 @@ impl Token {
             Token::Int(_) | Token::Bool(_) | Token::Str(_) => TokenKind::Literal,
             Token::Keyword(_) => TokenKind::Keyword,
             Token::Attribute(_) => TokenKind::Attribute,
+            Token::OuterDocComment(_) => TokenKind::OuterDocComment,
+            Token::InnerDocComment(_) => TokenKind::InnerDocComment,
             ref tok => TokenKind::Token(tok.clone()),
         }
     }
@@ -325,7 +341,7 @@ pub enum Attribute {
     Foreign(String),
     Builtin(String),
     Oracle(String),
-    Test,
+    Test(TestAttribute),
 }
 
 impl fmt::Display for Attribute {
@@ -334,7 +350,7 @@ impl fmt::Display for Attribute {
             Attribute::Foreign(ref k) => write!(f, "#[foreign({k})]"),
             Attribute::Builtin(ref k) => write!(f, "#[builtin({k})]"),
             Attribute::Oracle(ref k) => write!(f, "#[oracle({k})]"),
-            Attribute::Test => write!(f, "#[test]"),
+            Attribute::Test(ref test) => write!(f, "#[test{test}]"),
         }
     }
 }
@@ -343,20 +359,31 @@ impl Attribute {
     /// If the string is a fixed attribute return that, else
     /// return the custom attribute
     pub(crate) fn lookup_attribute(word: &str, span: Span) -> Result<Token, LexerErrorKind> {
+        let malformed = || LexerErrorKind::MalformedFuncAttribute { span, found: word.to_owned() };
+
+        // Test attributes may take string arguments, which can contain any character
+        if let Some(arguments) = word.strip_prefix("test") {
+            let arguments = arguments.trim();
+            let test = if arguments.is_empty() {
+                TestAttribute::default()
+            } else {
+                arguments
+                    .strip_prefix('(')
+                    .and_then(|arguments| arguments.strip_suffix(')'))
+                    .and_then(TestAttribute::lookup_str)
+                    .ok_or_else(malformed)?
+            };
+            return Ok(Token::Attribute(Attribute::Test(test)));
+        }
+
         let word_segments: Vec<&str> = word
             .split(|c| c == '(' || c == ')')
+            .map(str::trim)
             .filter(|string_segment| !string_segment.is_empty())
             .collect();
 
         if word_segments.len() != 2 {
-            if word_segments.len() == 1 && word_segments[0] == "test" {
-                return Ok(Token::Attribute(Attribute::Test));
-            } else {
-                return Err(LexerErrorKind::MalformedFuncAttribute {
-                    span,
-                    found: word.to_owned(),
-                });
-            }
+            return Err(malformed());
         }
 
         let attribute_type = word_segments[0];
@@ -366,9 +393,7 @@ impl Attribute {
             "foreign" => Token::Attribute(Attribute::Foreign(attribute_name.to_string())),
             "builtin" => Token::Attribute(Attribute::Builtin(attribute_name.to_string())),
             "oracle" => Token::Attribute(Attribute::Oracle(attribute_name.to_string())),
-            _ => {
-                return Err(LexerErrorKind::MalformedFuncAttribute { span, found: word.to_owned() })
-            }
+            _ => return Err(malformed()),
         };
         Ok(tok)
     }
@@ -396,13 +421,142 @@ impl Attribute {
     }
 }
 
+/// The arguments of a `#[test]` attribute, such as
+/// `#[test(should_fail_with = "reason", case = "x = 1, y = 2")]` or `#[test(fuzz)]`.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord, Default)]
+pub struct TestAttribute {
+    /// The outcome the test is expected to have.
+    pub scope: TestScope,
+    /// The inputs the test is run with, once for each case. Tests without parameters have none.
+    pub cases: Vec<TestCase>,
+    /// `#[test(fuzz)]`: the test is run many times with randomly generated inputs instead.
+    pub fuzz: bool,
+}
+
+impl TestAttribute {
+    fn lookup_str(string: &str) -> Option<TestAttribute> {
+        let mut test = TestAttribute::default();
+
+        for argument in split_arguments(string)? {
+            let (name, value) = match argument.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(parse_string_argument(value)?)),
+                None => (argument.trim(), None),
+            };
+
+            match (name, value) {
+                ("should_fail", None) if test.scope == TestScope::None => {
+                    test.scope = TestScope::ShouldFail { reason: None };
+                }
+                ("should_fail_with", Some(reason)) if test.scope == TestScope::None => {
+                    test.scope = TestScope::ShouldFail { reason: Some(reason) };
+                }
+                ("case", Some(inputs)) => test.cases.push(TestCase::Inline(inputs)),
+                ("inputs", Some(path)) => test.cases.push(TestCase::File(path)),
+                ("fuzz", None) if !test.fuzz => test.fuzz = true,
+                _ => return None,
+            }
+        }
+
+        // Fuzzed tests generate their own inputs
+        if test.fuzz && !test.cases.is_empty() {
+            return None;
+        }
+        Some(test)
+    }
+}
+
+impl fmt::Display for TestAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut arguments = Vec::new();
+        match &self.scope {
+            TestScope::None => (),
+            TestScope::ShouldFail { reason: None } => arguments.push("should_fail".to_owned()),
+            TestScope::ShouldFail { reason: Some(reason) } => {
+                arguments.push(format!("should_fail_with = \"{reason}\""));
+            }
+        }
+        arguments.extend(self.cases.iter().map(ToString::to_string));
+        if self.fuzz {
+            arguments.push("fuzz".to_owned());
+        }
+
+        if arguments.is_empty() {
+            Ok(())
+        } else {
+            write!(f, "({})", arguments.join(", "))
+        }
+    }
+}
+
+/// Splits the arguments of an attribute on the commas which aren't within a string.
+/// Returns `None` if any argument is empty or a string is left unterminated.
+fn split_arguments(string: &str) -> Option<Vec<&str>> {
+    let mut arguments = Vec::new();
+    let mut in_string = false;
+    let mut start = 0;
+
+    for (index, ch) in string.char_indices() {
+        match ch {
+            '"' => in_string = !in_string,
+            ',' if !in_string => {
+                arguments.push(&string[start..index]);
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    arguments.push(&string[start..]);
+
+    let is_valid = !in_string && arguments.iter().all(|argument| !argument.trim().is_empty());
+    is_valid.then_some(arguments)
+}
+
+/// Parses the value of an argument such as `reason = "..."`, which must be a single string.
+fn parse_string_argument(value: &str) -> Option<String> {
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    // Quotes can't be escaped within strings, so any left over were unbalanced
+    if value.contains('"') {
+        return None;
+    }
+    Some(value.to_owned())
+}
+
+/// The outcome a test function is expected to have.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord, Default)]
+pub enum TestScope {
+    /// `#[test(should_fail)]` or `#[test(should_fail_with = "reason")]`: the test passes only if
+    /// it fails and, if a reason is given, only if its failure message contains that reason.
+    ShouldFail { reason: Option<String> },
+    /// `#[test]`: the test passes if it executes successfully.
+    #[default]
+    None,
+}
+
+/// A set of inputs to run a test function with.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
+pub enum TestCase {
+    /// `case = "x = 1, y = [2, 3]"`: inputs written as the contents of a TOML inline table.
+    Inline(String),
+    /// `inputs = "Prover.toml"`: inputs read from a TOML or JSON file, relative to the package root.
+    File(String),
+}
+
+impl fmt::Display for TestCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TestCase::Inline(inputs) => write!(f, "case = \"{inputs}\""),
+            TestCase::File(path) => write!(f, "inputs = \"{path}\""),
+        }
+    }
+}
+
 impl AsRef<str> for Attribute {
     fn as_ref(&self) -> &str {
         match self {
             Attribute::Foreign(string) => string,
             Attribute::Builtin(string) => string,
             Attribute::Oracle(string) => string,
-            Attribute::Test => "",
+            Attribute::Test(_) => "",
         }
     }
 }
@@ -415,6 +569,7 @@ impl AsRef<str> for Attribute {
 pub enum Keyword {
     As,
     Assert,
+    AssertEq,
     Bool,
     Char,
     CompTime,
@@ -450,6 +605,7 @@ impl fmt::Display for Keyword {
         match *self {
             Keyword::As => write!(f, "as"),
             Keyword::Assert => write!(f, "assert"),
+            Keyword::AssertEq => write!(f, "assert_eq"),
             Keyword::Bool => write!(f, "bool"),
             Keyword::Char => write!(f, "char"),
             Keyword::CompTime => write!(f, "comptime"),
@@ -488,6 +644,7 @@ impl Keyword {
         let keyword = match word {
             "as" => Keyword::As,
             "assert" => Keyword::Assert,
+            "assert_eq" => Keyword::AssertEq,
             "bool" => Keyword::Bool,
             "char" => Keyword::Char,
             "comptime" => Keyword::CompTime,
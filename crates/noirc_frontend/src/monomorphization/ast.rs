@@ -31,7 +31,7 @@ pub enum Expression {
     Call(Call),
 
     Let(Let),
-    Constrain(Box<Expression>, Location),
+    Constrain(Box<Expression>, Location, Option<String>),
     Assign(Assign),
     Semi(Box<Expression>),
 }
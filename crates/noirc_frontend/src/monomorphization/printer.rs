@@ -58,9 +58,13 @@ impl AstPrinter {
                 write!(f, "let {}${} = ", let_expr.name, let_expr.id.0)?;
                 self.print_expr(&let_expr.expression, f)
             }
-            Expression::Constrain(expr, ..) => {
+            Expression::Constrain(expr, _, message) => {
                 write!(f, "constrain ")?;
-                self.print_expr(expr, f)
+                self.print_expr(expr, f)?;
+                match message {
+                    Some(message) => write!(f, ", {message:?}"),
+                    None => Ok(()),
+                }
             }
             Expression::Assign(assign) => {
                 self.print_lvalue(&assign.lvalue, f)?;
@@ -8,10 +8,10 @@ use noirc_errors::{Location, Span, Spanned};
 use crate::ast::Ident;
 use crate::graph::CrateId;
 use crate::hir::def_collector::dc_crate::UnresolvedStruct;
-use crate::hir::def_map::{LocalModuleId, ModuleId};
+use crate::hir::def_map::{LocalModuleId, ModuleDefId, ModuleId};
 use crate::hir::type_check::TypeCheckError;
 use crate::hir::StorageSlot;
-use crate::hir_def::stmt::HirLetStatement;
+use crate::hir_def::stmt::{HirLetStatement, HirPattern};
 use crate::hir_def::types::{StructType, Type};
 use crate::hir_def::{
     expr::HirExpression,
@@ -73,6 +73,18 @@ pub struct NodeInterner {
 
     /// Methods on primitive types defined in the stdlib.
     primitive_methods: HashMap<(TypeMethodKey, String), FuncId>,
+
+    /// The location of the name of each declaration which can be referred to elsewhere.
+    /// Only used by tooling such as the language server.
+    declarations: HashMap<ReferenceId, Location>,
+
+    /// Each location in the source program which refers to a declaration by name, in the
+    /// order they were resolved. Only used by tooling such as the language server.
+    references: Vec<(Location, ReferenceId)>,
+
+    /// The lines of the doc comments written for each declaration which has any. Only used by
+    /// tooling such as the language server and `nargo doc`.
+    doc_comments: HashMap<ReferenceId, Vec<String>>,
 }
 
 type TypeCheckFn = Box<dyn FnOnce() -> Result<(), TypeCheckError>>;
@@ -228,6 +240,22 @@ impl DefinitionKind {
     }
 }
 
+/// Anything declared in the source program which can be referred to by name elsewhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceId {
+    /// Functions, globals, locals and generics.
+    Definition(DefinitionId),
+
+    /// Only modules declared inline have their declaration stored in the interner, other
+    /// modules are declared by the file found through the module's origin in its `CrateDefMap`.
+    Module(ModuleId),
+
+    Struct(StructId),
+
+    /// The field of a struct at the given index.
+    StructMember(StructId, usize),
+}
+
 #[derive(Debug, Clone)]
 pub struct GlobalInfo {
     pub ident: Ident,
@@ -255,6 +283,9 @@ impl Default for NodeInterner {
             delayed_type_checks: vec![],
             struct_methods: HashMap::new(),
             primitive_methods: HashMap::new(),
+            declarations: HashMap::new(),
+            references: Vec::new(),
+            doc_comments: HashMap::new(),
         };
 
         // An empty block expression is used often, we add this into the `node` on startup
@@ -307,6 +338,7 @@ impl NodeInterner {
                     let id = TypeVariableId(0);
                     (id, Shared::new(TypeBinding::Unbound(id)))
                 }),
+                vecmap(&typ.struct_def.generics, |generic| generic.0.contents.clone()),
             )),
         );
     }
@@ -490,6 +522,25 @@ impl NodeInterner {
         &self.definition(id).name
     }
 
+    /// Returns a pattern as it would be written in the source program, such as the name of a
+    /// function parameter.
+    pub fn pattern_to_string(&self, pattern: &HirPattern) -> String {
+        match pattern {
+            HirPattern::Identifier(ident) => self.definition_name(ident.id).to_owned(),
+            HirPattern::Mutable(pattern, _) => format!("mut {}", self.pattern_to_string(pattern)),
+            HirPattern::Tuple(patterns, _) => {
+                let patterns = vecmap(patterns, |pattern| self.pattern_to_string(pattern));
+                format!("({})", patterns.join(", "))
+            }
+            HirPattern::Struct(typ, fields, _) => {
+                let fields = vecmap(fields, |(name, pattern)| {
+                    format!("{name}: {}", self.pattern_to_string(pattern))
+                });
+                format!("{typ} {{ {} }}", fields.join(", "))
+            }
+        }
+    }
+
     pub fn expr_span(&self, expr_id: &ExprId) -> Span {
         self.id_location(expr_id).span
     }
@@ -604,6 +655,155 @@ impl NodeInterner {
         get_type_method_key(typ)
             .and_then(|key| self.primitive_methods.get(&(key, method_name.to_owned())).copied())
     }
+
+    /// Records the location of the name of a declaration.
+    pub fn add_declaration(&mut self, id: ReferenceId, location: Location) {
+        self.declarations.insert(id, location);
+    }
+
+    /// Records the lines of the doc comments written for a declaration. A module may have both
+    /// outer and inner doc comments, in which case they are joined in the order they're added.
+    pub fn add_doc_comments(&mut self, id: ReferenceId, doc_comments: Vec<String>) {
+        if !doc_comments.is_empty() {
+            self.doc_comments.entry(id).or_default().extend(doc_comments);
+        }
+    }
+
+    /// Returns the lines of the doc comments written for a declaration, if it has any.
+    pub fn doc_comments(&self, id: ReferenceId) -> Option<&[String]> {
+        self.doc_comments.get(&id).map(Vec::as_slice)
+    }
+
+    /// Records that the name at the given location refers to a declaration.
+    pub fn add_reference(&mut self, location: Location, id: ReferenceId) {
+        self.references.push((location, id));
+    }
+
+    /// Returns the location of the name of the given declaration, if it was recorded.
+    pub fn declaration_location(&self, id: ReferenceId) -> Option<Location> {
+        self.declarations.get(&id).copied()
+    }
+
+    /// Returns the declaration named at the given byte offset into a file, if any. This may be
+    /// either a reference to a declaration or the name of the declaration itself.
+    pub fn reference_at(&self, file: FileId, offset: u32) -> Option<ReferenceId> {
+        self.reference_location_at(file, offset).map(|(_, id)| id)
+    }
+
+    /// Like [`NodeInterner::reference_at`], also returning the location of the name found.
+    pub fn reference_location_at(
+        &self,
+        file: FileId,
+        offset: u32,
+    ) -> Option<(Location, ReferenceId)> {
+        let references = self.references.iter().map(|(location, id)| (location, id));
+        let declarations = self.declarations.iter().map(|(id, location)| (location, id));
+
+        // Paths may overlap with the names within them, in which case the innermost name wins
+        references
+            .chain(declarations)
+            .filter(|(location, _)| location_contains(location, file, offset))
+            .min_by_key(|(location, _)| location.span.end() - location.span.start())
+            .map(|(location, id)| (*location, *id))
+    }
+
+    /// Returns the location of every reference to the given declaration.
+    pub fn find_references(&self, id: ReferenceId) -> Vec<Location> {
+        self.references
+            .iter()
+            .filter(|(_, reference)| *reference == id)
+            .map(|(location, _)| *location)
+            .collect()
+    }
+
+    /// Returns each declaration named at exactly the given location. A name can refer to more
+    /// than one declaration when written in shorthand, such as `x` in `Foo { x }` naming both a
+    /// field of `Foo` and a variable.
+    pub fn references_at_location(&self, location: Location) -> Vec<ReferenceId> {
+        let references = self.references.iter().map(|(location, id)| (location, id));
+        let declarations = self.declarations.iter().map(|(id, location)| (location, id));
+
+        references
+            .chain(declarations)
+            .filter(|(other, _)| **other == location)
+            .map(|(_, id)| *id)
+            .collect()
+    }
+
+    /// Returns the name and id of each method which can be called on values of the given type.
+    pub fn methods_of_type(&self, typ: &Type) -> Vec<(&str, FuncId)> {
+        match typ.follow_bindings() {
+            Type::Struct(struct_type, _) => {
+                let id = struct_type.borrow().id;
+                self.struct_methods
+                    .iter()
+                    .filter(|((struct_id, _), _)| *struct_id == id)
+                    .map(|((_, name), method)| (name.as_str(), *method))
+                    .collect()
+            }
+            other => match get_type_method_key(&other) {
+                Some(key) => self
+                    .primitive_methods
+                    .iter()
+                    .filter(|((method_key, _), _)| *method_key == key)
+                    .map(|((_, name), method)| (name.as_str(), *method))
+                    .collect(),
+                None => Vec::new(),
+            },
+        }
+    }
+
+    /// Returns the id of each function declared in the given file.
+    pub fn functions_in_file(&self, file: FileId) -> Vec<FuncId> {
+        self.func_meta
+            .iter()
+            .filter(|(_, meta)| meta.location.file == file)
+            .map(|(func_id, _)| *func_id)
+            .collect()
+    }
+
+    /// Returns the innermost function whose definition contains the given byte offset into a
+    /// file, if any.
+    pub fn function_at(&self, file: FileId, offset: u32) -> Option<FuncId> {
+        self.func_meta
+            .keys()
+            .filter_map(|func_id| {
+                let body = self.function(func_id);
+                let location = self.id_to_location.get(&Index::from(body.as_expr()))?;
+                location_contains(location, file, offset).then_some((func_id, location))
+            })
+            .min_by_key(|(_, location)| location.span.end() - location.span.start())
+            .map(|(func_id, _)| *func_id)
+    }
+
+    /// Returns the innermost expression containing the given byte offset into a file, if any.
+    pub fn expression_at(&self, file: FileId, offset: u32) -> Option<ExprId> {
+        self.id_to_location
+            .iter()
+            .filter(|(_, location)| location_contains(location, file, offset))
+            // Definitions have locations too, but are not stored in the arena
+            .filter(|(index, _)| matches!(self.nodes.get(**index), Some(Node::Expression(_))))
+            .min_by_key(|(_, location)| location.span.end() - location.span.start())
+            .map(|(index, _)| ExprId(*index))
+    }
+
+    /// Returns the declaration that the given item resolved from a path refers to.
+    pub fn module_def_reference(&self, id: ModuleDefId) -> ReferenceId {
+        match id {
+            ModuleDefId::ModuleId(id) => ReferenceId::Module(id),
+            ModuleDefId::FunctionId(id) => ReferenceId::Definition(self.function_definition_id(id)),
+            ModuleDefId::TypeId(id) => ReferenceId::Struct(id),
+            ModuleDefId::GlobalId(id) => {
+                ReferenceId::Definition(self.let_statement(&id).ident().id)
+            }
+        }
+    }
+}
+
+/// True if the given location is within `file` and its span contains the byte `offset`.
+fn location_contains(location: &Location, file: FileId, offset: u32) -> bool {
+    // Spans end on their last character, so the end is inclusive here
+    location.file == file && location.span.start() <= offset && offset <= location.span.end()
 }
 
 /// These are the primitive type variants that we support adding methods to
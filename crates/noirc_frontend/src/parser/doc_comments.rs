@@ -0,0 +1,140 @@
+//! Doc comments are lexed as tokens so that the parser can attach them to the items they
+//! document, yet like any other comment they may be written anywhere. Those which aren't
+//! directly before something the parser documents are removed before parsing, as they would
+//! otherwise be parse errors.
+use crate::token::{Keyword, SpannedToken, Token, Tokens};
+
+/// The kind of body a token is within.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    /// A file, module or contract, whose items and inner doc comments are documented
+    Module,
+    /// An impl, whose methods are documented
+    Impl,
+    /// A struct, whose fields are documented
+    Struct,
+    /// Anything else delimited, such as a block or an argument list
+    Other,
+}
+
+struct Frame {
+    scope: Scope,
+    /// True until the first token in the body other than an inner doc comment
+    at_start: bool,
+    /// True if the next token starts an item, or a field within a struct
+    at_item_start: bool,
+    /// The keyword which the item being read was declared with
+    item_keyword: Option<Keyword>,
+    /// The number of generic argument lists open within a struct field, so that the commas
+    /// between the arguments aren't mistaken for the end of the field
+    generics_depth: usize,
+}
+
+impl Frame {
+    fn new(scope: Scope) -> Frame {
+        Frame { scope, at_start: true, at_item_start: true, item_keyword: None, generics_depth: 0 }
+    }
+}
+
+/// Removes the doc comments which don't document an item, struct field or module.
+pub(super) fn remove_misplaced_doc_comments(tokens: Tokens) -> Tokens {
+    let tokens = tokens.0;
+    let mut keep = Vec::with_capacity(tokens.len());
+    let mut frames = vec![Frame::new(Scope::Module)];
+
+    for (index, token) in tokens.iter().enumerate() {
+        let frame = frames.last_mut().expect("the outermost module is never closed");
+        match token.token() {
+            Token::InnerDocComment(_) => {
+                keep.push(frame.scope == Scope::Module && frame.at_start);
+                continue;
+            }
+            Token::OuterDocComment(_) => {
+                let next = tokens[index + 1..]
+                    .iter()
+                    .map(SpannedToken::token)
+                    .find(|token| !is_doc_comment(token));
+                let documents_item = frame.at_item_start
+                    && match (frame.scope, next) {
+                        (Scope::Struct, Some(Token::Ident(_))) => true,
+                        (Scope::Module | Scope::Impl, Some(next)) => {
+                            !matches!(next, Token::RightBrace | Token::EOF)
+                        }
+                        _ => false,
+                    };
+                keep.push(documents_item);
+                frame.at_start = false;
+                continue;
+            }
+            _ => keep.push(true),
+        }
+
+        frame.at_start = false;
+        frame.at_item_start = false;
+        match token.token() {
+            Token::LeftBrace => {
+                let scope = match (frame.scope, frame.item_keyword) {
+                    (Scope::Module, Some(Keyword::Mod | Keyword::Contract)) => Scope::Module,
+                    (Scope::Module, Some(Keyword::Struct)) => Scope::Struct,
+                    (Scope::Module, Some(Keyword::Impl)) => Scope::Impl,
+                    _ => Scope::Other,
+                };
+                frames.push(Frame::new(scope));
+            }
+            Token::LeftParen | Token::LeftBracket => frames.push(Frame::new(Scope::Other)),
+            Token::RightBrace | Token::RightParen | Token::RightBracket => {
+                if frames.len() > 1 {
+                    frames.pop();
+                }
+                // Functions, structs, impls and modules end with their closing brace
+                let frame = frames.last_mut().expect("the outermost module is never closed");
+                if token.token() == &Token::RightBrace
+                    && matches!(
+                        frame.item_keyword,
+                        Some(
+                            Keyword::Fn
+                                | Keyword::Struct
+                                | Keyword::Impl
+                                | Keyword::Mod
+                                | Keyword::Contract
+                        )
+                    )
+                {
+                    frame.at_item_start = true;
+                    frame.item_keyword = None;
+                }
+            }
+            Token::Semicolon => {
+                frame.at_item_start = true;
+                frame.item_keyword = None;
+            }
+            Token::Comma if frame.scope == Scope::Struct && frame.generics_depth == 0 => {
+                frame.at_item_start = true;
+            }
+            Token::Less if frame.scope == Scope::Struct => frame.generics_depth += 1,
+            Token::Greater if frame.scope == Scope::Struct => {
+                frame.generics_depth = frame.generics_depth.saturating_sub(1);
+            }
+            Token::ShiftRight if frame.scope == Scope::Struct => {
+                frame.generics_depth = frame.generics_depth.saturating_sub(2);
+            }
+            Token::Keyword(
+                keyword @ (Keyword::Fn
+                | Keyword::Struct
+                | Keyword::Impl
+                | Keyword::Mod
+                | Keyword::Contract
+                | Keyword::Use
+                | Keyword::Global),
+            ) if frame.item_keyword.is_none() => frame.item_keyword = Some(*keyword),
+            _ => (),
+        }
+    }
+
+    let tokens = tokens.into_iter().zip(keep).filter(|(_, keep)| *keep).map(|(token, _)| token);
+    Tokens(tokens.collect())
+}
+
+fn is_doc_comment(token: &Token) -> bool {
+    matches!(token, Token::OuterDocComment(_) | Token::InnerDocComment(_))
+}
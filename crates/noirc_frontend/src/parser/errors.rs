@@ -23,6 +23,10 @@ pub enum ParserErrorReason {
     InvalidArrayLengthExpression(Expression),
     #[error("Early 'return' is unsupported")]
     EarlyReturn,
+    #[error("Expected {expected} arguments and an optional message, but found {found} arguments")]
+    AssertArgumentCount { expected: usize, found: usize },
+    #[error("Assertion messages must be string literals")]
+    AssertMessageNotString,
 }
 
 /// Represents a parsing error, or a parsing error in the making.
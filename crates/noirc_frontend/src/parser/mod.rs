@@ -6,6 +6,7 @@
 //!
 //! This file is mostly helper functions and types for the parser. For the parser itself,
 //! see parser.rs. The definition of the abstract syntax tree can be found in the `ast` folder.
+mod doc_comments;
 mod errors;
 mod labels;
 #[allow(clippy::module_inception)]
@@ -16,9 +17,9 @@ use std::sync::atomic::{AtomicU32, Ordering};
 use crate::token::{Keyword, Token};
 use crate::{ast::ImportStatement, Expression, NoirStruct};
 use crate::{
-    BlockExpression, ExpressionKind, ForExpression, Ident, IndexExpression, LetStatement,
-    MethodCallExpression, NoirFunction, NoirImpl, Path, PathKind, Pattern, Recoverable, Statement,
-    UnresolvedType, UseTree,
+    BlockExpression, Documented, ExpressionKind, ForExpression, Ident, IndexExpression,
+    LetStatement, MethodCallExpression, NoirFunction, NoirImpl, Path, PathKind, Pattern,
+    Recoverable, Statement, UnresolvedType, UseTree,
 };
 
 use acvm::FieldElement;
@@ -221,13 +222,16 @@ pub struct ParsedModule {
     pub functions: Vec<NoirFunction>,
     pub types: Vec<NoirStruct>,
     pub impls: Vec<NoirImpl>,
-    pub globals: Vec<LetStatement>,
+    pub globals: Vec<Documented<LetStatement>>,
 
     /// Module declarations like `mod foo;`
-    pub module_decls: Vec<Ident>,
+    pub module_decls: Vec<Documented<Ident>>,
 
     /// Full submodules as in `mod foo { ... definitions ... }`
     pub submodules: Vec<SubModule>,
+
+    /// The text of each line of the `//!` doc comments at the start of this module
+    pub doc_comments: Vec<String>,
 }
 
 /// A submodule defined via `mod name { contents }` in some larger file.
@@ -237,6 +241,9 @@ pub struct SubModule {
     pub name: Ident,
     pub contents: ParsedModule,
     pub is_contract: bool,
+
+    /// The text of each line of the `///` doc comments before this module
+    pub doc_comments: Vec<String>,
 }
 
 impl ParsedModule {
@@ -256,7 +263,7 @@ impl ParsedModule {
         self.imports.extend(import_stmt.desugar(None));
     }
 
-    fn push_module_decl(&mut self, mod_name: Ident) {
+    fn push_module_decl(&mut self, mod_name: Documented<Ident>) {
         self.module_decls.push(mod_name);
     }
 
@@ -264,7 +271,7 @@ impl ParsedModule {
         self.submodules.push(submodule);
     }
 
-    fn push_global(&mut self, global: LetStatement) {
+    fn push_global(&mut self, global: Documented<LetStatement>) {
         self.globals.push(global);
     }
 }
@@ -459,7 +466,7 @@ impl std::fmt::Display for TopLevelStatement {
 impl std::fmt::Display for ParsedModule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for decl in &self.module_decls {
-            writeln!(f, "mod {decl};")?;
+            writeln!(f, "mod {};", decl.item)?;
         }
 
         for import in &self.imports {
@@ -467,7 +474,7 @@ impl std::fmt::Display for ParsedModule {
         }
 
         for global_const in &self.globals {
-            write!(f, "{global_const}")?;
+            write!(f, "{}", global_const.item)?;
         }
 
         for type_ in &self.types {
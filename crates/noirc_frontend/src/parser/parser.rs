@@ -24,19 +24,20 @@
 //! be limited to cases like the above `fn` example where it is clear we shouldn't back out of the
 //! current parser to try alternative parsers in a `choice` expression.
 use super::{
-    foldl_with_span, labels::ParsingRuleLabel, parameter_name_recovery, parameter_recovery,
-    parenthesized, then_commit, then_commit_ignore, top_level_statement_recovery, ExprParser,
-    ForRange, NoirParser, ParsedModule, ParserError, ParserErrorReason, Precedence, SubModule,
-    TopLevelStatement,
+    doc_comments::remove_misplaced_doc_comments, foldl_with_span, labels::ParsingRuleLabel,
+    parameter_name_recovery, parameter_recovery, parenthesized, then_commit, then_commit_ignore,
+    top_level_statement_recovery, ExprParser, ForRange, NoirParser, ParsedModule, ParserError,
+    ParserErrorReason, Precedence, SubModule, TopLevelStatement,
 };
 use crate::ast::{Expression, ExpressionKind, LetStatement, Statement, UnresolvedType};
 use crate::lexer::Lexer;
 use crate::parser::{force, ignore_then_commit, statement_recovery};
 use crate::token::{Attribute, Keyword, Token, TokenKind};
 use crate::{
-    BinaryOp, BinaryOpKind, BlockExpression, CompTime, ConstrainStatement, FunctionDefinition,
-    Ident, IfExpression, InfixExpression, LValue, Lambda, NoirFunction, NoirImpl, NoirStruct, Path,
-    PathKind, Pattern, Recoverable, UnaryOp, UnresolvedTypeExpression, UseTree, UseTreeKind,
+    BinaryOp, BinaryOpKind, BlockExpression, CompTime, ConstrainStatement, Documented,
+    FunctionDefinition, Ident, IfExpression, InfixExpression, LValue, Lambda, Literal,
+    NoirFunction, NoirImpl, NoirStruct, Path, PathKind, Pattern, Recoverable, UnaryOp,
+    UnresolvedTypeExpression, UseTree, UseTreeKind,
 };
 
 use chumsky::prelude::*;
@@ -54,6 +55,7 @@ pub fn parse_program(source_program: &str) -> (ParsedModule, Vec<CustomDiagnosti
     let (tokens, lexing_errors) = Lexer::lex(source_program);
     let mut errors = vecmap(lexing_errors, Into::into);
 
+    let tokens = remove_misplaced_doc_comments(tokens);
     let (module, parsing_errors) = program().parse_recovery_verbose(tokens);
     errors.extend(parsing_errors.into_iter().map(Into::into));
 
@@ -65,22 +67,38 @@ fn program() -> impl NoirParser<ParsedModule> {
     module().then_ignore(force(just(Token::EOF)))
 }
 
-/// module: top_level_statement module
-///       | %empty
+/// module: inner_doc_comments top_level_statements
+///
+/// top_level_statements: outer_doc_comments top_level_statement top_level_statements
+///                     | %empty
 fn module() -> impl NoirParser<ParsedModule> {
     recursive(|module_parser| {
-        empty()
-            .map(|_| ParsedModule::default())
-            .then(top_level_statement(module_parser).repeated())
-            .foldl(|mut program, statement| {
+        inner_doc_comments()
+            .map(|doc_comments| ParsedModule { doc_comments, ..Default::default() })
+            .then(outer_doc_comments().then(top_level_statement(module_parser)).repeated())
+            .foldl(|mut program, (doc_comments, statement)| {
+                // Doc comments on imports and impls aren't kept as there is nothing to document
                 match statement {
-                    TopLevelStatement::Function(f) => program.push_function(f),
-                    TopLevelStatement::Module(m) => program.push_module_decl(m),
+                    TopLevelStatement::Function(mut f) => {
+                        f.def.doc_comments = doc_comments;
+                        program.push_function(f);
+                    }
+                    TopLevelStatement::Module(m) => {
+                        program.push_module_decl(Documented::new(m, doc_comments));
+                    }
                     TopLevelStatement::Import(i) => program.push_import(i),
-                    TopLevelStatement::Struct(s) => program.push_type(s),
+                    TopLevelStatement::Struct(mut s) => {
+                        s.doc_comments = doc_comments;
+                        program.push_type(s);
+                    }
                     TopLevelStatement::Impl(i) => program.push_impl(i),
-                    TopLevelStatement::SubModule(s) => program.push_submodule(s),
-                    TopLevelStatement::Global(c) => program.push_global(c),
+                    TopLevelStatement::SubModule(mut s) => {
+                        s.doc_comments = doc_comments;
+                        program.push_submodule(s);
+                    }
+                    TopLevelStatement::Global(c) => {
+                        program.push_global(Documented::new(c, doc_comments));
+                    }
                     TopLevelStatement::Error => (),
                 }
                 program
@@ -131,7 +149,12 @@ fn submodule(module_parser: impl NoirParser<ParsedModule>) -> impl NoirParser<To
         .then(module_parser)
         .then_ignore(just(Token::RightBrace))
         .map(|(name, contents)| {
-            TopLevelStatement::SubModule(SubModule { name, contents, is_contract: false })
+            TopLevelStatement::SubModule(SubModule {
+                name,
+                contents,
+                is_contract: false,
+                doc_comments: Vec::new(),
+            })
         })
 }
 
@@ -143,7 +166,12 @@ fn contract(module_parser: impl NoirParser<ParsedModule>) -> impl NoirParser<Top
         .then(module_parser)
         .then_ignore(just(Token::RightBrace))
         .map(|(name, contents)| {
-            TopLevelStatement::SubModule(SubModule { name, contents, is_contract: true })
+            TopLevelStatement::SubModule(SubModule {
+                name,
+                contents,
+                is_contract: true,
+                doc_comments: Vec::new(),
+            })
         })
 }
 
@@ -179,6 +207,7 @@ fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
                     return_type,
                     return_visibility,
                     return_distinctness,
+                    doc_comments: Vec::new(),
                 }
                 .into()
             },
@@ -225,7 +254,7 @@ fn struct_definition() -> impl NoirParser<TopLevelStatement> {
 
     keyword(Struct).ignore_then(ident()).then(generics()).then(fields).map_with_span(
         |((name, generics), fields), span| {
-            TopLevelStatement::Struct(NoirStruct { name, generics, fields, span })
+            TopLevelStatement::Struct(NoirStruct::new(name, generics, fields, span))
         },
     )
 }
@@ -258,10 +287,34 @@ fn attribute() -> impl NoirParser<Attribute> {
     })
 }
 
-fn struct_fields() -> impl NoirParser<Vec<(Ident, UnresolvedType)>> {
-    ident()
+/// outer_doc_comments: '///' outer_doc_comments
+///                   | %empty
+fn outer_doc_comments() -> impl NoirParser<Vec<String>> {
+    token_kind(TokenKind::OuterDocComment)
+        .map(|token| match token {
+            Token::OuterDocComment(comment) => comment,
+            _ => unreachable!(),
+        })
+        .repeated()
+}
+
+/// inner_doc_comments: '//!' inner_doc_comments
+///                   | %empty
+fn inner_doc_comments() -> impl NoirParser<Vec<String>> {
+    token_kind(TokenKind::InnerDocComment)
+        .map(|token| match token {
+            Token::InnerDocComment(comment) => comment,
+            _ => unreachable!(),
+        })
+        .repeated()
+}
+
+fn struct_fields() -> impl NoirParser<Vec<Documented<(Ident, UnresolvedType)>>> {
+    outer_doc_comments()
+        .then(ident())
         .then_ignore(just(Token::Colon))
         .then(parse_type())
+        .map(|((doc_comments, name), typ)| Documented::new((name, typ), doc_comments))
         .separated_by(just(Token::Comma))
         .allow_trailing()
 }
@@ -324,13 +377,20 @@ fn implementation() -> impl NoirParser<TopLevelStatement> {
         .ignore_then(generics())
         .then(parse_type().map_with_span(|typ, span| (typ, span)))
         .then_ignore(just(Token::LeftBrace))
-        .then(function_definition(true).repeated())
+        .then(method_definition().repeated())
         .then_ignore(just(Token::RightBrace))
         .map(|((generics, (object_type, type_span)), methods)| {
             TopLevelStatement::Impl(NoirImpl { generics, object_type, type_span, methods })
         })
 }
 
+fn method_definition() -> impl NoirParser<NoirFunction> {
+    outer_doc_comments().then(function_definition(true)).map(|(doc_comments, mut method)| {
+        method.def.doc_comments = doc_comments;
+        method
+    })
+}
+
 fn block_expr<'a, P>(expr_parser: P) -> impl NoirParser<Expression> + 'a
 where
     P: ExprParser + 'a,
@@ -475,6 +535,7 @@ where
     choice((
         constrain(expr_parser.clone()),
         assertion(expr_parser.clone()),
+        assertion_eq(expr_parser.clone()),
         declaration(expr_parser.clone()),
         assignment(expr_parser.clone()),
         return_statement(expr_parser.clone()),
@@ -490,7 +551,7 @@ where
         keyword(Keyword::Constrain).labelled(ParsingRuleLabel::Statement),
         expr_parser,
     )
-    .map(|expr| Statement::Constrain(ConstrainStatement(expr)))
+    .map(|expr| Statement::Constrain(ConstrainStatement(expr, None)))
     .validate(|expr, span, emit| {
         emit(ParserError::with_reason(ParserErrorReason::ConstrainDeprecated, span));
         expr
@@ -501,9 +562,63 @@ fn assertion<'a, P>(expr_parser: P) -> impl NoirParser<Statement> + 'a
 where
     P: ExprParser + 'a,
 {
-    ignore_then_commit(keyword(Keyword::Assert), parenthesized(expr_parser))
+    ignore_then_commit(keyword(Keyword::Assert), parenthesized(expression_list(expr_parser)))
         .labelled(ParsingRuleLabel::Statement)
-        .map(|expr| Statement::Constrain(ConstrainStatement(expr)))
+        .validate(|arguments, span, emit| {
+            let (mut arguments, message) = assertion_arguments(arguments, 1, span, emit);
+            let condition = arguments.remove(0);
+            Statement::Constrain(ConstrainStatement(condition, message))
+        })
+}
+
+fn assertion_eq<'a, P>(expr_parser: P) -> impl NoirParser<Statement> + 'a
+where
+    P: ExprParser + 'a,
+{
+    ignore_then_commit(keyword(Keyword::AssertEq), parenthesized(expression_list(expr_parser)))
+        .labelled(ParsingRuleLabel::Statement)
+        .validate(|arguments, span, emit| {
+            let (mut arguments, message) = assertion_arguments(arguments, 2, span, emit);
+            let rhs = arguments.remove(1);
+            let lhs = arguments.remove(0);
+            let operator = Spanned::from(span, BinaryOpKind::Equal);
+            let condition = create_infix_expression(lhs, (operator, rhs));
+            Statement::Constrain(ConstrainStatement(condition, message))
+        })
+}
+
+/// Splits the arguments of an `assert` or `assert_eq` into the `expected` number of operands and
+/// an optional trailing message, which must be a string literal. Missing operands are replaced
+/// with error expressions so that parsing can continue.
+fn assertion_arguments(
+    mut arguments: Vec<Expression>,
+    expected: usize,
+    span: Span,
+    emit: &mut dyn FnMut(ParserError),
+) -> (Vec<Expression>, Option<String>) {
+    if arguments.len() != expected && arguments.len() != expected + 1 {
+        let reason = ParserErrorReason::AssertArgumentCount { expected, found: arguments.len() };
+        emit(ParserError::with_reason(reason, span));
+    }
+
+    let message = if arguments.len() > expected {
+        let message = arguments.remove(expected);
+        match message.kind {
+            ExpressionKind::Literal(Literal::Str(message)) => Some(message),
+            _ => {
+                emit(ParserError::with_reason(
+                    ParserErrorReason::AssertMessageNotString,
+                    message.span,
+                ));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    arguments.resize(expected, Expression::error(span));
+    (arguments, message)
 }
 
 fn declaration<'a, P>(expr_parser: P) -> impl NoirParser<Statement> + 'a
@@ -1105,6 +1220,7 @@ mod test {
         if !lexer_errors.is_empty() {
             return Err(vecmap(lexer_errors, Into::into));
         }
+        let tokens = remove_misplaced_doc_comments(tokens);
         parser
             .then_ignore(just(Token::EOF))
             .parse(tokens)
@@ -1116,6 +1232,7 @@ mod test {
         P: NoirParser<T>,
     {
         let (tokens, lexer_errors) = Lexer::lex(program);
+        let tokens = remove_misplaced_doc_comments(tokens);
         let (opt, errs) = parser.then_ignore(force(just(Token::EOF))).parse_recovery(tokens);
 
         let mut errors = vecmap(lexer_errors, Into::into);
@@ -1365,6 +1482,42 @@ mod test {
                 "assert(x + x ^ x == y | m)",
             ],
         );
+
+        match parse_with(assertion(expression()), "assert(x == y, \"x must equal y\")").unwrap() {
+            Statement::Constrain(ConstrainStatement(_, message)) => {
+                assert_eq!(message.as_deref(), Some("x must equal y"));
+            }
+            _ => unreachable!(),
+        }
+
+        parse_all_failing(
+            assertion(expression()),
+            vec!["assert()", "assert(x == y, 5)", "assert(x == y, \"a\", \"b\")"],
+        );
+    }
+
+    #[test]
+    fn parse_assert_eq() {
+        let statement = parse_with(assertion_eq(expression()), "assert_eq(x + 1, y)").unwrap();
+        match statement {
+            Statement::Constrain(ConstrainStatement(condition, None)) => {
+                assert_eq!(condition.to_string(), "((x + 1) == y)");
+            }
+            _ => unreachable!(),
+        }
+
+        let src = "assert_eq(x, y, \"x must equal y\")";
+        match parse_with(assertion_eq(expression()), src).unwrap() {
+            Statement::Constrain(ConstrainStatement(_, message)) => {
+                assert_eq!(message.as_deref(), Some("x must equal y"));
+            }
+            _ => unreachable!(),
+        }
+
+        parse_all_failing(
+            assertion_eq(expression()),
+            vec!["assert_eq(x)", "assert_eq(x, y, z)", "assert_eq(x == y)"],
+        );
     }
 
     #[test]
@@ -1420,6 +1573,83 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_doc_comments() {
+        let src = "
+            //! The module
+            /// A global
+            global N: Field = 1;
+            /// A struct
+            struct Foo {
+                /// A field
+                x: Field,
+            }
+            impl Foo {
+                /// A method
+                fn get(self) -> Field {
+                    /// Not attached to anything
+                    self.x
+                }
+            }
+            /// A function
+            /// on two lines
+            fn main() {}
+        ";
+        let module = parse_with(module(), src).unwrap();
+
+        assert_eq!(module.doc_comments, vec![" The module"]);
+        assert_eq!(module.globals[0].doc_comments, vec![" A global"]);
+        assert_eq!(module.types[0].doc_comments, vec![" A struct"]);
+        assert_eq!(module.types[0].fields[0].doc_comments, vec![" A field"]);
+        assert_eq!(module.impls[0].methods[0].def.doc_comments, vec![" A method"]);
+        assert_eq!(module.functions[0].def.doc_comments, vec![" A function", " on two lines"]);
+    }
+
+    #[test]
+    fn parse_misplaced_doc_comments() {
+        let src = "
+            //! The module
+            struct Foo {
+                x: Field,
+                /// After the last field
+            }
+            impl Foo {
+                fn get(self) -> Field {
+                    self.x
+                    /// Before the end of a block
+                }
+                /// After the last method
+            }
+            //! Not at the start of the module
+            #[test]
+            /// Between an attribute and its function
+            fn test_foo() {
+                let foo = Foo {
+                    /// In a constructor
+                    x: 1,
+                };
+                assert(foo.get(/// In an argument list
+                    ) == 1);
+            }
+            mod bar {
+                /// A function
+                fn baz() {}
+                /// At the end of a module
+            }
+            /// At the end of the file
+        ";
+        let (module, errors) = parse_program(src);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        assert_eq!(module.doc_comments, vec![" The module"]);
+        assert!(module.types[0].fields.iter().all(|field| field.doc_comments.is_empty()));
+        assert!(module.functions[0].def.doc_comments.is_empty());
+        assert_eq!(
+            module.submodules[0].contents.functions[0].def.doc_comments,
+            vec![" A function"]
+        );
+    }
+
     #[test]
     fn parse_parenthesized_expression() {
         parse_all(atom(expression()), vec!["(0)", "(x+a)", "({(({{({(nested)})}}))})"]);